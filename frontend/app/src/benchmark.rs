@@ -99,6 +99,7 @@ impl Component for Benchmark {
                     true
                 }
             }
+            Msg::ReceivedSimAgentResponse(_) => false,
         }
     }
 
@@ -77,6 +77,7 @@ pub fn documentation(props: &DocumentationProps) -> Html {
               <li><code>{ "angular_velocity() → f64" }</code>{ ": Get the current angular velocity in radians/s." }</li>
               <li><code>{ "health() → f64" }</code>{ ": Current health." }</li>
               <li><code>{ "fuel() → f64" }</code>{ ": Current fuel (delta-v)." }</li>
+              <li><code>{ "ammo() → f64" }</code>{ ": Rounds of gun ammunition left (infinity if unlimited)." }</li>
               <li><code>{ "accelerate(acceleration: Vec2)" }</code>{ ": Accelerate the ship. Units are m/s²." }</li>
               <li><code>{ "turn(speed: f64)" }</code>{ ": Rotate the ship. Unit is radians/s." }</li>
               <li><code>{ "torque(acceleration: f64)" }</code>{ ": Angular acceleration. Unit is radians/s²." }</li>
@@ -113,6 +114,11 @@ pub fn documentation(props: &DocumentationProps) -> Html {
               <li><code>{ "EcmMode::None" }</code>{ ": No ECM, radar will operate normally." }</li>
               <li><code>{ "EcmMode::Noise" }</code>{ ": Decrease the enemy radar's signal to noise ratio, making it more difficult to detect targets and reducing accuracy of returned contacts." }</li>
               <li><code>{ "select_radar(index: usize)" }</code>{ ": Select the radar to control with subsequent API calls. Cruisers have two radars." }</li>
+              <li><code>{ "esm() → Option<EsmContact>" }</code>{ ": Find the strongest enemy radar illuminating this ship." }</li>
+              <li><code>{ "esm_contacts() → Vec<EsmContact>" }</code>{ ": Find up to four enemy radars illuminating this ship, strongest first." }</li>
+              <li><code>{ "struct EsmContact { bearing: f64, power: f64, jammer: bool }" }</code></li>
+              <li><code>{ "esm_count() -> usize" }</code>{ ": Number of enemy radars illuminating this ship." }</li>
+              <li><code>{ "illuminated_contact() → Option<ScanResult>" }</code>{ ": Missiles and torpedoes only. Get the target the launching ship's radar is tracking, if it is within this radar's beam (semi-active homing)." }</li>
             </ul>
 
             <h2>{ "Radio" }</h2>
@@ -136,10 +142,45 @@ pub fn documentation(props: &DocumentationProps) -> Html {
                   <li><code>{ "Ability::Boost" }</code>{ ": Fighter and missile only. Applies a 100 m/s² forward acceleration for 2s. Reloads in 10s." }</li>
                   <li><code>{ "Ability::Decoy" }</code>{ ": Torpedo only. Mimics the radar signature of a Cruiser for 0.5s. Reloads in 10s." }</li>
                   <li><code>{ "Ability::Shield" }</code>{ ": Cruiser only. Deflects damage for 1s. Reloads in 5s." }</li>
+                  <li><code>{ "Ability::Chaff" }</code>{ ": Fighter, frigate and cruiser. Drops chaff which shows up strongly on enemy radar as your ship's class for 5s. Each activation drops one. Reloads in 1s. Limited supply (4/10/20)." }</li>
+                  <li><code>{ "Ability::Flare" }</code>{ ": Fighter, frigate and cruiser. Drops a flare which distracts missile and torpedo seekers for 3s. Each activation drops one. Reloads in 1s. Limited supply (4/10/20)." }</li>
                 </ul>
               </li>
             </ul>
 
+            <h2>{ "Energy" }</h2>
+            <p>{ "Fighters, frigates and cruisers have a reactor and a battery. Radars (double while jamming), reloading guns, and the Boost and Shield abilities draw energy. Each subsystem is guaranteed its share of the reactor output and any shortfall comes out of the battery. When the battery runs dry an underpowered radar loses range, guns reload more slowly, and abilities shut off." }</p>
+            <ul>
+              <li><code>{ "energy() → f64" }</code>{ ": Energy stored in the battery (J)." }</li>
+              <li><code>{ "set_power_allocation(weapons: f64, sensors: f64, engines: f64)" }</code>{ ": Set the relative share of reactor output for each subsystem. Defaults to an even split." }</li>
+            </ul>
+
+            <h2>{ "Capture Zones" }</h2>
+            <p>{ "Some scenarios are won by holding capture zones. A team captures a zone by being the only team with combat ships inside it. Each zone earns its holder one point per second unless it is contested." }</p>
+            <ul>
+              <li><code>{ "capture_zones() → Vec<CaptureZone>" }</code>{ ": Position, radius and control state (Neutral, Friendly, Enemy or Contested) of each zone." }</li>
+              <li><code>{ "score() → f64" }</code>{ ", " }<code>{ "enemy_score() → f64" }</code>{ ": Control scores of our team and the leading enemy team." }</li>
+            </ul>
+
+            <h2>{ "Nebulae" }</h2>
+            <p>{ "Some scenarios contain nebulae, drawn as translucent fields. Radar contacts with a ship inside a nebula are less accurate, radio signals to or from a ship inside a nebula are much weaker, and ships inside a nebula are slowed by drag." }</p>
+            <ul>
+              <li><code>{ "nebulae() → Vec<Nebula>" }</code>{ ": Circles and convex polygons covered by nebulae." }</li>
+              <li><code>{ "nebula.contains(point: Vec2) → bool" }</code>{ ": Whether a point is inside a nebula." }</li>
+            </ul>
+
+            <h2>{ "Obstacles" }</h2>
+            <p>{ "Some scenarios contain static obstacles. They stop ships and bullets, and they block radar, radio and beams, so ships behind them can't be seen or hit." }</p>
+
+            <h2>{ "Logistics" }</h2>
+            <p>{ "In some longer scenarios, such as fleet_logistics, fighters carry limited fuel (20000 m/s) and gun ammunition (1500 rounds). A fighter holding station within 500m of a friendly frigate or cruiser, at less than 20 m/s relative to it, refills empty stores in 10s." }</p>
+
+            <h2>{ "Arena Boundary" }</h2>
+            <p>{ "Some scenarios surround the arena with a circular boundary centered on the origin that shrinks over time. Ships outside it lose health every second." }</p>
+            <ul>
+              <li><code>{ "boundary_radius() → f64" }</code>{ ", " }<code>{ "next_boundary_radius() → f64" }</code>{ ": Current radius of the boundary and the radius it will shrink to next. Infinity if the scenario has no boundary." }</li>
+            </ul>
+
             <h2>{ "Scalar Math" }</h2>
             <ul>
               <li><code>{ "PI, TAU" }</code>{ ": Constants."}</li>
@@ -187,6 +228,8 @@ pub fn documentation(props: &DocumentationProps) -> Html {
               <li><code>{ "current_time() → f64" }</code>{ ": Returns the number of seconds elapsed since the simulation started." }</li>
               <li><code>{ "angle_diff(a: f64, b: f64) → f64" }</code>{ ": Returns the shortest (possibly negative) distance between two angles." }</li>
               <li><code>{ "rand(low: f64, high: f64) → f64" }</code>{ ": Get a random number." }</li>
+              <li><code>{ "topology() → Topology" }</code>{ ": Returns whether the world is Walled or Toroidal. In a toroidal world ships and bullets wrap around the edges." }</li>
+              <li><code>{ "wrap_displacement(dp: Vec2) → Vec2" }</code>{ ": Returns the shortest displacement equivalent to dp, which may cross the edge of a toroidal world." }</li>
               <li><code>{ "target() → Vec2" }</code>{ ": Used in some scenarios, returns the position of the target." }</li>
               <li><code>{ "target_velocity() → Vec2" }</code>{ ": Used in some scenarios, returns the velocity of the target." }</li>
               <li><code>{ "seed() → u128" }</code>{ ": Returns a seed useful for initializing a random number generator." }</li>
@@ -201,12 +244,15 @@ pub fn documentation(props: &DocumentationProps) -> Html {
             </ul>
 
             <h2>{ "Ship Classes" }</h2>
+            <p>{ "Ramming an enemy ship or an asteroid damages both, in proportion to the kinetic energy of the impact." }</p>
+            <p>{ "Destroyed big asteroids break up into several smaller asteroids that keep drifting along the same path." }</p>
             <ul>
               <li>{ "Fighter: Small, fast, and lightly armored. One forward-facing gun and one missile launcher. "}</li>
-              <li>{ "Frigate: Medium size with heavy armor. One forward-facing high-velocity gun, two turreted guns, and one missile launcher. "}</li>
-              <li>{ "Cruiser: Large, slow, and heavily armored. One turreted heavy cannon, two missile launchers, and one torpedo launcher. "}</li>
+              <li>{ "Frigate: Medium size with heavy armor. One forward-facing high-velocity gun, two turreted guns (lasers on the point defense variant), one missile launcher, and a mine launcher holding 6 mines. "}</li>
+              <li>{ "Cruiser: Large, slow, and heavily armored. One turreted heavy cannon, two missile launchers, one torpedo launcher, and a mine launcher holding 12 mines. "}</li>
               <li>{ "Missile: Highly maneuverable but unarmored. Explodes on contact or after an " }<code>{ "explode()" }</code>{ " call." }</li>
               <li>{ "Torpedo: Better armor, larger warhead, but less maneuverable than a missile. Explodes on contact or after an " }<code>{ "explode()" }</code>{ " call." }</li>
+              <li>{ "Mine: Stationary and hard to see on radar. Arms 3s after launch and explodes in every direction when an enemy comes within 100m." }</li>
             </ul>
         </div>
     };
@@ -377,6 +377,10 @@ impl Component for EditorWindow {
             .props()
             .on_editor_action
             .reform(|_| "oort-replay-paused".to_string());
+        let test_cb = context
+            .props()
+            .on_editor_action
+            .reform(|_| "oort-test".to_string());
 
         let cmd_or_ctrl = cmd_or_ctrl();
 
@@ -446,6 +450,11 @@ impl Component for EditorWindow {
                         class="material-symbols-outlined"
                         title={format!("Replay paused ({cmd_or_ctrl}-Alt-Enter)")}
                     >{ "autopause" }</span></div>
+                    <div class="test_button"><span
+                        onclick={test_cb}
+                        class="material-symbols-outlined"
+                        title="Run tests"
+                    >{ "science" }</span></div>
                     <form>
                         <div class="drop_target display_none" ref={self.drop_target_ref.clone()}>
                             <span for="file" ondrop={context.link().callback(Msg::Drop)}>
@@ -541,6 +550,8 @@ impl Component for EditorWindow {
                     ),
                 );
 
+                add_action("oort-test", "Run tests", None);
+
                 add_action("oort-restore-initial-code", "Restore initial code", None);
 
                 add_action("oort-load-solution", "Load solution", None);
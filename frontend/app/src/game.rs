@@ -4,7 +4,7 @@ use crate::documentation::Documentation;
 use crate::editor_window::{EditorAction, EditorWindow};
 use crate::gtag;
 use crate::js;
-use crate::leaderboard::Leaderboard;
+use crate::leaderboard::{format_gas, Leaderboard};
 use crate::leaderboard_window::LeaderboardWindow;
 use crate::query_params;
 use crate::seed_window::SeedWindow;
@@ -17,10 +17,12 @@ use crate::welcome::Welcome;
 use monaco::yew::CodeEditorLink;
 use oort_proto::{LeaderboardSubmission, Telemetry};
 use oort_simulation_worker::SimAgent;
+use oort_simulator::harness::TestResult;
 use oort_simulator::scenario::{self, Status, MAX_TICKS};
 use oort_simulator::simulation;
 use oort_simulator::simulation::Code;
 use oort_simulator::snapshot::Snapshot;
+use oort_simulator::stats::{CombatStats, MatchStats};
 use rand::RngExt;
 use regex::Regex;
 use reqwasm::http::Request;
@@ -49,6 +51,7 @@ pub enum Msg {
     Start,
     SimulationFinished(Snapshot),
     ReceivedBackgroundSimAgentResponse(oort_simulation_worker::Response, u32),
+    ReceivedTestAgentResponse(oort_simulation_worker::Response),
     EditorAction { team: usize, action: String },
     ShowFeedback,
     DismissOverlay,
@@ -78,12 +81,14 @@ pub enum ExecutionMode {
     Initial,
     Run,
     Replay { paused: bool },
+    Test,
 }
 
 pub struct Game {
     background_agents: Vec<Box<dyn Bridge<SimAgent>>>,
     background_snapshots: Vec<(u32, Snapshot)>,
     background_nonce: u32,
+    test_agent: Option<Box<dyn Bridge<SimAgent>>>,
     overlay: Option<Overlay>,
     overlay_ref: NodeRef,
     simulation_canvas_ref: NodeRef,
@@ -106,6 +111,7 @@ pub struct Team {
     running_source_code: Code,
     running_compiled_code: Code,
     current_compiler_decorations: js_sys::Array,
+    current_test_decorations: js_sys::Array,
 }
 
 #[derive(Properties, PartialEq, Eq, Debug)]
@@ -139,6 +145,7 @@ impl Component for Game {
             background_agents: Vec::new(),
             background_snapshots: Vec::new(),
             background_nonce: 0,
+            test_agent: None,
             overlay: None,
             overlay_ref: NodeRef::default(),
             simulation_canvas_ref: NodeRef::default(),
@@ -229,6 +236,17 @@ impl Component for Game {
                 self.start_compile(context, ExecutionMode::Replay { paused: true });
                 true
             }
+            Msg::EditorAction {
+                team: _,
+                ref action,
+            } if action == "oort-test" => {
+                self.save_current_code(context, &context.props().scenario, None);
+                for team in self.teams.iter_mut() {
+                    team.running_source_code = team.get_editor_code();
+                }
+                self.start_compile(context, ExecutionMode::Test);
+                true
+            }
             Msg::EditorAction { team, ref action } if action == "oort-restore-initial-code" => {
                 let mut code = scenario::load(&context.props().scenario)
                     .initial_code()
@@ -312,6 +330,7 @@ impl Component for Game {
                                 code_size: crate::code_size::calculate(&code_to_string(&code)),
                                 success: summary.failed_seeds.is_empty(),
                                 time: summary.average_time,
+                                gas: summary.average_gas,
                             });
                             if summary.failed_seeds.is_empty() {
                                 if let Some(average_time) = summary.average_time {
@@ -329,6 +348,18 @@ impl Component for Game {
                     false
                 }
             }
+            Msg::ReceivedBackgroundSimAgentResponse(
+                oort_simulation_worker::Response::TestResults { .. },
+                _,
+            ) => false,
+            Msg::ReceivedTestAgentResponse(oort_simulation_worker::Response::TestResults {
+                results,
+            }) => {
+                self.test_agent = None;
+                self.on_tests_finished(results);
+                true
+            }
+            Msg::ReceivedTestAgentResponse(_) => false,
             Msg::ShowFeedback => {
                 self.overlay = Some(Overlay::Feedback);
                 true
@@ -378,7 +409,9 @@ impl Component for Game {
                     .filter_map(|x| x.as_ref().err())
                     .cloned()
                     .collect();
-                if errors.is_empty() {
+                if errors.is_empty() && execution_mode == ExecutionMode::Test {
+                    self.run_tests(context);
+                } else if errors.is_empty() {
                     // If no errors, start running simulation
                     services::send_telemetry(Telemetry::StartScenario {
                         scenario_name: context.props().scenario.clone(),
@@ -665,6 +698,7 @@ struct BackgroundSimSummary {
     victory_count: usize,
     failed_seeds: Vec<u32>,
     average_time: Option<f64>,
+    average_gas: Option<u64>,
     best_seed: Option<u32>,
     worst_seed: Option<u32>,
     scenario_name: String,
@@ -837,6 +871,18 @@ impl Game {
         } else {
             None
         };
+        let average_gas: Option<u64> = if victory_count > 0 {
+            Some(
+                self.background_snapshots
+                    .iter()
+                    .filter(|(_, snapshot)| is_victory(&snapshot.status))
+                    .map(|(_, snapshot)| snapshot.gas_used)
+                    .sum::<u64>()
+                    / victory_count as u64,
+            )
+        } else {
+            None
+        };
 
         let mut victory_seeds_by_time: Vec<_> = self
             .background_snapshots
@@ -856,6 +902,7 @@ impl Game {
             victory_count,
             failed_seeds,
             average_time,
+            average_gas,
             best_seed,
             worst_seed,
             scenario_name: scenario_name.to_owned(),
@@ -868,6 +915,12 @@ impl Game {
         } else {
             0.0
         };
+        let stats_table = self
+            .last_snapshot
+            .as_ref()
+            .and_then(|snapshot| snapshot.stats.as_ref())
+            .map(make_stats_table)
+            .unwrap_or_default();
         let source_code = code_to_string(&self.player_team().running_source_code);
         let code_size = crate::code_size::calculate(&source_code);
         let leaderboard_eligible = self.leaderboard_eligible();
@@ -1005,6 +1058,7 @@ impl Game {
                     code_size,
                     time: summary.average_time.unwrap(),
                     rescored_version: None,
+                    gas: summary.average_gas,
                 });
             html! {
                 <>
@@ -1019,6 +1073,13 @@ impl Game {
                             }
                         }
                     </span>
+                    {
+                        if let Some(average_gas) = summary.average_gas {
+                            html! { <><br /><span>{ "Average CPU: " }{ format_gas(average_gas) }</span></> }
+                        } else {
+                            html! {}
+                        }
+                    }
                     { failures }
                     { best_and_worst_seeds }
                     <br />
@@ -1042,6 +1103,7 @@ impl Game {
                 <h1>{ "Mission Complete" }</h1>
                 { "Time: " }{ format!("{score_time:.3}") }{ " seconds" }<br/>
                 { "Code size: " }{ code_size }{ " bytes" }<br/><br/>
+                { stats_table }<br/>
                 { background_status }<br/><br/>
                 <br/><br/>
             </div>
@@ -1144,7 +1206,7 @@ impl Game {
         // If replaying, reuse previous seed if it exists
         // instead of using a newly generated seed
         let seed = match execution_mode {
-            ExecutionMode::Initial | ExecutionMode::Run => {
+            ExecutionMode::Initial | ExecutionMode::Run | ExecutionMode::Test => {
                 self.configured_seed(context).unwrap_or(rand_seed)
             }
             ExecutionMode::Replay { .. } => self
@@ -1172,6 +1234,59 @@ impl Game {
         self.background_nonce = 0;
     }
 
+    /// Runs the player's `oort_test!` tests in a worker
+    /// NOTE: Assumes no compiler errors
+    fn run_tests(&mut self, context: &Context<Self>) {
+        let code = self.player_team().running_compiled_code.clone();
+        if code == Code::None {
+            self.compiler_errors = Some("No code to test".to_string());
+            js::golden_layout::select_tab("compiler_output");
+            return;
+        }
+
+        let cb = {
+            let link = context.link().clone();
+            move |e| link.send_message(Msg::ReceivedTestAgentResponse(e))
+        };
+        let mut test_agent = SimAgent::bridge(Rc::new(cb));
+        test_agent.send(oort_simulation_worker::Request::RunTests { code });
+        self.test_agent = Some(test_agent);
+        self.compiler_errors = Some("Running tests...".to_string());
+        js::golden_layout::select_tab("compiler_output");
+    }
+
+    /// Marks each test in the editor and summarizes the results in the compiler output
+    fn on_tests_finished(&mut self, results: Result<Vec<TestResult>, String>) {
+        let results = match results {
+            Ok(results) => results,
+            Err(e) => {
+                self.team_mut(0).display_test_results(&[]);
+                self.compiler_errors = Some(format!("Failed to run tests: {e}"));
+                return;
+            }
+        };
+        self.team_mut(0).display_test_results(&results);
+
+        let mut summary = if results.is_empty() {
+            "No tests found. Declare tests with oort_test!(name, |t| { ... }).\n".to_string()
+        } else {
+            let passed = results.iter().filter(|r| r.passed).count();
+            format!("{passed}/{} tests passed\n", results.len())
+        };
+        for result in results.iter() {
+            if result.passed {
+                summary.push_str(&format!("PASS {} ({} ticks)\n", result.name, result.ticks));
+            } else {
+                summary.push_str(&format!(
+                    "FAIL {}: {}\n",
+                    result.name,
+                    result.message.as_deref().unwrap_or("unknown error")
+                ));
+            }
+        }
+        self.compiler_errors = Some(summary);
+    }
+
     pub fn change_scenario(&mut self, context: &Context<Self>, scenario_name: &str, run: bool) {
         let codes = crate::codestorage::load(&context.props().scenario);
         let scenario = oort_simulator::scenario::load(&context.props().scenario);
@@ -1305,6 +1420,7 @@ impl Team {
             initial_compiled_code: Code::None,
             running_compiled_code: Code::None,
             current_compiler_decorations: js_sys::Array::new(),
+            current_test_decorations: js_sys::Array::new(),
         }
     }
 
@@ -1347,34 +1463,13 @@ impl Team {
     }
 
     pub fn display_compiler_errors(&mut self, errors: &[CompilerError]) {
-        use monaco::sys::{
-            editor::IModelDecorationOptions, editor::IModelDeltaDecoration, IMarkdownString, Range,
-        };
-        let decorations: Vec<IModelDeltaDecoration> = errors
-            .iter()
-            .map(|error| {
-                let decoration: IModelDeltaDecoration = empty().into();
-                decoration.set_range(
-                    &Range::new(error.line as f64, 1.0, error.line as f64, 1.0).unchecked_into(),
-                );
-                let options: IModelDecorationOptions = empty().into();
-                options.set_is_whole_line(Some(true));
-                options.set_class_name("errorDecoration".into());
-                let hover_message: IMarkdownString = empty().into();
-                js_sys::Reflect::set(
-                    &hover_message,
-                    &JsValue::from_str("value"),
-                    &JsValue::from_str(&error.msg),
-                )
-                .unwrap();
-                options.set_hover_message(&hover_message);
-                decoration.set_options(&options);
-                decoration
-            })
-            .collect();
         let decorations_jsarray = js_sys::Array::new();
-        for decoration in decorations {
-            decorations_jsarray.push(&decoration);
+        for error in errors {
+            decorations_jsarray.push(&make_line_decoration(
+                error.line,
+                "errorDecoration",
+                &error.msg,
+            ));
         }
         self.current_compiler_decorations = self
             .editor_link
@@ -1385,6 +1480,100 @@ impl Team {
             })
             .unwrap();
     }
+
+    /// Highlights each `oort_test!` declaration according to its result
+    pub fn display_test_results(&mut self, results: &[TestResult]) {
+        let text = self.get_editor_text();
+        let decorations_jsarray = js_sys::Array::new();
+        for result in results {
+            let Some(line) = find_test_line(&text, &result.name) else {
+                continue;
+            };
+            if result.passed {
+                decorations_jsarray.push(&make_line_decoration(
+                    line,
+                    "testPassedDecoration",
+                    "Test passed",
+                ));
+            } else {
+                decorations_jsarray.push(&make_line_decoration(
+                    line,
+                    "errorDecoration",
+                    result.message.as_deref().unwrap_or("Test failed"),
+                ));
+            }
+        }
+        self.current_test_decorations = self
+            .editor_link
+            .with_editor(|editor| {
+                editor
+                    .as_ref()
+                    .delta_decorations(&self.current_test_decorations, &decorations_jsarray)
+            })
+            .unwrap();
+    }
+}
+
+fn make_stats_table(stats: &MatchStats) -> Html {
+    let teams = stats.teams();
+    let row = |name: &str, f: &dyn Fn(&CombatStats) -> String| {
+        html! {
+            <tr>
+                <td>{ name }</td>
+                { teams.values().map(|x| html! { <td>{ f(x) }</td> }).collect::<Html>() }
+            </tr>
+        }
+    };
+    html! {
+        <table>
+            <tr>
+                <th>{ "Team" }</th>
+                { teams.keys().map(|team| html! { <th>{ team }</th> }).collect::<Html>() }
+            </tr>
+            { row("Shots fired", &|x| x.shots_fired.to_string()) }
+            { row("Hits", &|x| x.hits.to_string()) }
+            { row("Warhead hits", &|x| x.warhead_hits.to_string()) }
+            { row("Damage dealt", &|x| format!("{:.0}", x.damage_dealt)) }
+            { row("Damage taken", &|x| format!("{:.0}", x.damage_taken)) }
+            { row("Kills", &|x| x.kills.to_string()) }
+            { row("Missiles launched", &|x| x.missiles_launched.to_string()) }
+            { row("Missiles intercepted", &|x| x.missiles_intercepted.to_string()) }
+            { row("Distance travelled", &|x| format!("{:.1} km", x.distance_travelled / 1e3)) }
+        </table>
+    }
+}
+
+fn make_line_decoration(
+    line: usize,
+    class_name: &str,
+    msg: &str,
+) -> monaco::sys::editor::IModelDeltaDecoration {
+    use monaco::sys::{
+        editor::IModelDecorationOptions, editor::IModelDeltaDecoration, IMarkdownString, Range,
+    };
+    let decoration: IModelDeltaDecoration = empty().into();
+    decoration.set_range(&Range::new(line as f64, 1.0, line as f64, 1.0).unchecked_into());
+    let options: IModelDecorationOptions = empty().into();
+    options.set_is_whole_line(Some(true));
+    options.set_class_name(class_name.into());
+    let hover_message: IMarkdownString = empty().into();
+    js_sys::Reflect::set(
+        &hover_message,
+        &JsValue::from_str("value"),
+        &JsValue::from_str(msg),
+    )
+    .unwrap();
+    options.set_hover_message(&hover_message);
+    decoration.set_options(&options);
+    decoration
+}
+
+/// Returns the 1-based line where the named test is declared
+fn find_test_line(text: &str, name: &str) -> Option<usize> {
+    let re = Regex::new(&format!(r"oort_test!\s*\(\s*{}\b", regex::escape(name))).unwrap();
+    text.lines()
+        .position(|line| re.is_match(line))
+        .map(|index| index + 1)
 }
 
 pub fn code_to_string(code: &Code) -> String {
@@ -1,7 +1,7 @@
 use crate::services;
 use crate::userid;
 use oort_proto::LeaderboardSubmission;
-use oort_proto::{LeaderboardData, TimeLeaderboardRow};
+use oort_proto::{LeaderboardCategory, LeaderboardData, TimeLeaderboardRow};
 use oort_simulator::scenario;
 use yew::prelude::*;
 
@@ -9,6 +9,7 @@ use yew::prelude::*;
 pub enum Msg {
     SendRequest,
     ReceiveResponse(Result<LeaderboardData, anyhow::Error>),
+    SelectCategory(LeaderboardCategory),
 }
 
 #[derive(Properties, Clone, PartialEq)]
@@ -22,6 +23,7 @@ pub struct Leaderboard {
     data: Option<LeaderboardData>,
     error: Option<String>,
     fetching: bool,
+    category: LeaderboardCategory,
 }
 
 impl Component for Leaderboard {
@@ -34,6 +36,7 @@ impl Component for Leaderboard {
             data: None,
             error: None,
             fetching: false,
+            category: LeaderboardCategory::Time,
         }
     }
 
@@ -51,7 +54,11 @@ impl Component for Leaderboard {
                 if let Some(submission) = context.props().submission.as_ref() {
                     services::post_leaderboard(submission.clone(), callback);
                 } else {
-                    services::get_leaderboard(&context.props().scenario_name, callback);
+                    services::get_leaderboard(
+                        &context.props().scenario_name,
+                        self.category,
+                        callback,
+                    );
                 }
                 self.fetching = true;
                 true
@@ -66,15 +73,38 @@ impl Component for Leaderboard {
                 self.fetching = false;
                 true
             }
+            SelectCategory(category) => {
+                if category == self.category {
+                    return false;
+                }
+                self.category = category;
+                // A submission response already includes every board.
+                if context.props().submission.is_none() {
+                    self.data = None;
+                    self.error = None;
+                    context.link().send_message(Msg::SendRequest);
+                }
+                true
+            }
         }
     }
 
     fn view(&self, context: &yew::Context<Self>) -> Html {
         let current_version = oort_version::version();
+        let category = self.category;
+        let tabs = LeaderboardCategory::ALL
+            .iter()
+            .map(|&c| {
+                let onclick = context.link().callback(move |_| Msg::SelectCategory(c));
+                let class = (c == category).then_some("selected");
+                html! { <button class={classes!(class)} {onclick}>{ c.title() }</button> }
+            })
+            .collect::<Html>();
+        let tabs = html! { <div class="leaderboard-tabs">{ tabs }</div> };
         if let Some(ref error) = self.error {
-            html! { <p>{ error.clone() }</p> }
+            html! { <>{ tabs }<p>{ error.clone() }</p></> }
         } else if self.fetching {
-            html! { <p>{ "Fetching leaderboard..." }</p> }
+            html! { <>{ tabs }<p>{ "Fetching leaderboard..." }</p></> }
         } else if let Some(ref data) = self.data {
             let userid = userid::get_userid();
             let is_tournament = scenario::load_safe(&context.props().scenario_name)
@@ -93,7 +123,9 @@ impl Component for Leaderboard {
                         .play_cb
                         .reform(move |_| (team, shortcode.clone()))
                 };
-                let asterisk = if row.rescored_version.as_ref() != Some(&current_version) {
+                let asterisk = if category == LeaderboardCategory::Time
+                    && row.rescored_version.as_ref() != Some(&current_version)
+                {
                     html! { "*" }
                 } else {
                     html! {}
@@ -102,7 +134,7 @@ impl Component for Leaderboard {
                     <tr class={classes!(class)}>
                         <td class="centered"><b>{ rank }</b></td>
                         <td>{ row.username.clone().unwrap_or_else(|| userid::generate_username(&row.userid)) }</td>
-                        <td>{ &row.time }{ asterisk }</td>
+                        <td>{ format_score(category, row) }{ asterisk }</td>
                         <td>
                             <a title="Play As" class="material-symbols-outlined" onclick={make_play_cb(0)}>{ "play_arrow" }</a>
                             { if is_tournament { html! { <>
@@ -124,7 +156,7 @@ impl Component for Leaderboard {
 
             let mut table_rows = vec![];
             let mut last_index = None;
-            for (i, row) in data.lowest_time.iter().enumerate() {
+            for (i, row) in data.rows(category).iter().enumerate() {
                 let rank = i + 1;
                 let add_entry = i < 10
                     || i + 1 == own_row_index
@@ -142,17 +174,18 @@ impl Component for Leaderboard {
                 }
             }
             if let Some(last_index) = last_index {
-                if last_index + 1 != data.lowest_time.len() {
-                    let skipped = data.lowest_time.len() - (last_index + 1);
+                if last_index + 1 != data.rows(category).len() {
+                    let skipped = data.rows(category).len() - (last_index + 1);
                     table_rows.push(html! { <tr><td colspan=4 class="skip">{ "skipped " }{ skipped }{ " rows" }</td></tr> });
                 }
             }
 
             html! {
                 <div class="leaderboard">
+                    { tabs }
                     <table>
                         <tr><th colspan=4>{ "Leaderboard" }</th></tr>
-                        <tr><th>{ "Rank" }</th><th>{ "User" }</th><th>{ "Time" }</th><th>{ "Play" }</th></tr>
+                        <tr><th>{ "Rank" }</th><th>{ "User" }</th><th>{ category.title() }</th><th>{ "Play" }</th></tr>
                         <tbody>{ for table_rows }</tbody>
                     </table>
                 </div>
@@ -173,3 +206,19 @@ impl Component for Leaderboard {
         }
     }
 }
+
+fn format_score(category: LeaderboardCategory, row: &TimeLeaderboardRow) -> String {
+    match category {
+        LeaderboardCategory::Time => row.time.clone(),
+        LeaderboardCategory::CodeSize => row
+            .code_size
+            .map(|x| format!("{x} bytes"))
+            .unwrap_or_default(),
+        LeaderboardCategory::Gas => row.gas.map(format_gas).unwrap_or_default(),
+    }
+}
+
+/// Formats a gas count (instructions executed) for display.
+pub fn format_gas(gas: u64) -> String {
+    format!("{:.2}M instructions", gas as f64 / 1e6)
+}
@@ -1,7 +1,7 @@
 use crate::userid;
 use anyhow::anyhow;
 use chrono::Utc;
-use oort_proto::{LeaderboardData, LeaderboardSubmission, TournamentResults};
+use oort_proto::{LeaderboardCategory, LeaderboardData, LeaderboardSubmission, TournamentResults};
 use oort_proto::{ShortcodeUpload, TournamentSubmission};
 use oort_proto::{Telemetry, TelemetryMsg};
 use reqwasm::http::{Request, Response};
@@ -45,9 +45,15 @@ async fn send_request(request: Request) -> anyhow::Result<Response> {
 
 pub fn get_leaderboard(
     scenario_name: &str,
+    category: LeaderboardCategory,
     callback: yew::Callback<anyhow::Result<LeaderboardData>>,
 ) {
-    let url = format!("{}/leaderboard/{}", backend_url(), encode(scenario_name));
+    let url = format!(
+        "{}/leaderboard/{}/{}",
+        backend_url(),
+        category.name(),
+        encode(scenario_name)
+    );
     wasm_bindgen_futures::spawn_local(async move {
         match send_request(Request::get(&url)).await {
             Err(e) => {
@@ -169,6 +169,7 @@ impl Component for SimulationWindow {
                 }
                 false
             }
+            Msg::ReceivedSimAgentResponse(_) => false,
             Msg::TimelineEvent(index, is_change_event) => {
                 // Timeline was set to a new index
                 // Display the relevant snapshot
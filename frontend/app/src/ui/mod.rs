@@ -669,6 +669,7 @@ impl UI {
                 team,
                 health,
                 fuel,
+                energy,
                 active_abilities,
                 ..
             } = ship;
@@ -684,6 +685,11 @@ impl UI {
                 } else {
                     "".to_string()
                 };
+                let energy_text = if let Some(energy) = energy {
+                    format!("Energy: {:.1} MJ\n", energy / 1e6)
+                } else {
+                    "".to_string()
+                };
                 let active_abilities_text = if !active_abilities.is_empty() {
                     format!(
                         "Active abilities: {}\n",
@@ -697,7 +703,7 @@ impl UI {
                     "".to_string()
                 };
                 elem.set_text_content(Some(&format!(
-                    "{class:?}\nTeam: {team:?}\nHealth: {health:.0}\n{fuel_text}{active_abilities_text}{debug_text}"
+                    "{class:?}\nTeam: {team:?}\nHealth: {health:.0}\n{fuel_text}{energy_text}{active_abilities_text}{debug_text}"
                 )));
             }
         } else if let Some(elem) = self.picked_ref.cast::<Element>() {
@@ -0,0 +1,152 @@
+use super::{buffer_arena, glutil};
+use glutil::VertexAttribBuilder;
+use nalgebra::{vector, Matrix4, Vector4};
+use oort_simulator::snapshot::Field;
+use wasm_bindgen::prelude::*;
+use web_sys::{WebGl2RenderingContext, WebGlProgram, WebGlUniformLocation, WebGlVertexArrayObject};
+use WebGl2RenderingContext as gl;
+
+/// Draws translucent convex regions such as nebulae.
+pub struct FieldRenderer {
+    context: WebGl2RenderingContext,
+    program: WebGlProgram,
+    transform_loc: WebGlUniformLocation,
+    buffer_arena: buffer_arena::BufferArena,
+    vao: WebGlVertexArrayObject,
+}
+
+pub struct DrawSet {
+    projection_matrix: Matrix4<f32>,
+    draw: Option<Draw>,
+}
+
+pub struct Draw {
+    num_vertices: usize,
+    attribs_token: buffer_arena::Token,
+}
+
+struct Attribs {
+    vertex: Vector4<f32>,
+    color: Vector4<f32>,
+}
+
+impl FieldRenderer {
+    pub fn new(context: WebGl2RenderingContext) -> Result<Self, JsValue> {
+        let vert_shader = glutil::compile_shader(
+            &context,
+            gl::VERTEX_SHADER,
+            r#"#version 300 es
+uniform mat4 transform;
+layout(location = 0) in vec4 vertex;
+layout(location = 1) in vec4 color;
+out vec4 varying_color;
+void main() {
+    gl_Position = transform * vertex;
+    varying_color = color;
+}
+    "#,
+        )?;
+        let frag_shader = glutil::compile_shader(
+            &context,
+            gl::FRAGMENT_SHADER,
+            r#"#version 300 es
+precision mediump float;
+in vec4 varying_color;
+out vec4 fragmentColor;
+void main() {
+    fragmentColor = varying_color;
+}
+    "#,
+        )?;
+        let program = glutil::link_program(&context, &vert_shader, &frag_shader)?;
+
+        let transform_loc = context
+            .get_uniform_location(&program, "transform")
+            .ok_or("did not find uniform")?;
+
+        let vao = context
+            .create_vertex_array()
+            .ok_or("failed to create vertex array")?;
+
+        assert_eq!(context.get_error(), gl::NO_ERROR);
+
+        Ok(Self {
+            context: context.clone(),
+            program,
+            transform_loc,
+            buffer_arena: buffer_arena::BufferArena::new(
+                "field_renderer",
+                context,
+                gl::ARRAY_BUFFER,
+                1024 * 1024,
+            )?,
+            vao,
+        })
+    }
+
+    pub fn upload(&mut self, projection_matrix: &Matrix4<f32>, fields: &[Field]) -> DrawSet {
+        // Each convex field is drawn as a triangle fan around its first vertex.
+        let mut attribs = vec![];
+        for field in fields {
+            let vertices: Vec<_> = field
+                .vertices
+                .iter()
+                .map(|v| {
+                    let p = v.coords.cast::<f32>();
+                    vector![p.x, p.y, 0.0, 1.0]
+                })
+                .collect();
+            for i in 1..vertices.len().saturating_sub(1) {
+                for vertex in [vertices[0], vertices[i], vertices[i + 1]] {
+                    attribs.push(Attribs {
+                        vertex,
+                        color: field.color,
+                    });
+                }
+            }
+        }
+
+        let draw = if attribs.is_empty() {
+            None
+        } else {
+            Some(Draw {
+                num_vertices: attribs.len(),
+                attribs_token: self.buffer_arena.write(&attribs),
+            })
+        };
+        DrawSet {
+            projection_matrix: *projection_matrix,
+            draw,
+        }
+    }
+
+    pub fn draw(&mut self, drawset: &DrawSet) {
+        let Some(draw) = &drawset.draw else {
+            return;
+        };
+
+        self.context.use_program(Some(&self.program));
+        self.context.bind_vertex_array(Some(&self.vao));
+
+        self.context.uniform_matrix4fv_with_f32_array(
+            Some(&self.transform_loc),
+            false,
+            drawset.projection_matrix.data.as_slice(),
+        );
+
+        let vab = VertexAttribBuilder::new(&self.context).data_token(&draw.attribs_token);
+        vab.index(0)
+            .size(4)
+            .offset(offset_of!(Attribs, vertex))
+            .build();
+        vab.index(1)
+            .size(4)
+            .offset(offset_of!(Attribs, color))
+            .build();
+
+        self.context
+            .draw_arrays(gl::TRIANGLES, 0, draw.num_vertices as i32);
+
+        self.context.bind_vertex_array(None);
+    }
+}
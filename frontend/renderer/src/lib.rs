@@ -3,6 +3,7 @@
 pub mod blur;
 pub mod buffer_arena;
 pub mod bullet_renderer;
+pub mod field_renderer;
 pub mod flare_renderer;
 pub mod geometry;
 pub mod glutil;
@@ -18,6 +19,7 @@ extern crate memoffset;
 
 use blur::Blur;
 use bullet_renderer::BulletRenderer;
+use field_renderer::FieldRenderer;
 use flare_renderer::FlareRenderer;
 use grid_renderer::GridRenderer;
 use line_renderer::LineRenderer;
@@ -38,6 +40,7 @@ pub struct Renderer {
     canvas: HtmlCanvasElement,
     context: WebGl2RenderingContext,
     grid_renderer: GridRenderer,
+    field_renderer: FieldRenderer,
     line_renderer: LineRenderer,
     ship_renderer: ShipRenderer,
     bullet_renderer: BulletRenderer,
@@ -80,6 +83,7 @@ impl Renderer {
             canvas,
             context: context.clone(),
             grid_renderer: GridRenderer::new(context.clone())?,
+            field_renderer: FieldRenderer::new(context.clone())?,
             line_renderer: LineRenderer::new(context.clone())?,
             ship_renderer: ShipRenderer::new(context.clone())?,
             bullet_renderer: BulletRenderer::new(context.clone())?,
@@ -224,9 +228,15 @@ impl Renderer {
             r
         };
 
+        let field_drawset = self
+            .field_renderer
+            .upload(&self.projection_matrix, &snapshot.fields);
         let scenario_line_drawset = self
             .line_renderer
             .upload(&self.projection_matrix, &snapshot.scenario_lines);
+        let beam_drawset = self
+            .line_renderer
+            .upload(&self.projection_matrix, &snapshot.beams);
 
         self.set_view_params(&debug_view_params);
         let debug_line_drawset = {
@@ -265,6 +275,7 @@ impl Renderer {
             self.particle_renderer
                 .draw(&particle_drawset, 10.0 * self.base_line_width);
             self.line_renderer.draw(&scenario_line_drawset);
+            self.line_renderer.draw(&beam_drawset);
             self.ship_renderer.draw(&ship_drawset);
             if let Err(e) = self.blur.finish() {
                 log::error!("WebGL error during blur rendering: {:?}", e);
@@ -278,6 +289,7 @@ impl Renderer {
             self.context.clear(gl::COLOR_BUFFER_BIT);
             self.grid_renderer
                 .draw(zoom, camera_target, snapshot.world_size);
+            self.field_renderer.draw(&field_drawset);
             if self.blur_enabled {
                 self.blur.draw();
             }
@@ -287,6 +299,7 @@ impl Renderer {
             self.particle_renderer
                 .draw(&particle_drawset, 5.0 * self.base_line_width);
             self.line_renderer.draw(&scenario_line_drawset);
+            self.line_renderer.draw(&beam_drawset);
             self.line_renderer.draw(&debug_line_drawset);
             self.ship_renderer.draw(&ship_drawset);
             self.text_renderer.draw(&text_drawset);
@@ -95,11 +95,23 @@ void main() {
             0 => vector![0.99, 0.98, 0.00, 1.00],
             1 => vector![0.99, 0.00, 0.98, 1.00],
             2 => vector![0.13, 0.50, 0.73, 1.00],
+            3 => vector![0.20, 0.85, 0.30, 1.00],
             9 => vector![0.40, 0.40, 0.40, 1.00],
             _ => vector![1.0, 1.0, 1.0, 1.0],
         }
     }
 
+    /// Countermeasures and mines are drawn in their own color rather than the
+    /// team's.
+    pub fn class_color(class: ShipClass) -> Option<Vector4<f32>> {
+        match class {
+            ShipClass::Chaff => Some(vector![0.70, 0.70, 0.80, 0.60]),
+            ShipClass::Flare => Some(vector![1.00, 0.60, 0.20, 1.00]),
+            ShipClass::Mine => Some(vector![0.90, 0.20, 0.20, 1.00]),
+            _ => None,
+        }
+    }
+
     pub fn upload(
         &mut self,
         projection_matrix: &Matrix4<f32>,
@@ -134,7 +146,8 @@ void main() {
                 if nlips_draw
                     && (!nlips_enabled
                         || nlips_scale < min_nlips_scale
-                        || matches!(class, ShipClass::Asteroid { .. }))
+                        || matches!(class, ShipClass::Asteroid { .. })
+                        || class.is_countermeasure())
                 {
                     continue;
                 }
@@ -148,7 +161,8 @@ void main() {
                 for ship in ships.iter() {
                     let p = ship.position.coords.cast::<f32>();
                     let shielded = ship.active_abilities.contains(&oort_api::Ability::Shield);
-                    let mut team_color = Self::team_color(ship.team);
+                    let mut team_color =
+                        Self::class_color(class).unwrap_or_else(|| Self::team_color(ship.team));
                     if nlips_draw {
                         team_color.w *= (nlips_scale / min_nlips_scale - 1.0)
                             .clamp(0.0, 1.0)
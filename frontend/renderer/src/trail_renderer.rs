@@ -108,7 +108,10 @@ void main() {
         let creation_time = snapshot.time as f32;
 
         for ship in snapshot.ships.iter() {
-            if let ShipClass::Asteroid { .. } = ship.class {
+            if matches!(
+                ship.class,
+                ShipClass::Asteroid { .. } | ShipClass::Chaff | ShipClass::Flare | ShipClass::Mine
+            ) {
                 continue;
             }
             if let Some(fuel) = ship.fuel {
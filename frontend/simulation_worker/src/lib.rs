@@ -1,3 +1,4 @@
+use oort_simulator::harness::{self, TestResult};
 use oort_simulator::scenario::{Status, MAX_TICKS};
 use oort_simulator::simulation::Code;
 use oort_simulator::simulation::Simulation;
@@ -17,11 +18,19 @@ pub enum Request {
         ticks: u32,
         nonce: u32,
     },
+    RunTests {
+        code: Code,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Response {
-    Snapshot { snapshot: Snapshot },
+    Snapshot {
+        snapshot: Snapshot,
+    },
+    TestResults {
+        results: Result<Vec<TestResult>, String>,
+    },
 }
 
 pub struct SimAgent {
@@ -74,6 +83,10 @@ impl yew_agent::Worker for SimAgent {
                 self.errored = !snapshot.errors.is_empty();
                 self.link.respond(who, Response::Snapshot { snapshot });
             }
+            Request::RunTests { code } => {
+                let results = harness::run_all(&code).map_err(|e| e.msg);
+                self.link.respond(who, Response::TestResults { results });
+            }
         };
     }
 
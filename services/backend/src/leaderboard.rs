@@ -1,4 +1,4 @@
-use crate::{discord, error, project_id, Error};
+use crate::{discord, error, project_id, rescore, Error};
 use axum::debug_handler;
 use axum::extract::{Path, State};
 use axum::Json;
@@ -6,15 +6,27 @@ use bytes::Bytes;
 use chrono::Utc;
 use firestore::*;
 use gcloud_sdk::google::firestore::v1::Document;
-use oort_proto::{LeaderboardData, LeaderboardSubmission, TimeLeaderboardRow};
+use oort_proto::{LeaderboardCategory, LeaderboardData, LeaderboardSubmission, TimeLeaderboardRow};
 
 async fn fetch_leaderboard(
     db: &FirestoreDb,
     scenario_name: &str,
 ) -> anyhow::Result<LeaderboardData> {
+    let mut leaderboard = LeaderboardData::default();
+    for category in LeaderboardCategory::ALL {
+        *leaderboard.rows_mut(category) = fetch_board(db, category, scenario_name).await?;
+    }
+    Ok(leaderboard)
+}
+
+async fn fetch_board(
+    db: &FirestoreDb,
+    category: LeaderboardCategory,
+    scenario_name: &str,
+) -> anyhow::Result<Vec<TimeLeaderboardRow>> {
     let docs: Vec<Document> = db
         .query_doc(
-            FirestoreQueryParams::new("leaderboard".into())
+            FirestoreQueryParams::new(category.collection().into())
                 .with_filter(FirestoreQueryFilter::Composite(
                     FirestoreQueryFilterComposite::new(
                         vec![FirestoreQueryFilter::Compare(Some(
@@ -27,7 +39,10 @@ async fn fetch_leaderboard(
                     ),
                 ))
                 .with_order_by(vec![
-                    FirestoreQueryOrder::new("time".to_owned(), FirestoreQueryDirection::Ascending),
+                    FirestoreQueryOrder::new(
+                        category.field().to_owned(),
+                        FirestoreQueryDirection::Ascending,
+                    ),
                     FirestoreQueryOrder::new(
                         "timestamp".to_owned(),
                         FirestoreQueryDirection::Ascending,
@@ -37,20 +52,35 @@ async fn fetch_leaderboard(
         )
         .await?;
 
-    let mut leaderboard = LeaderboardData::default();
+    let mut rows = vec![];
 
     for doc in &docs {
         if let Ok(msg) = FirestoreDb::deserialize_doc_to::<LeaderboardSubmission>(doc) {
-            leaderboard.lowest_time.push(make_row(&msg));
+            rows.push(make_row(&msg, category));
         } else {
             log::error!("Failed to deserialize doc {}", doc.name);
         }
     }
 
-    Ok(leaderboard)
+    Ok(rows)
 }
 
-pub fn make_row(submission: &LeaderboardSubmission) -> TimeLeaderboardRow {
+pub fn make_row(
+    submission: &LeaderboardSubmission,
+    category: LeaderboardCategory,
+) -> TimeLeaderboardRow {
+    let shortcode = match category {
+        LeaderboardCategory::Time => format!(
+            "leaderboard:{}:{}",
+            submission.username, submission.scenario_name
+        ),
+        _ => format!(
+            "leaderboard:{}:{}:{}",
+            category.name(),
+            submission.username,
+            submission.scenario_name
+        ),
+    };
     TimeLeaderboardRow {
         userid: submission.userid.clone(),
         username: Some(submission.username.clone()),
@@ -58,11 +88,10 @@ pub fn make_row(submission: &LeaderboardSubmission) -> TimeLeaderboardRow {
         encrypted_code: "".into(),
         timestamp: Some(submission.timestamp),
         time_float: Some(submission.time),
-        shortcode: Some(format!(
-            "leaderboard:{}:{}",
-            submission.username, submission.scenario_name
-        )),
+        shortcode: Some(shortcode),
         rescored_version: submission.rescored_version.clone(),
+        code_size: Some(submission.code_size),
+        gas: submission.gas,
     }
 }
 
@@ -75,6 +104,23 @@ pub async fn get(
     Ok(Json(data))
 }
 
+pub async fn get_category(
+    Path((category, scenario_name)): Path<(String, String)>,
+    cache: State<SharedLeaderboardCache>,
+) -> Result<Json<LeaderboardData>, Error> {
+    let Some(category) = LeaderboardCategory::from_name(&category) else {
+        return Err(error(
+            axum::http::StatusCode::NOT_FOUND,
+            "unknown leaderboard category".into(),
+        ));
+    };
+    let db = FirestoreDb::new(&project_id()).await?;
+    let data: LeaderboardData = cache.get(&db, &scenario_name).await?;
+    let mut result = LeaderboardData::default();
+    *result.rows_mut(category) = data.rows(category).clone();
+    Ok(Json(result))
+}
+
 #[debug_handler]
 pub async fn post(
     cache: State<SharedLeaderboardCache>,
@@ -101,28 +147,42 @@ pub async fn post(
         ));
     }
 
+    if obj.gas.is_some() {
+        obj.gas = verify_gas(&obj).await;
+    }
+
     obj.timestamp = Utc::now();
     let path = format!("{}.{}", obj.scenario_name, obj.userid);
 
     let old_leaderboard = cache.get(&db, &obj.scenario_name).await?;
 
-    if let Ok(existing_obj) = db
-        .get_obj::<LeaderboardSubmission, _>("leaderboard", &path)
-        .await
-    {
-        log::debug!("Got existing obj {:?}", existing_obj);
-        if existing_obj.time <= obj.time {
-            log::debug!("Ignoring slower time");
-            return Ok(Json(old_leaderboard));
+    for category in LeaderboardCategory::ALL {
+        let Some(score) = obj.score(category) else {
+            continue;
+        };
+
+        if let Ok(existing_obj) = db
+            .get_obj::<LeaderboardSubmission, _>(category.collection(), &path)
+            .await
+        {
+            log::debug!("Got existing {} obj {:?}", category.name(), existing_obj);
+            if existing_obj
+                .score(category)
+                .map(|existing_score| existing_score <= score)
+                .unwrap_or(false)
+            {
+                log::debug!("Ignoring worse {}", category.name());
+                continue;
+            }
         }
-    }
 
-    db.update_obj::<_, (), _>("leaderboard", &path, &obj, None, None, None)
-        .await?;
+        db.update_obj::<_, (), _>(category.collection(), &path, &obj, None, None, None)
+            .await?;
 
-    cache
-        .update(&db, &obj.scenario_name, make_row(&obj))
-        .await?;
+        cache
+            .update(&db, &obj.scenario_name, category, make_row(&obj, category))
+            .await?;
+    }
 
     let new_leaderboard = cache.get(&db, &obj.scenario_name).await?;
 
@@ -160,6 +220,37 @@ pub async fn post(
     Ok(Json(new_leaderboard))
 }
 
+/// Recomputes the gas used by a submission instead of trusting the client.
+async fn verify_gas(obj: &LeaderboardSubmission) -> Option<u64> {
+    let http = reqwest::Client::new();
+    let code = match rescore::compile(&http, &obj.userid, &obj.code).await {
+        Ok(code) => code,
+        Err(e) => {
+            log::warn!(
+                "Compilation failed for userid={} scenario_name={}: {}",
+                obj.userid,
+                obj.scenario_name,
+                e
+            );
+            return None;
+        }
+    };
+    let scenario_name = obj.scenario_name.clone();
+    let scores =
+        tokio::task::spawn_blocking(move || rescore::run_simulations(&scenario_name, &code)).await;
+    match scores {
+        Ok(Ok(Some(scores))) => Some(scores.gas),
+        _ => {
+            log::warn!(
+                "Failed to verify gas for userid={} scenario_name={}",
+                obj.userid,
+                obj.scenario_name
+            );
+            None
+        }
+    }
+}
+
 pub type SharedLeaderboardCache = std::sync::Arc<LeaderboardCache>;
 
 pub struct LeaderboardCache {
@@ -205,9 +296,14 @@ impl LeaderboardCache {
         &self,
         db: &FirestoreDb,
         scenario_name: &str,
+        category: LeaderboardCategory,
         row: TimeLeaderboardRow,
     ) -> Result<(), Error> {
-        log::info!("Leaderboard cache update for {}", scenario_name);
+        log::info!(
+            "Leaderboard cache update for {} ({})",
+            scenario_name,
+            category.name()
+        );
         let has_cache_entry = {
             let scenarios = self.scenarios.lock().await;
             scenarios.contains_key(scenario_name)
@@ -227,15 +323,20 @@ impl LeaderboardCache {
 
         let mut scenarios = self.scenarios.lock().await;
         let cached = scenarios.get_mut(scenario_name).unwrap();
-        cached
-            .leaderboard
-            .lowest_time
-            .retain(|x| x.userid != row.userid);
-        cached.leaderboard.lowest_time.push(row);
-        cached
-            .leaderboard
-            .lowest_time
-            .sort_by_key(|x| ((x.time_float.unwrap_or(1e6) * 1e6) as u64, x.timestamp));
+        let rows = cached.leaderboard.rows_mut(category);
+        rows.retain(|x| x.userid != row.userid);
+        rows.push(row);
+        match category {
+            LeaderboardCategory::Time => {
+                rows.sort_by_key(|x| ((x.time_float.unwrap_or(1e6) * 1e6) as u64, x.timestamp))
+            }
+            LeaderboardCategory::CodeSize => {
+                rows.sort_by_key(|x| (x.code_size.unwrap_or(usize::MAX), x.timestamp))
+            }
+            LeaderboardCategory::Gas => {
+                rows.sort_by_key(|x| (x.gas.unwrap_or(u64::MAX), x.timestamp))
+            }
+        }
         Ok(())
     }
 }
@@ -68,6 +68,10 @@ async fn serve() -> anyhow::Result<()> {
             .route("/tournament/submit", post(tournament::submit))
             .route("/tournament/results/:id", get(tournament::get_results))
             .route("/leaderboard/:scenario_name", get(leaderboard::get))
+            .route(
+                "/leaderboard/:category/:scenario_name",
+                get(leaderboard::get_category),
+            )
             .route("/leaderboard", post(leaderboard::post))
             .with_state(leaderboard_cache)
             .layer(cors)
@@ -2,13 +2,20 @@ use comfy_table::presets::UTF8_FULL;
 use comfy_table::Table;
 use firestore::*;
 use gcloud_sdk::google::firestore::v1::Document;
-use oort_proto::LeaderboardSubmission;
+use oort_proto::{LeaderboardCategory, LeaderboardSubmission};
 use oort_simulator::simulation::Code;
 use oort_simulator::{scenario, simulation};
 use rayon::prelude::*;
 
 const TOP_N: u32 = 10;
 
+/// Scores averaged over all seeds of a passing solution.
+#[derive(Debug, Clone, Copy)]
+pub struct Scores {
+    pub time: f64,
+    pub gas: u64,
+}
+
 pub async fn rescore(dry_run: bool) -> anyhow::Result<()> {
     let db = FirestoreDb::new(&crate::project_id()).await?;
     let http = reqwest::Client::new();
@@ -20,140 +27,176 @@ pub async fn rescore(dry_run: bool) -> anyhow::Result<()> {
         .collect();
 
     for scenario_name in &scenario_names {
-        log::info!("Processing scenario {}", scenario_name);
-        let mut updates: Vec<(String, LeaderboardSubmission, Option<LeaderboardSubmission>)> =
-            Vec::new();
-
-        let docs: Vec<Document> = db
-            .query_doc(
-                FirestoreQueryParams::new("leaderboard".into())
-                    .with_filter(FirestoreQueryFilter::Composite(
-                        FirestoreQueryFilterComposite::new(
-                            vec![FirestoreQueryFilter::Compare(Some(
-                                FirestoreQueryFilterCompare::Equal(
-                                    "scenario_name".into(),
-                                    scenario_name.into(),
-                                ),
-                            ))],
-                            FirestoreQueryFilterCompositeOperator::And,
-                        ),
-                    ))
-                    .with_order_by(vec![
-                        FirestoreQueryOrder::new(
-                            "time".to_owned(),
-                            FirestoreQueryDirection::Ascending,
-                        ),
-                        FirestoreQueryOrder::new(
-                            "timestamp".to_owned(),
-                            FirestoreQueryDirection::Ascending,
-                        ),
-                    ])
-                    .with_limit(TOP_N),
+        for category in LeaderboardCategory::ALL {
+            rescore_category(
+                &db,
+                &http,
+                &current_version,
+                scenario_name,
+                category,
+                dry_run,
             )
             .await?;
+        }
+    }
 
-        for doc in docs {
-            let docid = extract_docid(&doc.name);
-            if let Ok(msg) = FirestoreDb::deserialize_doc_to::<LeaderboardSubmission>(&doc) {
-                if let Some(ref rescored_version) = msg.rescored_version {
-                    if *rescored_version == current_version {
-                        log::info!(
-                            "Skipping rescore for userid={} scenario_name={} docid={}",
-                            msg.userid,
-                            msg.scenario_name,
-                            docid
-                        );
-                        continue;
-                    }
+    Ok(())
+}
+
+async fn rescore_category(
+    db: &FirestoreDb,
+    http: &reqwest::Client,
+    current_version: &str,
+    scenario_name: &str,
+    category: LeaderboardCategory,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    log::info!(
+        "Processing scenario {} category {}",
+        scenario_name,
+        category.name()
+    );
+    let mut updates: Vec<(String, LeaderboardSubmission, Option<LeaderboardSubmission>)> =
+        Vec::new();
+
+    let docs: Vec<Document> = db
+        .query_doc(
+            FirestoreQueryParams::new(category.collection().into())
+                .with_filter(FirestoreQueryFilter::Composite(
+                    FirestoreQueryFilterComposite::new(
+                        vec![FirestoreQueryFilter::Compare(Some(
+                            FirestoreQueryFilterCompare::Equal(
+                                "scenario_name".into(),
+                                scenario_name.into(),
+                            ),
+                        ))],
+                        FirestoreQueryFilterCompositeOperator::And,
+                    ),
+                ))
+                .with_order_by(vec![
+                    FirestoreQueryOrder::new(
+                        category.field().to_owned(),
+                        FirestoreQueryDirection::Ascending,
+                    ),
+                    FirestoreQueryOrder::new(
+                        "timestamp".to_owned(),
+                        FirestoreQueryDirection::Ascending,
+                    ),
+                ])
+                .with_limit(TOP_N),
+        )
+        .await?;
+
+    for doc in docs {
+        let docid = extract_docid(&doc.name);
+        if let Ok(msg) = FirestoreDb::deserialize_doc_to::<LeaderboardSubmission>(&doc) {
+            if let Some(ref rescored_version) = msg.rescored_version {
+                if rescored_version == current_version {
+                    log::info!(
+                        "Skipping rescore for userid={} scenario_name={} docid={}",
+                        msg.userid,
+                        msg.scenario_name,
+                        docid
+                    );
+                    continue;
                 }
+            }
 
-                log::info!(
-                    "Running simulations for username={} scenario={} old_time={} docid={}",
-                    msg.username,
-                    msg.scenario_name,
-                    msg.time,
-                    docid
-                );
-
-                let code = compile(&http, &docid, &msg.code).await;
-
-                let wasm = match code {
-                    Ok(wasm) => wasm,
-                    Err(e) => {
-                        log::warn!(
-                            "Compilation failed for userid={} scenario_name={} docid={}: {}",
-                            msg.username,
-                            msg.scenario_name,
-                            docid,
-                            e
-                        );
-                        continue;
-                    }
-                };
-
-                log::info!("Successfully compiled to WASM");
-                let status = run_simulations(&msg.scenario_name, &wasm);
-                match status {
-                    Ok(Some(new_time)) => {
-                        if (msg.time - new_time).abs() >= 0.001 {
-                            log::info!("Updating time from {} to {}", msg.time, new_time);
-                        } else {
-                            log::info!("Time unchanged, {}", new_time);
-                        }
-                        let mut new_msg = msg.clone();
-                        new_msg.time = new_time;
-                        new_msg.rescored_version = Some(current_version.clone());
-                        updates.push((doc.name.to_string(), msg.clone(), Some(new_msg)));
-                    }
-                    Ok(None) => {
-                        log::warn!(
-                            "Simulation failed for userid={} scenario_name={} docid={}",
-                            msg.username,
-                            msg.scenario_name,
-                            docid,
-                        );
-                        updates.push((doc.name.to_string(), msg.clone(), None));
+            log::info!(
+                "Running simulations for username={} scenario={} old_time={} docid={}",
+                msg.username,
+                msg.scenario_name,
+                msg.time,
+                docid
+            );
+
+            let code = compile(http, &docid, &msg.code).await;
+
+            let wasm = match code {
+                Ok(wasm) => wasm,
+                Err(e) => {
+                    log::warn!(
+                        "Compilation failed for userid={} scenario_name={} docid={}: {}",
+                        msg.username,
+                        msg.scenario_name,
+                        docid,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            log::info!("Successfully compiled to WASM");
+            let status = run_simulations(&msg.scenario_name, &wasm);
+            match status {
+                Ok(Some(scores)) => {
+                    if (msg.time - scores.time).abs() >= 0.001 {
+                        log::info!("Updating time from {} to {}", msg.time, scores.time);
+                    } else {
+                        log::info!("Time unchanged, {}", scores.time);
                     }
-                    Err(e) => {
-                        log::error!(
-                            "Simulation panicked for userid={} scenario_name={} docid={}: {:?}",
-                            msg.username,
-                            msg.scenario_name,
-                            docid,
-                            e
-                        );
+                    if msg.gas != Some(scores.gas) {
+                        log::info!("Updating gas from {:?} to {}", msg.gas, scores.gas);
                     }
+                    let mut new_msg = msg.clone();
+                    new_msg.time = scores.time;
+                    new_msg.gas = Some(scores.gas);
+                    new_msg.rescored_version = Some(current_version.to_owned());
+                    updates.push((doc.name.to_string(), msg.clone(), Some(new_msg)));
+                }
+                Ok(None) => {
+                    log::warn!(
+                        "Simulation failed for userid={} scenario_name={} docid={}",
+                        msg.username,
+                        msg.scenario_name,
+                        docid,
+                    );
+                    updates.push((doc.name.to_string(), msg.clone(), None));
+                }
+                Err(e) => {
+                    log::error!(
+                        "Simulation panicked for userid={} scenario_name={} docid={}: {:?}",
+                        msg.username,
+                        msg.scenario_name,
+                        docid,
+                        e
+                    );
                 }
             }
         }
+    }
+
+    log::info!("Applying {} updates:", updates.len());
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL);
+    table.set_header(vec![
+        "Scenario", "Category", "User", "Old Time", "New Time", "Old Gas", "New Gas", "Docid",
+    ]);
+    for (docname, old_msg, new_msg) in &updates {
+        let docid = extract_docid(docname);
+        table.add_row(vec![
+            old_msg.scenario_name.clone(),
+            category.name().to_owned(),
+            old_msg.username.clone(),
+            format!("{:.3}", old_msg.time),
+            format!("{:.3?}", new_msg.as_ref().map(|x| x.time)),
+            format!("{:?}", old_msg.gas),
+            format!("{:?}", new_msg.as_ref().and_then(|x| x.gas)),
+            docid.clone(),
+        ]);
+    }
+    println!("{table}");
 
-        log::info!("Applying {} updates:", updates.len());
-        let mut table = Table::new();
-        table.load_preset(UTF8_FULL);
-        table.set_header(vec!["Scenario", "User", "Old Time", "New Time", "Docid"]);
-        for (docname, old_msg, new_msg) in &updates {
+    if dry_run {
+        log::info!("Dry run, skipping database update");
+    } else {
+        for (docname, _old_msg, new_msg) in &updates {
             let docid = extract_docid(docname);
-            table.add_row(vec![
-                old_msg.scenario_name.clone(),
-                old_msg.username.clone(),
-                format!("{:.3}", old_msg.time),
-                format!("{:.3?}", new_msg.as_ref().map(|x| x.time)),
-                docid.clone(),
-            ]);
-        }
-        println!("{table}");
-
-        if dry_run {
-            log::info!("Dry run, skipping database update");
-        } else {
-            for (docname, _old_msg, new_msg) in &updates {
-                let docid = extract_docid(docname);
-                if let Some(new_msg) = new_msg {
-                    db.update_obj::<_, (), _>("leaderboard", &docid, new_msg, None, None, None)
-                        .await?;
-                } else {
-                    db.delete_by_id("leaderboard", &docid, None).await?;
-                }
+            if let Some(new_msg) = new_msg {
+                db.update_obj::<_, (), _>(category.collection(), &docid, new_msg, None, None, None)
+                    .await?;
+            } else {
+                db.delete_by_id(category.collection(), &docid, None).await?;
             }
         }
     }
@@ -161,7 +204,11 @@ pub async fn rescore(dry_run: bool) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn compile(http: &reqwest::Client, name: &str, source_code: &str) -> anyhow::Result<Code> {
+pub async fn compile(
+    http: &reqwest::Client,
+    name: &str,
+    source_code: &str,
+) -> anyhow::Result<Code> {
     let compiler_url =
         std::env::var("COMPILER_URL").unwrap_or_else(|_| "https://compiler.oort.rs".to_string());
     log::info!("Using compiler at {}", compiler_url);
@@ -180,8 +227,9 @@ async fn compile(http: &reqwest::Client, name: &str, source_code: &str) -> anyho
     Ok(oort_simulator::vm::precompile(&compiled_code).unwrap())
 }
 
-fn run_simulations(scenario_name: &str, code: &Code) -> std::thread::Result<Option<f64>> {
-    let results: std::thread::Result<Vec<Option<f64>>> = (0..10u32)
+/// Runs the solution on every seed, returning `None` unless it wins them all.
+pub fn run_simulations(scenario_name: &str, code: &Code) -> std::thread::Result<Option<Scores>> {
+    let results: std::thread::Result<Vec<Option<Scores>>> = (0..10u32)
         .into_par_iter()
         .map(|seed| run_simulation(scenario_name, seed, code.clone()))
         .collect();
@@ -190,12 +238,18 @@ fn run_simulations(scenario_name: &str, code: &Code) -> std::thread::Result<Opti
     if results.iter().any(|x| x.is_none()) {
         return Ok(None);
     }
-    Ok(Some(
-        results.iter().map(|x| x.unwrap()).sum::<f64>() / results.len() as f64,
-    ))
+    let n = results.len();
+    Ok(Some(Scores {
+        time: results.iter().map(|x| x.unwrap().time).sum::<f64>() / n as f64,
+        gas: results.iter().map(|x| x.unwrap().gas).sum::<u64>() / n as u64,
+    }))
 }
 
-fn run_simulation(scenario_name: &str, seed: u32, code: Code) -> std::thread::Result<Option<f64>> {
+fn run_simulation(
+    scenario_name: &str,
+    seed: u32,
+    code: Code,
+) -> std::thread::Result<Option<Scores>> {
     std::panic::catch_unwind(|| {
         let scenario = scenario::load(scenario_name);
         let mut codes = scenario.initial_code();
@@ -205,7 +259,10 @@ fn run_simulation(scenario_name: &str, seed: u32, code: Code) -> std::thread::Re
             sim.step();
         }
         match sim.status() {
-            scenario::Status::Victory { team: 0 } => Some(sim.score_time()),
+            scenario::Status::Victory { team: 0 } => Some(Scores {
+                time: sim.score_time(),
+                gas: sim.gas_used(0),
+            }),
             _ => None,
         }
     })
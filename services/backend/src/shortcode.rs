@@ -4,12 +4,15 @@ use axum::extract::{Json, Path};
 use chrono::Utc;
 use firestore::*;
 use gcloud_sdk::google::firestore::v1::Document;
-use oort_proto::{LeaderboardSubmission, ShortcodeUpload, TournamentSubmission};
+use oort_proto::{
+    LeaderboardCategory, LeaderboardSubmission, ShortcodeUpload, TournamentSubmission,
+};
 use regex::Regex;
 
 #[derive(Clone, Debug)]
 enum Shortcode {
     Leaderboard {
+        category: LeaderboardCategory,
         username: String,
         scenario_name: String,
     },
@@ -24,12 +27,24 @@ enum Shortcode {
 
 fn parse_id(id: &str) -> anyhow::Result<Shortcode> {
     let leaderboard_re = Regex::new(r"^leaderboard:([a-zA-Z0-9_-]+):(\w+)$")?;
+    let category_leaderboard_re =
+        Regex::new(r"^leaderboard:(time|code_size|gas):([a-zA-Z0-9_-]+):(\w+)$")?;
     let tournament_re = Regex::new(r"^tournament:([a-zA-Z0-9_-]+):(\w+)$")?;
     let uploaded_re = Regex::new(r"^([a-zA-Z0-9_.-]+)$")?;
-    if let Some(caps) = leaderboard_re.captures(id) {
+    if let Some(caps) = category_leaderboard_re.captures(id) {
+        let category = LeaderboardCategory::from_name(caps.get(1).unwrap().as_str()).unwrap();
+        let username = caps.get(2).unwrap().as_str().to_string();
+        let scenario_name = caps.get(3).unwrap().as_str().to_string();
+        Ok(Shortcode::Leaderboard {
+            category,
+            username,
+            scenario_name,
+        })
+    } else if let Some(caps) = leaderboard_re.captures(id) {
         let username = caps.get(1).unwrap().as_str().to_string();
         let scenario_name = caps.get(2).unwrap().as_str().to_string();
         Ok(Shortcode::Leaderboard {
+            category: LeaderboardCategory::Time,
             username,
             scenario_name,
         })
@@ -50,12 +65,13 @@ fn parse_id(id: &str) -> anyhow::Result<Shortcode> {
 
 async fn fetch_leaderboard(
     db: &FirestoreDb,
+    category: LeaderboardCategory,
     scenario_name: &str,
     username: &str,
 ) -> anyhow::Result<String> {
     let docs: Vec<Document> = db
         .query_doc(
-            FirestoreQueryParams::new("leaderboard".into())
+            FirestoreQueryParams::new(category.collection().into())
                 .with_filter(FirestoreQueryFilter::Composite(
                     FirestoreQueryFilterComposite::new(
                         vec![
@@ -76,7 +92,10 @@ async fn fetch_leaderboard(
                     ),
                 ))
                 .with_order_by(vec![
-                    FirestoreQueryOrder::new("time".to_owned(), FirestoreQueryDirection::Ascending),
+                    FirestoreQueryOrder::new(
+                        category.field().to_owned(),
+                        FirestoreQueryDirection::Ascending,
+                    ),
                     FirestoreQueryOrder::new(
                         "timestamp".to_owned(),
                         FirestoreQueryDirection::Ascending,
@@ -143,9 +162,10 @@ pub async fn get(Path(id): Path<String>) -> Result<String, Error> {
     let db = FirestoreDb::new(&project_id()).await?;
     let code = match parse_id(&id)? {
         Shortcode::Leaderboard {
+            category,
             username,
             scenario_name,
-        } => fetch_leaderboard(&db, &scenario_name, &username).await?,
+        } => fetch_leaderboard(&db, category, &scenario_name, &username).await?,
         Shortcode::Tournament {
             username,
             scenario_name,
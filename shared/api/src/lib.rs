@@ -5,6 +5,7 @@ use std::f64::consts::TAU;
 
 #[doc(hidden)]
 pub mod panic;
+pub mod testing;
 mod vec;
 
 #[allow(missing_docs)]
@@ -161,8 +162,61 @@ pub enum SystemState {
 
     Id,
 
+    EsmCount,
+    EsmBearing,
+    EsmPower,
+    EsmJammer,
+
+    PowerAllocationWeapons,
+    PowerAllocationSensors,
+    PowerAllocationEngines,
+    Energy,
+
+    ZoneCount,
+    Zone0PositionX,
+    Zone0PositionY,
+    Zone0Radius,
+    Zone0Control,
+    Zone1PositionX,
+    Zone1PositionY,
+    Zone1Radius,
+    Zone1Control,
+    Zone2PositionX,
+    Zone2PositionY,
+    Zone2Radius,
+    Zone2Control,
+    Zone3PositionX,
+    Zone3PositionY,
+    Zone3Radius,
+    Zone3Control,
+    Score,
+    EnemyScore,
+    BoundaryRadius,
+    NextBoundaryRadius,
+    Aim4,
+    Fire4,
+    ReloadTicks4,
+    IlluminatedContactFound,
+    IlluminatedContactClass,
+    IlluminatedContactPositionX,
+    IlluminatedContactPositionY,
+    IlluminatedContactVelocityX,
+    IlluminatedContactVelocityY,
+    IlluminatedContactRssi,
+    IlluminatedContactSnr,
+    Ammo,
+    EsmBearing1,
+    EsmPower1,
+    EsmJammer1,
+    EsmBearing2,
+    EsmPower2,
+    EsmJammer2,
+    EsmBearing3,
+    EsmPower3,
+    EsmJammer3,
+
     Size,
-    MaxSize = 128,
+    MaxSize = 192,
 }
 
 #[allow(missing_docs)]
@@ -180,6 +234,7 @@ pub enum Class {
     Missile,
     Torpedo,
     Unknown,
+    Mine,
 }
 
 impl Class {
@@ -193,6 +248,7 @@ impl Class {
             4 => Class::Target,
             5 => Class::Missile,
             6 => Class::Torpedo,
+            8 => Class::Mine,
             _ => Class::Unknown,
         }
     }
@@ -264,6 +320,14 @@ impl Class {
                 max_lateral_acceleration: 0.0,
                 max_angular_acceleration: 0.0,
             },
+            Class::Mine => ClassStats {
+                max_health: 20.0,
+                mass: 200.0,
+                max_forward_acceleration: 0.0,
+                max_backward_acceleration: 0.0,
+                max_lateral_acceleration: 0.0,
+                max_angular_acceleration: 0.0,
+            },
         }
     }
 }
@@ -333,10 +397,25 @@ pub enum Ability {
     Decoy,
     /// Cruiser only. Deflects projectiles for 1s. Reloads in 5s.
     Shield,
+    /// Fighter, frigate and cruiser. Drops a cloud of chaff behind the ship
+    /// which shows up strongly on enemy radar as the launching ship's class
+    /// for 5s. Each activation drops one. Reloads in 1s. Fighters carry 4,
+    /// frigates 10, and cruisers 20.
+    Chaff,
+    /// Fighter, frigate and cruiser. Drops a flare behind the ship which
+    /// distracts missile and torpedo seekers for 3s. Each activation drops
+    /// one. Reloads in 1s. Fighters carry 4, frigates 10, and cruisers 20.
+    Flare,
 }
 
 /// Array of all ability types.
-pub const ABILITIES: &[Ability] = &[Ability::Boost, Ability::Decoy, Ability::Shield];
+pub const ABILITIES: &[Ability] = &[
+    Ability::Boost,
+    Ability::Decoy,
+    Ability::Shield,
+    Ability::Chaff,
+    Ability::Flare,
+];
 
 /// Electronic Counter Measures (ECM) modes.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -359,6 +438,43 @@ impl From<f64> for EcmMode {
     }
 }
 
+/// Which team holds a capture zone, from the point of view of the calling ship.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ZoneControl {
+    /// No team has captured the zone yet.
+    Neutral,
+    /// Held by our team.
+    Friendly,
+    /// Held by an enemy team.
+    Enemy,
+    /// Ships from more than one team are inside the zone. Nobody scores.
+    Contested,
+}
+
+impl From<f64> for ZoneControl {
+    fn from(x: f64) -> Self {
+        match x as u32 {
+            1 => ZoneControl::Friendly,
+            2 => ZoneControl::Enemy,
+            3 => ZoneControl::Contested,
+            _ => ZoneControl::Neutral,
+        }
+    }
+}
+
+/// Shape of the world.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology {
+    /// A square surrounded by walls. Ships that hit a wall explode.
+    #[default]
+    Walled,
+    /// A square without walls. Ships and bullets leaving one edge reappear on
+    /// the opposite edge.
+    Toroidal,
+}
+
 #[doc(hidden)]
 #[derive(Default, Clone)]
 pub struct Line {
@@ -395,6 +511,13 @@ pub mod sys {
     pub static mut SYSTEM_STATE: [u64; SystemState::MaxSize as usize] =
         [0; SystemState::MaxSize as usize];
 
+    /// Length of `SYSTEM_STATE`. The simulator shares only this many slots
+    /// with the ship.
+    #[unsafe(no_mangle)]
+    pub extern "C" fn system_state_size() -> u32 {
+        SystemState::MaxSize as u32
+    }
+
     pub fn read_system_state_u64(index: SystemState) -> u64 {
         let system_state = ptr::addr_of!(SYSTEM_STATE);
         unsafe { (*system_state)[index as usize] }
@@ -499,7 +622,7 @@ pub mod rng_state {
 
 mod api {
     use super::sys::{read_system_state, write_system_state};
-    use super::{Ability, Class, EcmMode, SystemState};
+    use super::{Ability, Class, EcmMode, SystemState, Topology, ZoneControl};
     use crate::sys::{read_system_state_u64, write_system_state_u64};
     use crate::{vec::*, ActiveAbilities, Message};
 
@@ -534,6 +657,31 @@ mod api {
             .unwrap_or(0.0)
     }
 
+    /// Returns the shape of the world.
+    pub fn topology() -> Topology {
+        match super::sys::getenv("WORLD_TOPOLOGY") {
+            Some("toroidal") => Topology::Toroidal,
+            _ => Topology::Walled,
+        }
+    }
+
+    /// Returns the shortest displacement equivalent to `dp`.
+    ///
+    /// In a toroidal world the shortest path between two points may cross
+    /// the edge of the world. For example, `wrap_displacement(target - position())`
+    /// gives the direction to aim at a target on the other side of the seam.
+    /// In a walled world this returns `dp` unchanged.
+    pub fn wrap_displacement(dp: Vec2) -> Vec2 {
+        match topology() {
+            Topology::Walled => dp,
+            Topology::Toroidal => {
+                let s = world_size();
+                let wrap = |x: f64| (x + s / 2.0).rem_euclid(s) - s / 2.0;
+                vec2(wrap(dp.x), wrap(dp.y))
+            }
+        }
+    }
+
     /// Returns the current position (in meters).
     pub fn position() -> Vec2 {
         vec2(
@@ -606,6 +754,7 @@ mod api {
             1 => SystemState::Aim1,
             2 => SystemState::Aim2,
             3 => SystemState::Aim3,
+            4 => SystemState::Aim4,
             _ => return,
         };
         write_system_state(state_index, heading);
@@ -620,6 +769,7 @@ mod api {
             1 => SystemState::Fire1,
             2 => SystemState::Fire2,
             3 => SystemState::Fire3,
+            4 => SystemState::Fire4,
             _ => return,
         };
         write_system_state(state_index, 1.0);
@@ -634,6 +784,7 @@ mod api {
             1 => SystemState::ReloadTicks1,
             2 => SystemState::ReloadTicks2,
             3 => SystemState::ReloadTicks3,
+            4 => SystemState::ReloadTicks4,
             _ => return 0,
         };
         read_system_state(state_index) as u32
@@ -656,6 +807,31 @@ mod api {
         read_system_state(SystemState::Fuel)
     }
 
+    /// Returns the rounds of gun ammunition left, or infinity if unlimited.
+    ///
+    /// In scenarios with logistics, fighters carry limited fuel and ammunition.
+    /// Both are replenished by holding station near a friendly frigate or
+    /// cruiser.
+    pub fn ammo() -> f64 {
+        read_system_state(SystemState::Ammo)
+    }
+
+    /// Returns the energy stored in the battery (J).
+    pub fn energy() -> f64 {
+        read_system_state(SystemState::Energy)
+    }
+
+    /// Sets how reactor output is shared between subsystems.
+    ///
+    /// Each subsystem is guaranteed its share of the reactor output. Shares
+    /// are relative, so `set_power_allocation(2.0, 1.0, 1.0)` reserves half
+    /// for weapons. The default is an even split.
+    pub fn set_power_allocation(weapons: f64, sensors: f64, engines: f64) {
+        write_system_state(SystemState::PowerAllocationWeapons, weapons);
+        write_system_state(SystemState::PowerAllocationSensors, sensors);
+        write_system_state(SystemState::PowerAllocationEngines, engines);
+    }
+
     #[doc(hidden)]
     pub mod radar_internal {
         use super::SystemState;
@@ -842,6 +1018,252 @@ mod api {
         })
     }
 
+    /// Returns the contact illuminated by the ship that launched this missile
+    /// or torpedo (semi-active radar homing).
+    ///
+    /// The missile's radar receives reflections of its parent's radar beam
+    /// from whatever target the parent is currently tracking, as long as that
+    /// target is within the missile's own radar beam. This reaches much further
+    /// than the missile's own radar, but the contact is lost as soon as the
+    /// parent stops tracking the target.
+    pub fn illuminated_contact() -> Option<ScanResult> {
+        if read_system_state(SystemState::IlluminatedContactFound) == 0.0 {
+            return None;
+        }
+        Some(ScanResult {
+            class: Class::from_f64(read_system_state(SystemState::IlluminatedContactClass)),
+            position: vec2(
+                read_system_state(SystemState::IlluminatedContactPositionX),
+                read_system_state(SystemState::IlluminatedContactPositionY),
+            ),
+            velocity: vec2(
+                read_system_state(SystemState::IlluminatedContactVelocityX),
+                read_system_state(SystemState::IlluminatedContactVelocityY),
+            ),
+            rssi: read_system_state(SystemState::IlluminatedContactRssi),
+            snr: read_system_state(SystemState::IlluminatedContactSnr),
+        })
+    }
+
+    /// An enemy radar illuminating this ship, detected by the passive
+    /// radar warning receiver.
+    #[derive(Clone, Debug)]
+    pub struct EsmContact {
+        /// The approximate bearing to the emitter (in radians).
+        pub bearing: f64,
+        /// The received signal strength measured in dBm.
+        pub power: f64,
+        /// Whether the emitter is a noise jammer.
+        pub jammer: bool,
+    }
+
+    #[doc(hidden)]
+    pub mod esm_internal {
+        use super::SystemState;
+
+        pub const MAX_CONTACTS: usize = 4;
+
+        pub struct EsmIndices {
+            pub bearing: SystemState,
+            pub power: SystemState,
+            pub jammer: SystemState,
+        }
+
+        pub fn esm_indices(sel: usize) -> EsmIndices {
+            assert!(sel < MAX_CONTACTS);
+            if sel == 0 {
+                return EsmIndices {
+                    bearing: SystemState::EsmBearing,
+                    power: SystemState::EsmPower,
+                    jammer: SystemState::EsmJammer,
+                };
+            }
+            let stride = 3;
+            let offset = stride * (sel - 1);
+            let add_offset =
+                |x| unsafe { ::std::mem::transmute::<u8, SystemState>((x as u8) + offset as u8) };
+            EsmIndices {
+                bearing: add_offset(SystemState::EsmBearing1),
+                power: add_offset(SystemState::EsmPower1),
+                jammer: add_offset(SystemState::EsmJammer1),
+            }
+        }
+    }
+
+    fn read_esm_contact(sel: usize) -> EsmContact {
+        let indices = esm_internal::esm_indices(sel);
+        EsmContact {
+            bearing: read_system_state(indices.bearing),
+            power: read_system_state(indices.power),
+            jammer: read_system_state(indices.jammer) != 0.0,
+        }
+    }
+
+    /// Returns the strongest enemy radar illuminating this ship.
+    pub fn esm() -> Option<EsmContact> {
+        if read_system_state(SystemState::EsmCount) == 0.0 {
+            return None;
+        }
+        Some(read_esm_contact(0))
+    }
+
+    /// Returns up to four enemy radars illuminating this ship, strongest
+    /// first.
+    pub fn esm_contacts() -> Vec<EsmContact> {
+        (0..esm_count().min(esm_internal::MAX_CONTACTS))
+            .map(read_esm_contact)
+            .collect()
+    }
+
+    /// Returns the number of enemy radars illuminating this ship.
+    pub fn esm_count() -> usize {
+        read_system_state(SystemState::EsmCount) as usize
+    }
+
+    #[doc(hidden)]
+    pub mod zone_internal {
+        use super::SystemState;
+
+        pub const MAX_ZONES: usize = 4;
+
+        pub struct ZoneIndices {
+            pub position: [SystemState; 2],
+            pub radius: SystemState,
+            pub control: SystemState,
+        }
+
+        pub fn zone_indices(sel: usize) -> ZoneIndices {
+            assert!(sel < MAX_ZONES);
+            let stride = 4;
+            let offset = stride * sel;
+            let add_offset =
+                |x| unsafe { ::std::mem::transmute::<u8, SystemState>((x as u8) + offset as u8) };
+            ZoneIndices {
+                position: [
+                    add_offset(SystemState::Zone0PositionX),
+                    add_offset(SystemState::Zone0PositionY),
+                ],
+                radius: add_offset(SystemState::Zone0Radius),
+                control: add_offset(SystemState::Zone0Control),
+            }
+        }
+    }
+
+    /// A capture zone.
+    #[derive(Clone, Debug)]
+    pub struct CaptureZone {
+        /// The center of the zone.
+        pub position: Vec2,
+        /// The radius of the zone.
+        pub radius: f64,
+        /// Which team holds the zone.
+        pub control: ZoneControl,
+    }
+
+    /// Returns the capture zones in the scenario.
+    pub fn capture_zones() -> Vec<CaptureZone> {
+        let count =
+            (read_system_state(SystemState::ZoneCount) as usize).min(zone_internal::MAX_ZONES);
+        (0..count)
+            .map(|i| {
+                let indices = zone_internal::zone_indices(i);
+                CaptureZone {
+                    position: vec2(
+                        read_system_state(indices.position[0]),
+                        read_system_state(indices.position[1]),
+                    ),
+                    radius: read_system_state(indices.radius),
+                    control: ZoneControl::from(read_system_state(indices.control)),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the control score of our team.
+    pub fn score() -> f64 {
+        read_system_state(SystemState::Score)
+    }
+
+    /// Returns the highest control score of any enemy team.
+    pub fn enemy_score() -> f64 {
+        read_system_state(SystemState::EnemyScore)
+    }
+
+    /// A region of space that degrades sensors and slows ships inside it.
+    #[derive(Clone, Debug)]
+    pub enum Nebula {
+        Circle {
+            center: Vec2,
+            radius: f64,
+        },
+        /// A convex polygon with vertices in counter-clockwise order.
+        Polygon {
+            vertices: Vec<Vec2>,
+        },
+    }
+
+    impl Nebula {
+        /// Returns true if `point` is inside the nebula.
+        pub fn contains(&self, point: Vec2) -> bool {
+            match self {
+                Nebula::Circle { center, radius } => point.distance(*center) < *radius,
+                Nebula::Polygon { vertices } => {
+                    let n = vertices.len();
+                    (0..n).all(|i| {
+                        let a = vertices[i];
+                        let b = vertices[(i + 1) % n];
+                        let (u, v) = (b - a, point - a);
+                        u.x * v.y - u.y * v.x >= 0.0
+                    })
+                }
+            }
+        }
+    }
+
+    /// Returns the nebulae in the scenario.
+    ///
+    /// Radar contacts with a ship inside a nebula are less accurate, radio
+    /// signals to or from a ship inside a nebula are much weaker, and ships
+    /// inside a nebula are slowed by drag.
+    pub fn nebulae() -> Vec<Nebula> {
+        let Some(s) = super::sys::getenv("NEBULAE") else {
+            return vec![];
+        };
+        s.split(';')
+            .filter_map(|nebula| {
+                let mut parts = nebula.split(' ');
+                let kind = parts.next()?;
+                let values: Vec<f64> = parts.filter_map(|x| x.parse().ok()).collect();
+                match kind {
+                    "c" if values.len() == 3 => Some(Nebula::Circle {
+                        center: vec2(values[0], values[1]),
+                        radius: values[2],
+                    }),
+                    "p" => Some(Nebula::Polygon {
+                        vertices: values.chunks_exact(2).map(|v| vec2(v[0], v[1])).collect(),
+                    }),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the radius of the arena boundary, a circle centered on the
+    /// origin. Ships outside it take damage.
+    ///
+    /// Returns infinity if the scenario has no boundary.
+    pub fn boundary_radius() -> f64 {
+        read_system_state(SystemState::BoundaryRadius)
+    }
+
+    /// Returns the radius the arena boundary is shrinking towards, or will
+    /// shrink to next.
+    ///
+    /// Returns infinity if the scenario has no boundary.
+    pub fn next_boundary_radius() -> f64 {
+        read_system_state(SystemState::NextBoundaryRadius)
+    }
+
     #[doc(hidden)]
     pub mod radio_internal {
         use super::SystemState;
@@ -1336,11 +1758,13 @@ pub mod prelude {
     #[doc(inline)]
     pub use super::rng::*;
     #[doc(inline)]
+    pub use super::testing::{Test, TestShip};
+    #[doc(inline)]
     pub use super::vec::*;
     #[doc(inline)]
-    pub use super::{Ability, Class, EcmMode, Message};
+    pub use super::{Ability, Class, EcmMode, Message, Topology, ZoneControl};
     #[doc(inline)]
-    pub use crate::{debug, draw_text};
+    pub use crate::{debug, draw_text, oort_test};
 
     pub use byteorder;
     pub use maths_rs;
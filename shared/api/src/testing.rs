@@ -0,0 +1,164 @@
+//! Unit scenarios for testing ship code.
+//!
+//! A test describes a small scenario: which ships to spawn, how many ticks to
+//! run, and what must be true afterwards. Tests are declared with the
+//! [oort_test!](crate::oort_test) macro and run by the "Run tests" editor
+//! action or the `test-ai` tool:
+//!
+//! ```rust,ignore
+//! use oort_api::prelude::*;
+//!
+//! oort_test!(destroys_target, |t: &mut Test| {
+//!     let me = t.spawn(Class::Fighter, 0, vec2(0.0, 0.0), 0.0);
+//!     let target = t.spawn(Class::Target, 1, vec2(1000.0, 0.0), 0.0);
+//!     t.run(600);
+//!     t.expect_alive(me);
+//!     t.expect_destroyed(target);
+//! });
+//! ```
+//!
+//! Ships on team 0 run your `Ship` code. Ships on other teams have no code and
+//! drift with their initial velocity.
+use crate::vec::Vec2;
+use crate::Class;
+
+/// Maximum number of values a test description can contain.
+pub const TEST_BUFFER_SIZE: usize = 4096;
+
+#[doc(hidden)]
+#[unsafe(no_mangle)]
+pub static mut TEST_BUFFER: [f64; TEST_BUFFER_SIZE] = [0.0; TEST_BUFFER_SIZE];
+
+#[doc(hidden)]
+pub mod opcodes {
+    pub const SPAWN: f64 = 1.0;
+    pub const RUN: f64 = 2.0;
+    pub const EXPECT_ALIVE: f64 = 3.0;
+    pub const EXPECT_DESTROYED: f64 = 4.0;
+    pub const EXPECT_POSITION_NEAR: f64 = 5.0;
+    pub const EXPECT_HEALTH_ABOVE: f64 = 6.0;
+    pub const EXPECT_HEALTH_BELOW: f64 = 7.0;
+}
+
+/// Identifies a ship spawned by [Test::spawn].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TestShip(usize);
+
+/// Builder for a test scenario.
+///
+/// Steps are executed in order: expectations are checked against the state of
+/// the simulation after all previous [Test::run] calls.
+#[derive(Default)]
+pub struct Test {
+    values: Vec<f64>,
+    num_ships: usize,
+}
+
+impl Test {
+    #[doc(hidden)]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Spawns a stationary ship.
+    pub fn spawn(&mut self, class: Class, team: i32, position: Vec2, heading: f64) -> TestShip {
+        self.spawn_with_velocity(class, team, position, Vec2::new(0.0, 0.0), heading)
+    }
+
+    /// Spawns a ship moving with the given velocity.
+    pub fn spawn_with_velocity(
+        &mut self,
+        class: Class,
+        team: i32,
+        position: Vec2,
+        velocity: Vec2,
+        heading: f64,
+    ) -> TestShip {
+        self.values.extend_from_slice(&[
+            opcodes::SPAWN,
+            class as u32 as f64,
+            team as f64,
+            position.x,
+            position.y,
+            velocity.x,
+            velocity.y,
+            heading,
+        ]);
+        self.num_ships += 1;
+        TestShip(self.num_ships - 1)
+    }
+
+    /// Runs the simulation for the given number of ticks.
+    pub fn run(&mut self, ticks: u32) {
+        self.values.extend_from_slice(&[opcodes::RUN, ticks as f64]);
+    }
+
+    /// Fails the test if the ship has been destroyed.
+    pub fn expect_alive(&mut self, ship: TestShip) {
+        self.values
+            .extend_from_slice(&[opcodes::EXPECT_ALIVE, ship.0 as f64]);
+    }
+
+    /// Fails the test if the ship still exists.
+    pub fn expect_destroyed(&mut self, ship: TestShip) {
+        self.values
+            .extend_from_slice(&[opcodes::EXPECT_DESTROYED, ship.0 as f64]);
+    }
+
+    /// Fails the test if the ship is further than `tolerance` meters from `position`.
+    pub fn expect_position_near(&mut self, ship: TestShip, position: Vec2, tolerance: f64) {
+        self.values.extend_from_slice(&[
+            opcodes::EXPECT_POSITION_NEAR,
+            ship.0 as f64,
+            position.x,
+            position.y,
+            tolerance,
+        ]);
+    }
+
+    /// Fails the test unless the ship exists and has more than `health` health.
+    pub fn expect_health_above(&mut self, ship: TestShip, health: f64) {
+        self.values
+            .extend_from_slice(&[opcodes::EXPECT_HEALTH_ABOVE, ship.0 as f64, health]);
+    }
+
+    /// Fails the test if the ship exists and has at least `health` health.
+    pub fn expect_health_below(&mut self, ship: TestShip, health: f64) {
+        self.values
+            .extend_from_slice(&[opcodes::EXPECT_HEALTH_BELOW, ship.0 as f64, health]);
+    }
+
+    /// Writes the test description to TEST_BUFFER.
+    ///
+    /// The first value is the number of values that follow.
+    #[doc(hidden)]
+    pub fn publish(&self) {
+        assert!(
+            self.values.len() < TEST_BUFFER_SIZE,
+            "test has too many steps"
+        );
+        unsafe {
+            let buffer = &mut *std::ptr::addr_of_mut!(TEST_BUFFER);
+            buffer[0] = self.values.len() as f64;
+            buffer[1..(self.values.len() + 1)].copy_from_slice(&self.values);
+        }
+    }
+}
+
+/// Declares a test scenario for your ship code.
+///
+/// Takes the name of the test and a closure which describes it using [Test](crate::testing::Test).
+#[macro_export]
+macro_rules! oort_test {
+    ($name:ident, $body:expr) => {
+        const _: () = {
+            #[unsafe(export_name = concat!("oort_test_", stringify!($name)))]
+            extern "C" fn run_test() {
+                let mut test = $crate::testing::Test::new();
+                let body: fn(&mut $crate::testing::Test) = $body;
+                body(&mut test);
+                test.publish();
+            }
+        };
+    };
+}
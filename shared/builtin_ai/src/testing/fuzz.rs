@@ -10,7 +10,7 @@ impl Ship {
 
     pub fn tick(&mut self) {
         draw_triangle(vec2(gen_f64(), gen_f64()), gen_f64(), 0xffffff);
-        for i in 0..(SystemState::MaxSize as u8) {
+        for i in 0..(SystemState::Size as u8) {
             if i == SystemState::Explode as u8 {
                 continue;
             }
@@ -0,0 +1,35 @@
+use oort_api::prelude::*;
+
+pub struct Ship {}
+
+impl Ship {
+    pub fn new() -> Ship {
+        Ship {}
+    }
+
+    pub fn tick(&mut self) {
+        accelerate(vec2(100.0, 0.0).rotate(heading()));
+        fire(0);
+    }
+}
+
+oort_test!(moves_forward, |t| {
+    let ship = t.spawn(Class::Fighter, 0, vec2(0.0, 0.0), 0.0);
+    t.run(60);
+    t.expect_position_near(ship, vec2(30.0, 0.0), 5.0);
+});
+
+oort_test!(destroys_target, |t| {
+    let ship = t.spawn(Class::Fighter, 0, vec2(0.0, 0.0), 0.0);
+    let target = t.spawn(Class::Target, 1, vec2(1000.0, 0.0), 0.0);
+    t.run(300);
+    t.expect_alive(ship);
+    t.expect_health_above(ship, 99.0);
+    t.expect_destroyed(target);
+});
+
+oort_test!(expected_failure, |t| {
+    let ship = t.spawn(Class::Fighter, 0, vec2(0.0, 0.0), 0.0);
+    t.run(10);
+    t.expect_destroyed(ship);
+});
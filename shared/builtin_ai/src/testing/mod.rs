@@ -1,4 +1,5 @@
 pub mod fuzz;
+pub mod harness;
 pub mod missile;
 pub mod radar_test;
 pub mod radar_test_enemy;
@@ -16,6 +16,21 @@ impl Ship {
             "id" => debug!("ID: {}", id()),
             "panic" => panic!("Panic!"),
             "infinite_loop" => loop {},
+            "esm" => debug!(
+                "ESM: {} {:?} {}",
+                esm_count(),
+                esm().map(|contact| contact.jammer),
+                esm_contacts().len()
+            ),
+            "chaff" => {
+                if current_tick() == 0 {
+                    activate_ability(Ability::Chaff);
+                }
+            }
+            "energy" => {
+                set_power_allocation(0.0, 1.0, 3.0);
+                debug!("Energy: {}", energy());
+            }
             _ => debug!("Unknown testcase: {:?}", testcase),
         }
     }
@@ -0,0 +1,197 @@
+use image::{Rgba, RgbaImage};
+use nalgebra::{vector, Vector2, Vector4};
+
+/// An RGB framebuffer with alpha blending and antialiased primitives.
+///
+/// Coordinates are in pixels with the origin at the top left.
+pub struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 3]>,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0.0; 3]; (width * height) as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn clear(&mut self, color: Vector4<f32>) {
+        self.pixels.fill([color.x, color.y, color.z]);
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [f32; 3] {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Blends `color` into the pixel, scaling its alpha by `coverage`.
+    pub fn blend(&mut self, x: i32, y: i32, color: Vector4<f32>, coverage: f32) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let alpha = (color.w * coverage).clamp(0.0, 1.0);
+        if alpha <= 0.0 {
+            return;
+        }
+        let pixel = &mut self.pixels[(y as u32 * self.width + x as u32) as usize];
+        for (dst, src) in pixel.iter_mut().zip([color.x, color.y, color.z]) {
+            *dst = *dst * (1.0 - alpha) + src * alpha;
+        }
+    }
+
+    /// Draws a line segment `width` pixels wide.
+    pub fn line(&mut self, a: Vector2<f32>, b: Vector2<f32>, width: f32, color: Vector4<f32>) {
+        if !(a.x.is_finite() && a.y.is_finite() && b.x.is_finite() && b.y.is_finite()) {
+            return;
+        }
+        let half_width = width * 0.5;
+        let pad = half_width + 1.0;
+        let min = vector![a.x.min(b.x) - pad, a.y.min(b.y) - pad];
+        let max = vector![a.x.max(b.x) + pad, a.y.max(b.y) + pad];
+        if max.x < 0.0 || max.y < 0.0 || min.x >= self.width as f32 || min.y >= self.height as f32 {
+            return;
+        }
+        let x0 = min.x.max(0.0) as i32;
+        let y0 = min.y.max(0.0) as i32;
+        let x1 = max.x.min(self.width as f32 - 1.0) as i32;
+        let y1 = max.y.min(self.height as f32 - 1.0) as i32;
+        let ab = b - a;
+        let length_squared = ab.norm_squared();
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                let p = vector![x as f32 + 0.5, y as f32 + 0.5];
+                let t = if length_squared > 0.0 {
+                    ((p - a).dot(&ab) / length_squared).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let distance = (p - (a + ab * t)).norm();
+                let coverage = (half_width + 0.5 - distance).clamp(0.0, 1.0);
+                self.blend(x, y, color, coverage);
+            }
+        }
+    }
+
+    /// Draws a closed polygon outline.
+    pub fn line_loop(&mut self, vertices: &[Vector2<f32>], width: f32, color: Vector4<f32>) {
+        for i in 0..vertices.len() {
+            let j = (i + 1) % vertices.len();
+            self.line(vertices[i], vertices[j], width, color);
+        }
+    }
+
+    /// Draws a filled circle.
+    pub fn disc(&mut self, center: Vector2<f32>, radius: f32, color: Vector4<f32>) {
+        self.line(center, center, radius * 2.0, color);
+    }
+
+    /// Fills a convex polygon. The vertices may be in either winding order.
+    pub fn convex_polygon(&mut self, vertices: &[Vector2<f32>], color: Vector4<f32>) {
+        if vertices.len() < 3
+            || vertices
+                .iter()
+                .any(|v| !(v.x.is_finite() && v.y.is_finite()))
+        {
+            return;
+        }
+        let min = vertices
+            .iter()
+            .fold(vector![f32::MAX, f32::MAX], |a, v| a.inf(v));
+        let max = vertices
+            .iter()
+            .fold(vector![f32::MIN, f32::MIN], |a, v| a.sup(v));
+        if max.x < 0.0 || max.y < 0.0 || min.x >= self.width as f32 || min.y >= self.height as f32 {
+            return;
+        }
+        let x0 = min.x.max(0.0) as i32;
+        let y0 = min.y.max(0.0) as i32;
+        let x1 = max.x.min(self.width as f32 - 1.0) as i32;
+        let y1 = max.y.min(self.height as f32 - 1.0) as i32;
+        let n = vertices.len();
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                let p = vector![x as f32 + 0.5, y as f32 + 0.5];
+                let sides = (0..n).map(|i| {
+                    let a = vertices[i];
+                    let b = vertices[(i + 1) % n];
+                    (b - a).perp(&(p - a))
+                });
+                let (mut positive, mut negative) = (false, false);
+                for side in sides {
+                    positive |= side > 0.0;
+                    negative |= side < 0.0;
+                }
+                if !(positive && negative) {
+                    self.blend(x, y, color, 1.0);
+                }
+            }
+        }
+    }
+
+    pub fn to_image(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.width, self.height, |x, y| {
+            let [r, g, b] = self.pixel(x, y);
+            let convert = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+            Rgba([convert(r), convert(g), convert(b), 255])
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const WHITE: Vector4<f32> = Vector4::new(1.0, 1.0, 1.0, 1.0);
+
+    #[test]
+    fn test_line() {
+        let mut canvas = Canvas::new(10, 10);
+        canvas.line(vector![0.0, 5.5], vector![10.0, 5.5], 1.0, WHITE);
+        assert_eq!(canvas.pixel(3, 5), [1.0; 3]);
+        assert_eq!(canvas.pixel(3, 2), [0.0; 3]);
+        assert_eq!(canvas.pixel(3, 8), [0.0; 3]);
+    }
+
+    #[test]
+    fn test_blend() {
+        let mut canvas = Canvas::new(1, 1);
+        canvas.blend(0, 0, vector![1.0, 0.0, 0.0, 0.5], 1.0);
+        assert_eq!(canvas.pixel(0, 0), [0.5, 0.0, 0.0]);
+        canvas.blend(0, 0, vector![1.0, 0.0, 0.0, 1.0], 0.0);
+        assert_eq!(canvas.pixel(0, 0), [0.5, 0.0, 0.0]);
+        canvas.blend(-1, 5, WHITE, 1.0);
+    }
+
+    #[test]
+    fn test_convex_polygon() {
+        let mut canvas = Canvas::new(10, 10);
+        let square = [
+            vector![2.0, 2.0],
+            vector![2.0, 6.0],
+            vector![6.0, 6.0],
+            vector![6.0, 2.0],
+        ];
+        canvas.convex_polygon(&square, WHITE);
+        assert_eq!(canvas.pixel(4, 4), [1.0; 3]);
+        assert_eq!(canvas.pixel(8, 4), [0.0; 3]);
+    }
+
+    #[test]
+    fn test_offscreen() {
+        let mut canvas = Canvas::new(10, 10);
+        canvas.line(vector![-100.0, -100.0], vector![-50.0, -50.0], 3.0, WHITE);
+        canvas.line(vector![f32::NAN, 0.0], vector![5.0, 5.0], 3.0, WHITE);
+        assert!(canvas.pixels.iter().all(|p| *p == [0.0; 3]));
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::Context;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, ImageFormat, RgbaImage};
+use std::io::Write;
+use std::path::Path;
+
+pub fn write_png(path: &Path, image: &RgbaImage) -> anyhow::Result<()> {
+    image
+        .save_with_format(path, ImageFormat::Png)
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+/// Writes frames to a looping animated GIF.
+pub struct GifWriter<W: Write> {
+    encoder: GifEncoder<W>,
+    delay: Delay,
+}
+
+impl<W: Write> GifWriter<W> {
+    pub fn new(writer: W, fps: u32) -> anyhow::Result<Self> {
+        let mut encoder = GifEncoder::new(writer);
+        encoder.set_repeat(Repeat::Infinite)?;
+        Ok(Self {
+            encoder,
+            delay: Delay::from_numer_denom_ms(1000, fps),
+        })
+    }
+
+    pub fn add_frame(&mut self, image: &RgbaImage) -> anyhow::Result<()> {
+        self.encoder
+            .encode_frame(Frame::from_parts(image.clone(), 0, 0, self.delay))?;
+        Ok(())
+    }
+
+    pub fn finish(self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes frames to a looping animated PNG.
+///
+/// APNG needs the number of frames up front. If fewer frames are added the
+/// last one is repeated.
+pub struct ApngWriter<W: Write> {
+    writer: png::Writer<W>,
+    remaining: u32,
+    last_frame: Option<Vec<u8>>,
+}
+
+impl<W: Write> ApngWriter<W> {
+    pub fn new(
+        writer: W,
+        width: u32,
+        height: u32,
+        num_frames: u32,
+        fps: u32,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(num_frames > 0, "APNG needs at least one frame");
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(num_frames, 0)?;
+        encoder.set_frame_delay(1, fps.min(u16::MAX as u32) as u16)?;
+        Ok(Self {
+            writer: encoder.write_header()?,
+            remaining: num_frames,
+            last_frame: None,
+        })
+    }
+
+    pub fn add_frame(&mut self, image: &RgbaImage) -> anyhow::Result<()> {
+        anyhow::ensure!(self.remaining > 0, "too many APNG frames");
+        self.writer.write_image_data(image.as_raw())?;
+        self.remaining -= 1;
+        self.last_frame = Some(image.as_raw().clone());
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> anyhow::Result<()> {
+        let last_frame = self.last_frame.take().context("no APNG frames")?;
+        while self.remaining > 0 {
+            self.writer.write_image_data(&last_frame)?;
+            self.remaining -= 1;
+        }
+        self.writer.finish()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use image::Rgba;
+
+    fn frames() -> Vec<RgbaImage> {
+        (0..3)
+            .map(|i| RgbaImage::from_pixel(4, 4, Rgba([i * 100, 0, 0, 255])))
+            .collect()
+    }
+
+    #[test]
+    fn test_gif() {
+        let mut data = vec![];
+        {
+            let mut writer = GifWriter::new(&mut data, 30).unwrap();
+            for frame in frames().iter() {
+                writer.add_frame(frame).unwrap();
+            }
+            writer.finish().unwrap();
+        }
+        assert!(data.starts_with(b"GIF89a"));
+    }
+
+    #[test]
+    fn test_apng() {
+        let mut data = vec![];
+        let mut writer = ApngWriter::new(&mut data, 4, 4, 5, 30).unwrap();
+        for frame in frames().iter() {
+            writer.add_frame(frame).unwrap();
+        }
+        writer.finish().unwrap();
+        assert!(data.starts_with(b"\x89PNG"));
+        assert!(data.windows(4).any(|w| w == b"acTL"));
+
+        let decoded = image::load_from_memory(&data).unwrap().to_rgba8();
+        assert_eq!(decoded, frames()[0]);
+    }
+}
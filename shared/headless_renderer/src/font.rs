@@ -0,0 +1,92 @@
+use crate::canvas::Canvas;
+use image::RgbaImage;
+use nalgebra::Vector4;
+
+const FONT_PNG: &[u8] = include_bytes!("../../../assets/null_terminator.png");
+const FONT_ROWS: usize = 12;
+const FONT_COLS: usize = 8;
+const FONT_GLYPH_SIZE: usize = 8;
+
+/// Bitmap font shared with the WebGL text renderer.
+pub struct Font {
+    atlas: RgbaImage,
+    scale: u32,
+}
+
+impl Font {
+    pub fn new(scale: u32) -> Self {
+        let atlas = image::load_from_memory(FONT_PNG)
+            .expect("decoding font")
+            .to_rgba8();
+        Self { atlas, scale }
+    }
+
+    /// Horizontal distance between glyphs in pixels.
+    pub fn advance(&self) -> i32 {
+        (FONT_GLYPH_SIZE as i32 + 1) * self.scale as i32
+    }
+
+    /// Height of a line of text in pixels.
+    pub fn line_height(&self) -> i32 {
+        self.advance()
+    }
+
+    /// Draws `text` with its top left corner at (`x`, `y`).
+    pub fn draw(&self, canvas: &mut Canvas, x: i32, y: i32, text: &str, color: Vector4<f32>) {
+        let mut pos_x = x;
+        let mut pos_y = y;
+        for c in text.bytes() {
+            if c == b'\n' {
+                pos_x = x;
+                pos_y += self.line_height();
+                continue;
+            }
+            self.draw_glyph(canvas, pos_x, pos_y, c, color);
+            pos_x += self.advance();
+        }
+    }
+
+    fn draw_glyph(&self, canvas: &mut Canvas, x: i32, y: i32, c: u8, color: Vector4<f32>) {
+        let idx = (c as usize)
+            .saturating_sub(32)
+            .min(FONT_ROWS * FONT_COLS - 1);
+        let base_x = (idx % FONT_COLS * FONT_GLYPH_SIZE) as u32;
+        let base_y = (idx / FONT_COLS * FONT_GLYPH_SIZE) as u32;
+        let size = FONT_GLYPH_SIZE as u32 - 1;
+        for gy in 0..size {
+            for gx in 0..size {
+                let texel = self.atlas.get_pixel(base_x + gx, base_y + gy);
+                let coverage = (texel[0] as f32 / 255.0) * (texel[3] as f32 / 255.0);
+                if coverage <= 0.0 {
+                    continue;
+                }
+                for sy in 0..self.scale {
+                    for sx in 0..self.scale {
+                        canvas.blend(
+                            x + (gx * self.scale + sx) as i32,
+                            y + (gy * self.scale + sy) as i32,
+                            color,
+                            coverage,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::vector;
+
+    #[test]
+    fn test_draw() {
+        let font = Font::new(1);
+        let mut canvas = Canvas::new(64, 16);
+        font.draw(&mut canvas, 0, 0, " ", vector![1.0, 1.0, 1.0, 1.0]);
+        assert!((0..16).all(|y| (0..64).all(|x| canvas.pixel(x, y) == [0.0; 3])));
+        font.draw(&mut canvas, 0, 0, "Oort", vector![1.0, 1.0, 1.0, 1.0]);
+        assert!((0..16).any(|y| (0..64).any(|x| canvas.pixel(x, y) != [0.0; 3])));
+    }
+}
@@ -0,0 +1,374 @@
+//! Software renderer for simulation snapshots.
+//!
+//! This draws roughly the same picture as the WebGL renderer in
+//! `frontend/renderer` without needing a browser, so that tools can produce
+//! images and clips of battles. See [encode] for writing PNG frames and
+//! animated GIF/APNG files.
+pub mod canvas;
+pub mod encode;
+pub mod font;
+
+use canvas::Canvas;
+use font::Font;
+use image::RgbaImage;
+use nalgebra::{point, vector, Point2, Rotation2, Vector2, Vector4};
+use oort_simulator::color;
+use oort_simulator::model;
+use oort_simulator::scenario::Status;
+use oort_simulator::ship::ShipClass;
+use oort_simulator::simulation::{Line, PHYSICS_TICK_LENGTH};
+use oort_simulator::snapshot::Snapshot;
+
+const BACKGROUND_COLOR: Vector4<f32> = Vector4::new(0.0, 0.0, 0.0, 1.0);
+const BOUNDARY_COLOR: Vector4<f32> = Vector4::new(0.3, 0.3, 0.3, 1.0);
+const STATUS_COLOR: Vector4<f32> = Vector4::new(0.7, 0.7, 0.7, 1.0);
+const LINE_WIDTH: f32 = 1.5;
+const MIN_SHIP_RADIUS: f32 = 3.0;
+const PARTICLE_RADIUS: f32 = 3.0;
+
+pub fn team_color(team: i32) -> Vector4<f32> {
+    match team {
+        0 => vector![0.99, 0.98, 0.00, 1.00],
+        1 => vector![0.99, 0.00, 0.98, 1.00],
+        2 => vector![0.13, 0.50, 0.73, 1.00],
+        3 => vector![0.20, 0.85, 0.30, 1.00],
+        9 => vector![0.40, 0.40, 0.40, 1.00],
+        _ => vector![1.0, 1.0, 1.0, 1.0],
+    }
+}
+
+/// Countermeasures and mines are drawn in their own color rather than the
+/// team's.
+pub fn class_color(class: ShipClass) -> Option<Vector4<f32>> {
+    match class {
+        ShipClass::Chaff => Some(vector![0.70, 0.70, 0.80, 0.60]),
+        ShipClass::Flare => Some(vector![1.00, 0.60, 0.20, 1.00]),
+        ShipClass::Mine => Some(vector![0.90, 0.20, 0.20, 1.00]),
+        _ => None,
+    }
+}
+
+/// Which part of the world is visible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub center: Point2<f64>,
+    /// Width of the visible area in meters.
+    pub view_width: f64,
+}
+
+impl Camera {
+    /// Shows the whole world.
+    pub fn world(snapshot: &Snapshot) -> Self {
+        Self {
+            center: point![0.0, 0.0],
+            view_width: snapshot.world_size,
+        }
+    }
+
+    /// Shows every ship, with `margin` meters of space around them.
+    pub fn fit_ships(snapshot: &Snapshot, aspect_ratio: f64, margin: f64) -> Self {
+        if snapshot.ships.is_empty() {
+            return Self::world(snapshot);
+        }
+        let mut min = vector![f64::INFINITY, f64::INFINITY];
+        let mut max = vector![f64::NEG_INFINITY, f64::NEG_INFINITY];
+        for ship in snapshot.ships.iter() {
+            min = min.inf(&ship.position.coords);
+            max = max.sup(&ship.position.coords);
+        }
+        let size = max - min + vector![margin, margin] * 2.0;
+        Self {
+            center: Point2::from((min + max) * 0.5),
+            view_width: size.x.max(size.y * aspect_ratio).min(snapshot.world_size),
+        }
+    }
+
+    /// Moves a fraction of the way towards `target`, for smooth camera motion.
+    pub fn approach(&self, target: &Camera, fraction: f64) -> Self {
+        Self {
+            center: self.center + (target.center - self.center) * fraction,
+            view_width: self.view_width + (target.view_width - self.view_width) * fraction,
+        }
+    }
+}
+
+/// Options controlling what is drawn.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// Draw debug lines and text from all ships.
+    pub debug: bool,
+    /// Draw the simulation time and status in the corner.
+    pub show_status: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            debug: false,
+            show_status: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Particle {
+    position: Vector2<f64>,
+    velocity: Vector2<f64>,
+    color: Vector4<f32>,
+    lifetime: f32,
+    creation_time: f64,
+}
+
+pub struct Renderer {
+    width: u32,
+    height: u32,
+    options: RenderOptions,
+    font: Font,
+    particles: Vec<Particle>,
+    furthest_snapshot_seen: f64,
+}
+
+impl Renderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            options: Default::default(),
+            font: Font::new(2),
+            particles: Vec::new(),
+            furthest_snapshot_seen: f64::NEG_INFINITY,
+        }
+    }
+
+    pub fn set_options(&mut self, options: RenderOptions) {
+        self.options = options;
+    }
+
+    pub fn aspect_ratio(&self) -> f64 {
+        self.width as f64 / self.height as f64
+    }
+
+    /// Records the particles emitted in `snapshot`.
+    ///
+    /// Particles live longer than the tick that created them, so this should be
+    /// called for every snapshot even if it isn't rendered.
+    pub fn update(&mut self, snapshot: &Snapshot) {
+        if snapshot.time <= self.furthest_snapshot_seen {
+            return;
+        }
+        self.particles
+            .retain(|p| snapshot.time <= p.creation_time + p.lifetime as f64);
+        for particle in snapshot.particles.iter() {
+            self.particles.push(Particle {
+                position: particle.position,
+                velocity: particle.velocity,
+                color: particle.color,
+                lifetime: particle.lifetime,
+                creation_time: snapshot.time,
+            });
+        }
+        self.furthest_snapshot_seen = snapshot.time;
+    }
+
+    pub fn render(&self, camera: &Camera, snapshot: &Snapshot) -> RgbaImage {
+        let mut canvas = Canvas::new(self.width, self.height);
+        canvas.clear(BACKGROUND_COLOR);
+        let view = View::new(camera, self.width, self.height);
+
+        self.draw_boundary(&mut canvas, &view, snapshot);
+        self.draw_fields(&mut canvas, &view, snapshot);
+        self.draw_particles(&mut canvas, &view, snapshot);
+        self.draw_bullets(&mut canvas, &view, snapshot);
+        self.draw_lines(&mut canvas, &view, &snapshot.scenario_lines);
+        self.draw_lines(&mut canvas, &view, &snapshot.beams);
+        if self.options.debug {
+            for (_, lines) in snapshot.debug_lines.iter() {
+                self.draw_lines(&mut canvas, &view, lines);
+            }
+        }
+        self.draw_ships(&mut canvas, &view, snapshot);
+        self.draw_texts(&mut canvas, &view, snapshot);
+        if self.options.show_status {
+            self.draw_status(&mut canvas, snapshot);
+        }
+
+        canvas.to_image()
+    }
+
+    fn draw_boundary(&self, canvas: &mut Canvas, view: &View, snapshot: &Snapshot) {
+        let s = snapshot.world_size / 2.0;
+        let corners: Vec<_> = [[-s, -s], [s, -s], [s, s], [-s, s]]
+            .iter()
+            .map(|&[x, y]| view.project(point![x, y]))
+            .collect();
+        canvas.line_loop(&corners, 1.0, BOUNDARY_COLOR);
+    }
+
+    fn draw_fields(&self, canvas: &mut Canvas, view: &View, snapshot: &Snapshot) {
+        for field in snapshot.fields.iter() {
+            let vertices: Vec<_> = field.vertices.iter().map(|&v| view.project(v)).collect();
+            canvas.convex_polygon(&vertices, field.color);
+        }
+    }
+
+    fn draw_ships(&self, canvas: &mut Canvas, view: &View, snapshot: &Snapshot) {
+        for ship in snapshot.ships.iter() {
+            let mut color = class_color(ship.class).unwrap_or_else(|| team_color(ship.team));
+            if ship.active_abilities.contains(&oort_api::Ability::Shield) {
+                let frac = (snapshot.time as f32 * 30.0).sin() * 0.2 + 0.5;
+                color = color * (1.0 - frac) + vector![0.0, 0.0, 1.0, 1.0] * frac;
+            }
+            let center = view.project(ship.position);
+            let radius = model::radius(ship.class) * view.scale;
+            if radius < MIN_SHIP_RADIUS {
+                canvas.disc(center, MIN_SHIP_RADIUS * 0.5, color);
+                continue;
+            }
+            let rotation = Rotation2::new(ship.heading);
+            let vertices: Vec<_> = model::load(ship.class)
+                .iter()
+                .map(|v| view.project(ship.position + rotation * v.cast::<f64>()))
+                .collect();
+            canvas.line_loop(&vertices, LINE_WIDTH, color);
+        }
+    }
+
+    fn draw_bullets(&self, canvas: &mut Canvas, view: &View, snapshot: &Snapshot) {
+        let dt = PHYSICS_TICK_LENGTH;
+        for bullet in snapshot.bullets.iter() {
+            let mut color = color::from_u32(bullet.color);
+            if bullet.ttl < 0.3 {
+                color.w *= bullet.ttl + 0.3;
+            }
+            let a = view.project(bullet.position - 2.0 * bullet.velocity * dt);
+            let b = view.project(bullet.position);
+            canvas.line(a, b, LINE_WIDTH, color);
+        }
+    }
+
+    fn draw_particles(&self, canvas: &mut Canvas, view: &View, snapshot: &Snapshot) {
+        for particle in self.particles.iter() {
+            let dt = snapshot.time - particle.creation_time;
+            if dt < 0.0 {
+                continue;
+            }
+            let life_fraction = (dt as f32 / particle.lifetime).clamp(0.0, 1.0);
+            let mut color = particle.color;
+            color.w *= 1.0 - life_fraction;
+            let position = particle.position + particle.velocity * dt;
+            canvas.disc(
+                view.project(Point2::from(position)),
+                PARTICLE_RADIUS * (1.0 - life_fraction),
+                color,
+            );
+        }
+    }
+
+    fn draw_lines(&self, canvas: &mut Canvas, view: &View, lines: &[Line]) {
+        for line in lines.iter() {
+            canvas.line(view.project(line.a), view.project(line.b), 1.0, line.color);
+        }
+    }
+
+    fn draw_texts(&self, canvas: &mut Canvas, view: &View, snapshot: &Snapshot) {
+        for (ship, texts) in snapshot.drawn_text.iter() {
+            if ship.is_some() && !self.options.debug {
+                continue;
+            }
+            for text in texts.iter() {
+                let length = (text.length as usize).min(text.text.len());
+                let s = String::from_utf8_lossy(&text.text[..length]);
+                let p = view.project(point![text.x, text.y]);
+                self.font.draw(
+                    canvas,
+                    p.x.floor() as i32,
+                    p.y.floor() as i32 - self.font.line_height(),
+                    &s,
+                    color::from_u24(text.color),
+                );
+            }
+        }
+    }
+
+    fn draw_status(&self, canvas: &mut Canvas, snapshot: &Snapshot) {
+        let mut status = format!("{:.2}s", snapshot.time);
+        match snapshot.status {
+            Status::Running => {}
+            Status::Victory { team } => status.push_str(&format!(" Team {team} wins")),
+            Status::Failed => status.push_str(" Failed"),
+            Status::Draw => status.push_str(" Draw"),
+        }
+        self.font.draw(canvas, 8, 8, &status, STATUS_COLOR);
+    }
+}
+
+/// Maps world coordinates to pixels.
+struct View {
+    center: Point2<f64>,
+    scale: f32,
+    half_size: Vector2<f32>,
+}
+
+impl View {
+    fn new(camera: &Camera, width: u32, height: u32) -> Self {
+        Self {
+            center: camera.center,
+            scale: (width as f64 / camera.view_width) as f32,
+            half_size: vector![width as f32, height as f32] * 0.5,
+        }
+    }
+
+    fn project(&self, p: Point2<f64>) -> Vector2<f32> {
+        let d = (p - self.center).cast::<f32>() * self.scale;
+        // Flip y so that +y points up as in the game.
+        vector![self.half_size.x + d.x, self.half_size.y - d.y]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use oort_simulator::simulation::{Code, Simulation};
+
+    #[test]
+    fn test_project() {
+        let camera = Camera {
+            center: point![100.0, 100.0],
+            view_width: 1000.0,
+        };
+        let view = View::new(&camera, 100, 50);
+        assert_eq!(view.project(point![100.0, 100.0]), vector![50.0, 25.0]);
+        assert_eq!(view.project(point![200.0, 200.0]), vector![60.0, 15.0]);
+    }
+
+    #[test]
+    fn test_fit_ships() {
+        let sim = Simulation::new("fighter_duel", 0, &[Code::None, Code::None]);
+        let snapshot = sim.snapshot(0);
+        let camera = Camera::fit_ships(&snapshot, 1.0, 100.0);
+        let view = View::new(&camera, 100, 100);
+        for ship in snapshot.ships.iter() {
+            let p = view.project(ship.position);
+            assert!((0.0..100.0).contains(&p.x) && (0.0..100.0).contains(&p.y));
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let mut sim = Simulation::new("fighter_duel", 0, &[Code::None, Code::None]);
+        let mut renderer = Renderer::new(320, 240);
+        for _ in 0..10 {
+            sim.step();
+            renderer.update(&sim.snapshot(0));
+        }
+        let snapshot = sim.snapshot(0);
+        let camera = Camera::fit_ships(&snapshot, renderer.aspect_ratio(), 500.0);
+        let image = renderer.render(&camera, &snapshot);
+        assert_eq!(image.dimensions(), (320, 240));
+        let yellow = image
+            .pixels()
+            .filter(|p| p[0] > 200 && p[1] > 200 && p[2] < 50)
+            .count();
+        assert!(yellow > 0);
+    }
+}
@@ -30,6 +30,8 @@ pub enum Telemetry {
         code_size: usize,
         success: bool,
         time: Option<f64>,
+        #[serde(default)]
+        gas: Option<u64>,
     },
     Crash {
         msg: String,
@@ -46,6 +48,85 @@ pub enum Telemetry {
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct LeaderboardData {
     pub lowest_time: Vec<TimeLeaderboardRow>,
+    #[serde(default)]
+    pub smallest_code_size: Vec<TimeLeaderboardRow>,
+    #[serde(default)]
+    pub lowest_gas: Vec<TimeLeaderboardRow>,
+}
+
+impl LeaderboardData {
+    pub fn rows(&self, category: LeaderboardCategory) -> &Vec<TimeLeaderboardRow> {
+        match category {
+            LeaderboardCategory::Time => &self.lowest_time,
+            LeaderboardCategory::CodeSize => &self.smallest_code_size,
+            LeaderboardCategory::Gas => &self.lowest_gas,
+        }
+    }
+
+    pub fn rows_mut(&mut self, category: LeaderboardCategory) -> &mut Vec<TimeLeaderboardRow> {
+        match category {
+            LeaderboardCategory::Time => &mut self.lowest_time,
+            LeaderboardCategory::CodeSize => &mut self.smallest_code_size,
+            LeaderboardCategory::Gas => &mut self.lowest_gas,
+        }
+    }
+}
+
+/// Each category is a separate board ranking a different metric of passing solutions.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardCategory {
+    #[default]
+    Time,
+    CodeSize,
+    Gas,
+}
+
+impl LeaderboardCategory {
+    pub const ALL: [LeaderboardCategory; 3] = [
+        LeaderboardCategory::Time,
+        LeaderboardCategory::CodeSize,
+        LeaderboardCategory::Gas,
+    ];
+
+    /// Name used in backend routes.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LeaderboardCategory::Time => "time",
+            LeaderboardCategory::CodeSize => "code_size",
+            LeaderboardCategory::Gas => "gas",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|x| x.name() == name)
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            LeaderboardCategory::Time => "Time",
+            LeaderboardCategory::CodeSize => "Code Size",
+            LeaderboardCategory::Gas => "CPU",
+        }
+    }
+
+    /// Firestore collection holding each user's best submission for this category.
+    pub fn collection(&self) -> &'static str {
+        match self {
+            LeaderboardCategory::Time => "leaderboard",
+            LeaderboardCategory::CodeSize => "leaderboard_code_size",
+            LeaderboardCategory::Gas => "leaderboard_gas",
+        }
+    }
+
+    /// Field of `LeaderboardSubmission` that submissions are ranked by.
+    pub fn field(&self) -> &'static str {
+        match self {
+            LeaderboardCategory::Time => "time",
+            LeaderboardCategory::CodeSize => "code_size",
+            LeaderboardCategory::Gas => "gas",
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
@@ -58,6 +139,10 @@ pub struct TimeLeaderboardRow {
     pub time_float: Option<f64>,
     pub shortcode: Option<String>,
     pub rescored_version: Option<String>,
+    #[serde(default)]
+    pub code_size: Option<usize>,
+    #[serde(default)]
+    pub gas: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -72,10 +157,23 @@ pub struct LeaderboardSubmission {
     pub code_size: usize,
     pub code: String,
     pub rescored_version: Option<String>,
+    #[serde(default)]
+    pub gas: Option<u64>,
 }
 
 impl Eq for LeaderboardSubmission {}
 
+impl LeaderboardSubmission {
+    /// Returns the value this submission is ranked by in `category`, lower is better.
+    pub fn score(&self, category: LeaderboardCategory) -> Option<f64> {
+        match category {
+            LeaderboardCategory::Time => Some(self.time),
+            LeaderboardCategory::CodeSize => Some(self.code_size as f64),
+            LeaderboardCategory::Gas => self.gas.map(|x| x as f64),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct TournamentSubmission {
     pub scenario_name: String,
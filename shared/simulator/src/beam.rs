@@ -0,0 +1,147 @@
+//! Hitscan beam weapons.
+//!
+//! A beam gun resolves its shot instantly with a ray cast instead of spawning
+//! a bullet. Damage falls off linearly with range and each shot adds heat; a
+//! gun that would go over its heat limit refuses to fire until it cools down.
+use crate::collision;
+use crate::debug::Line;
+use crate::index_set::HasIndex;
+use crate::obstacle;
+use crate::ship::ShipHandle;
+use crate::simulation::{Particle, Simulation, PHYSICS_TICK_LENGTH};
+use crate::stats;
+use nalgebra::{vector, Point2, Rotation2, Vector2, Vector4};
+use oort_api::Ability;
+use rand::RngExt;
+use rapier2d_f64::prelude::*;
+use std::f64::consts::TAU;
+
+const MAX_HEAT: f64 = 1.0;
+const COLOR: Vector4<f32> = Vector4::new(0.5, 1.0, 1.0, 0.8);
+
+#[derive(Debug, Clone)]
+pub struct Beam {
+    /// Damage per shot at point blank range.
+    pub damage: f64,
+    /// Damage falls off linearly to zero at this range.
+    pub range: f64,
+    /// Heat added by each shot, as a fraction of the limit.
+    pub heat_per_shot: f64,
+    /// Heat removed every tick.
+    pub cooling: f64,
+    pub heat: f64,
+}
+
+impl Beam {
+    /// Adds the heat for one shot. Returns false if the beam is too hot to fire.
+    pub(crate) fn try_heat(&mut self) -> bool {
+        if self.heat + self.heat_per_shot > MAX_HEAT {
+            return false;
+        }
+        self.heat += self.heat_per_shot;
+        true
+    }
+
+    pub(crate) fn cool(&mut self) {
+        self.heat = (self.heat - self.cooling).max(0.0);
+    }
+}
+
+/// Fires a beam from `origin` along `direction` and damages the first ship
+/// it hits. Returns the ship that was hit, if any.
+pub fn fire(
+    sim: &mut Simulation,
+    team: i32,
+    shooter: Option<ShipHandle>,
+    origin: Vector2<f64>,
+    direction: Vector2<f64>,
+    beam: &Beam,
+) -> Option<ShipHandle> {
+    let ray = Ray::new(Point2::from(origin), direction.normalize());
+    let groups = collision::bullet_interaction_groups(team);
+    let range = obstacle::cast_ray(sim, &ray, beam.range).unwrap_or(beam.range);
+
+    let mut closest: Option<(ShipHandle, f64)> = None;
+    for &handle in sim.ships.iter() {
+        let body = sim.ship(handle).body();
+        let Some(&collider_handle) = body.colliders().first() else {
+            continue;
+        };
+        let collider = &sim.colliders[collider_handle];
+        if !groups.test(collider.collision_groups()) {
+            continue;
+        }
+        let max_toi = closest.map(|(_, toi)| toi).unwrap_or(range);
+        if let Some(toi) = collider
+            .shape()
+            .cast_ray(collider.position(), &ray, max_toi, true)
+        {
+            closest = Some((handle, toi));
+        }
+    }
+
+    let toi = closest.map(|(_, toi)| toi).unwrap_or(range);
+    let end = ray.point_at(toi);
+    sim.events.beams.push(Line {
+        a: ray.origin,
+        b: end,
+        color: COLOR,
+    });
+
+    let (handle, toi) = closest?;
+    let damage = beam.damage * (1.0 - toi / beam.range);
+    for _ in 0..3 {
+        let rot = Rotation2::new(sim.rng.random_range(0.0..TAU));
+        let v = rot.transform_vector(&vector![sim.rng.random_range(0.0..200.0), 0.0]);
+        sim.events.particles.push(Particle {
+            position: end.coords,
+            velocity: v,
+            color: COLOR,
+            lifetime: (PHYSICS_TICK_LENGTH * 10.0) as f32,
+        });
+    }
+    if sim.ship(handle).is_ability_active(Ability::Shield) {
+        return Some(handle);
+    }
+    let ship_data = sim.ship_data.get_mut(handle.index()).unwrap();
+    ship_data.health -= damage;
+    if ship_data.health <= 0.0 {
+        ship_data.destroyed = true;
+    }
+    stats::record_damage(sim, shooter, handle, damage);
+    Some(handle)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use test_log::test;
+
+    fn beam() -> Beam {
+        Beam {
+            damage: 1.0,
+            range: 1000.0,
+            heat_per_shot: 0.25,
+            cooling: 0.1,
+            heat: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_heat() {
+        let mut beam = beam();
+        for _ in 0..4 {
+            assert!(beam.try_heat());
+        }
+        assert!(!beam.try_heat());
+        beam.cool();
+        assert!(!beam.try_heat());
+        beam.cool();
+        beam.cool();
+        assert!(beam.try_heat());
+        for _ in 0..20 {
+            beam.cool();
+        }
+        assert_eq!(beam.heat, 0.0);
+    }
+}
@@ -0,0 +1,163 @@
+//! Shrinking arena boundary.
+//!
+//! A scenario can surround the arena with a circular boundary centered on
+//! the origin that shrinks over time. Ships outside it lose a fraction of
+//! their maximum health every second, which forces long stalemates to a
+//! conclusion.
+use crate::debug::Line;
+use crate::index_set::HasIndex;
+use crate::ship::ShipClass;
+use crate::simulation::{Simulation, PHYSICS_TICK_LENGTH};
+use crate::stats;
+use nalgebra::{point, vector, Vector4};
+use std::f64::consts::TAU;
+
+const COLOR: Vector4<f32> = Vector4::new(1.0, 0.2, 0.2, 0.8);
+const NEXT_COLOR: Vector4<f32> = Vector4::new(1.0, 0.2, 0.2, 0.3);
+
+#[derive(Clone, Debug)]
+pub struct Boundary {
+    /// Radius at each keyframe, sorted by tick. The radius changes linearly
+    /// between keyframes.
+    stages: Vec<(u32, f64)>,
+    /// Fraction of a ship's maximum health lost per second outside the boundary.
+    pub damage: f64,
+}
+
+impl Boundary {
+    pub fn new(mut stages: Vec<(u32, f64)>, damage: f64) -> Self {
+        assert!(!stages.is_empty());
+        stages.sort_by_key(|&(tick, _)| tick);
+        Self { stages, damage }
+    }
+
+    pub fn radius(&self, tick: u32) -> f64 {
+        let next = self.stages.iter().position(|&(t, _)| t > tick);
+        match next {
+            Some(0) => self.stages[0].1,
+            Some(i) => {
+                let (t0, r0) = self.stages[i - 1];
+                let (t1, r1) = self.stages[i];
+                let frac = (tick - t0) as f64 / (t1 - t0) as f64;
+                r0 + (r1 - r0) * frac
+            }
+            None => self.stages.last().unwrap().1,
+        }
+    }
+
+    /// Radius the boundary is currently shrinking towards, or will shrink to
+    /// next.
+    pub fn next_radius(&self, tick: u32) -> f64 {
+        let current = self.radius(tick);
+        self.stages
+            .iter()
+            .filter(|&&(t, _)| t > tick)
+            .map(|&(_, r)| r)
+            .find(|&r| r < current)
+            .unwrap_or(current)
+    }
+}
+
+pub(crate) fn tick(sim: &mut Simulation) {
+    let Some(boundary) = sim.boundary() else {
+        return;
+    };
+    let radius = boundary.radius(sim.tick());
+    let damage = boundary.damage * PHYSICS_TICK_LENGTH;
+
+    let handles: Vec<_> = sim.ships.iter().cloned().collect();
+    for handle in handles {
+        let ship = sim.ship(handle);
+        if !matches!(
+            ship.data().class,
+            ShipClass::Fighter
+                | ShipClass::Frigate
+                | ShipClass::Cruiser
+                | ShipClass::Missile
+                | ShipClass::Torpedo
+        ) || ship.position().vector.norm() <= radius
+        {
+            continue;
+        }
+        let ship_data = sim.ship_data.get_mut(handle.index()).unwrap();
+        let ship_damage = ship_data.max_health * damage;
+        ship_data.health -= ship_damage;
+        if ship_data.health <= 0.0 {
+            ship_data.destroyed = true;
+        }
+        stats::record_damage(sim, None, handle, ship_damage);
+    }
+}
+
+/// Outlines of the current and next boundary.
+pub fn lines(sim: &Simulation) -> Vec<Line> {
+    let Some(boundary) = sim.boundary() else {
+        return vec![];
+    };
+    let current = boundary.radius(sim.tick());
+    let next = boundary.next_radius(sim.tick());
+    let mut outlines = vec![(current, COLOR)];
+    if next < current {
+        outlines.push((next, NEXT_COLOR));
+    }
+    let n = 128;
+    let mut lines = vec![];
+    for (radius, color) in outlines {
+        for i in 0..n {
+            let angle_a = TAU * i as f64 / n as f64;
+            let angle_b = TAU * (i + 1) as f64 / n as f64;
+            lines.push(Line {
+                a: point![0.0, 0.0] + vector![angle_a.cos(), angle_a.sin()] * radius,
+                b: point![0.0, 0.0] + vector![angle_b.cos(), angle_b.sin()] * radius,
+                color,
+            });
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ship;
+    use crate::simulation::Code;
+    use test_log::test;
+
+    #[test]
+    fn test_schedule() {
+        let boundary = Boundary::new(vec![(0, 1000.0), (100, 1000.0), (200, 500.0)], 0.1);
+        assert_eq!(boundary.radius(0), 1000.0);
+        assert_eq!(boundary.next_radius(0), 500.0);
+        assert_eq!(boundary.radius(150), 750.0);
+        assert_eq!(boundary.next_radius(150), 500.0);
+        assert_eq!(boundary.radius(1000), 500.0);
+        assert_eq!(boundary.next_radius(1000), 500.0);
+    }
+
+    #[test]
+    fn test_damage() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let radius = 1000.0;
+        sim.boundary = Some(Boundary::new(vec![(0, radius)], 0.1));
+
+        let inside = ship::create(
+            &mut sim,
+            vector![radius - 100.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let outside = ship::create(
+            &mut sim,
+            vector![0.0, radius + 100.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        for _ in 0..60 {
+            sim.step();
+        }
+        assert_eq!(sim.ship(inside).data().health, 100.0);
+        approx::assert_abs_diff_eq!(sim.ship(outside).data().health, 90.0, epsilon = 1e-6);
+    }
+}
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
 use super::index_set::{HasIndex, Index};
+use crate::ship::ShipHandle;
 use crate::simulation::{Simulation, MAX_WORLD_SIZE, PHYSICS_TICK_LENGTH};
-use crate::{collision, simulation};
+use crate::topology::Topology;
+use crate::{collision, obstacle, simulation, topology};
 use bitvec::vec::BitVec;
 use nalgebra::Vector2;
 use rapier2d_f64::prelude::*;
@@ -31,6 +33,10 @@ pub struct BulletData {
     pub team: i32,
     pub ttl: f32,
     pub color: u32,
+    /// Ship credited with the damage in the match statistics.
+    pub shooter: Option<ShipHandle>,
+    /// Whether this is a warhead fragment rather than a gun round.
+    pub fragment: bool,
 }
 
 pub fn body(sim: &Simulation, handle: BulletHandle) -> &RigidBody {
@@ -109,6 +115,24 @@ pub fn tick(sim: &mut Simulation) {
         }
 
         let team = data.team;
+
+        // Bullets only get colliders near enemy ships, so check for obstacles
+        // in their path directly.
+        if !sim.obstacles.is_empty() {
+            let (position, velocity) = {
+                let body = body(sim, handle);
+                (*body.translation(), *body.linvel())
+            };
+            let speed = velocity.norm();
+            if speed > 0.0 {
+                let ray = Ray::new(position.into(), velocity / speed);
+                if obstacle::cast_ray(sim, &ray, speed * dt).is_some() {
+                    destroy(sim, handle);
+                    continue;
+                }
+            }
+        }
+
         let has_collider;
         let coarse_grid_hit;
         let mut needs_collider = false;
@@ -117,14 +141,18 @@ pub fn tick(sim: &mut Simulation) {
             let body = sim.bodies.get_mut(RigidBodyHandle(handle.index())).unwrap();
             has_collider = !body.colliders().is_empty();
 
-            // If bullet is outside world, destroy it
+            // If bullet is outside world, destroy it or wrap it around
             let position = *body.translation();
             if position.x < -world_size / 2.0
                 || position.x > world_size / 2.0
                 || position.y < -world_size / 2.0
                 || position.y > world_size / 2.0
             {
-                destroy(sim, handle);
+                if sim.topology() == Topology::Toroidal {
+                    topology::wrap_bullet(sim, handle);
+                } else {
+                    destroy(sim, handle);
+                }
                 continue;
             }
 
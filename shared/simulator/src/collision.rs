@@ -2,6 +2,7 @@ use crate::bullet::{self, BulletHandle};
 use crate::index_set::HasIndex;
 use crate::ship::{ShipClass, ShipHandle};
 use crate::simulation::{Particle, Simulation, PHYSICS_TICK_LENGTH};
+use crate::stats;
 use nalgebra::{ComplexField, Rotation2, UnitComplex};
 use oort_api::Ability;
 use rand::RngExt;
@@ -12,6 +13,7 @@ const DAMAGE_FACTOR: f64 = 0.00014;
 const WALL_COLLISION_GROUP: Group = Group::GROUP_1;
 const SHIP_COLLISION_GROUP: Group = Group::GROUP_2;
 const PLANET_COLLISION_GROUP: Group = Group::GROUP_3;
+const OBSTACLE_COLLISION_GROUP: Group = Group::GROUP_15;
 const BULLET_GROUPS: &[Group] = &[
     Group::GROUP_4,
     Group::GROUP_5,
@@ -38,7 +40,10 @@ fn all_bullet_groups() -> Group {
 pub fn bullet_interaction_groups(team: i32) -> InteractionGroups {
     InteractionGroups::new(
         bullet_group(team),
-        WALL_COLLISION_GROUP | SHIP_COLLISION_GROUP | PLANET_COLLISION_GROUP,
+        WALL_COLLISION_GROUP
+            | SHIP_COLLISION_GROUP
+            | PLANET_COLLISION_GROUP
+            | OBSTACLE_COLLISION_GROUP,
     )
 }
 
@@ -53,7 +58,11 @@ pub fn ship_interaction_groups(team: i32) -> InteractionGroups {
     let bullet_groups = all_bullet_groups() ^ bullet_group(team);
     InteractionGroups::new(
         SHIP_COLLISION_GROUP,
-        WALL_COLLISION_GROUP | SHIP_COLLISION_GROUP | PLANET_COLLISION_GROUP | bullet_groups,
+        WALL_COLLISION_GROUP
+            | SHIP_COLLISION_GROUP
+            | PLANET_COLLISION_GROUP
+            | OBSTACLE_COLLISION_GROUP
+            | bullet_groups,
     )
 }
 
@@ -65,12 +74,80 @@ pub fn planet_interaction_groups() -> InteractionGroups {
     )
 }
 
+pub fn obstacle_interaction_groups() -> InteractionGroups {
+    InteractionGroups::new(
+        OBSTACLE_COLLISION_GROUP,
+        SHIP_COLLISION_GROUP | all_bullet_groups(),
+    )
+}
+
 pub fn beacon_interaction_groups() -> InteractionGroups {
     InteractionGroups::new(Group::GROUP_14, Group::empty())
 }
 
-pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
-    for event in events {
+/// Applies damage to both ships based on the kinetic energy of their relative
+/// motion along the contact normal.
+fn handle_ship_impact(sim: &mut Simulation, ships: [ShipHandle; 2], impact_speed: f64) {
+    let classes = ships.map(|ship| sim.ship(ship).data().class);
+    if classes.iter().any(|class| {
+        matches!(
+            class,
+            ShipClass::Missile | ShipClass::Torpedo | ShipClass::Mine
+        )
+    }) {
+        return;
+    }
+    // Planets don't move, so they act as if they had infinite mass.
+    let inv_mass: f64 = ships
+        .iter()
+        .zip(classes.iter())
+        .map(|(&ship, &class)| {
+            if class == ShipClass::Planet {
+                0.0
+            } else {
+                1.0 / sim.ship(ship).data().mass
+            }
+        })
+        .sum();
+    if inv_mass == 0.0 {
+        return;
+    }
+    let energy = 0.5 * impact_speed * impact_speed / inv_mass;
+    let damage = energy * DAMAGE_FACTOR;
+    for (ship, class) in ships.into_iter().zip(classes) {
+        if class == ShipClass::Planet {
+            continue;
+        }
+        let ship_data = sim.ship_data.get_mut(ship.index()).unwrap();
+        ship_data.health -= damage;
+        if ship_data.health <= 0.0 {
+            ship_data.destroyed = true;
+        }
+        stats::record_damage(sim, None, ship, damage);
+    }
+}
+
+/// Applies damage to a ship that ran into an obstacle. Obstacles don't move,
+/// so the ship takes the full kinetic energy of the impact.
+fn handle_obstacle_impact(sim: &mut Simulation, ship: ShipHandle, impact_speed: f64) {
+    if matches!(
+        sim.ship(ship).data().class,
+        ShipClass::Missile | ShipClass::Torpedo | ShipClass::Planet
+    ) {
+        return;
+    }
+    let ship_data = sim.ship_data.get_mut(ship.index()).unwrap();
+    let energy = 0.5 * ship_data.mass * impact_speed * impact_speed;
+    let damage = energy * DAMAGE_FACTOR;
+    ship_data.health -= damage;
+    if ship_data.health <= 0.0 {
+        ship_data.destroyed = true;
+    }
+    stats::record_damage(sim, None, ship, damage);
+}
+
+pub fn handle_collisions(sim: &mut Simulation, events: &[(CollisionEvent, f64)]) {
+    for (event, impact_speed) in events {
         if let CollisionEvent::Started(h1, h2, _flags) = event {
             let get_index = |h| sim.colliders.get(h).and_then(|x| x.parent()).map(|x| x.0);
             let handle_hit = |sim: &mut Simulation, ship, bullet: BulletHandle| {
@@ -91,6 +168,7 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
                         );
                     }
                     bullet::data_mut(sim, bullet).team = sim.ship(ship).data().team;
+                    bullet::data_mut(sim, bullet).shooter = Some(ship);
                     return;
                 }
                 if bullet::data(sim, bullet).team == sim.ship(ship).data().team {
@@ -116,6 +194,15 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
                     ship_data.health -= damage;
                     ship_data.health <= 0.0
                 };
+                let (shooter, fragment) = {
+                    let data = bullet::data(sim, bullet);
+                    (data.shooter, data.fragment)
+                };
+                if fragment {
+                    stats::record_fragment_damage(sim, shooter, ship, damage);
+                } else {
+                    stats::record_damage(sim, shooter, ship, damage);
+                }
                 if ship_destroyed {
                     for _ in 0..10 {
                         let rot = Rotation2::new(sim.rng.random_range(0.0..TAU));
@@ -146,6 +233,7 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
                 enum Collider {
                     Bullet(BulletHandle),
                     Ship(ShipHandle),
+                    Obstacle,
                     Wall,
                 }
                 let classify_collider = |idx| {
@@ -153,6 +241,8 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
                         Collider::Bullet(BulletHandle(idx))
                     } else if sim.ships.contains(ShipHandle(idx)) {
                         Collider::Ship(ShipHandle(idx))
+                    } else if sim.obstacles.iter().any(|obstacle| obstacle.body.0 == idx) {
+                        Collider::Obstacle
                     } else {
                         Collider::Wall
                     }
@@ -163,14 +253,23 @@ pub fn handle_collisions(sim: &mut Simulation, events: &[CollisionEvent]) {
                     [Collider::Bullet(b), Collider::Ship(s)] => {
                         handle_hit(sim, s, b);
                     }
-                    [Collider::Bullet(b), Collider::Wall] => {
+                    [Collider::Bullet(b), Collider::Obstacle | Collider::Wall] => {
                         bullet::destroy(sim, b);
                     }
                     [Collider::Ship(s1), Collider::Ship(s2)]
                         if sim.ship(s1).data().team != sim.ship(s2).data().team =>
                     {
-                        sim.ship_mut(s1).handle_collision();
-                        sim.ship_mut(s2).handle_collision();
+                        if sim.collision_damage() {
+                            handle_ship_impact(sim, [s1, s2], *impact_speed);
+                        }
+                        sim.ship_mut(s1).handle_collision(Some(s2));
+                        sim.ship_mut(s2).handle_collision(Some(s1));
+                    }
+                    [Collider::Ship(s), Collider::Obstacle] => {
+                        if sim.collision_damage() {
+                            handle_obstacle_impact(sim, s, *impact_speed);
+                        }
+                        sim.ship_mut(s).handle_collision(None);
                     }
                     [Collider::Ship(s), Collider::Wall]
                         if sim.ship(s).data().class != ShipClass::Planet =>
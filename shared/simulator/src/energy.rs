@@ -0,0 +1,271 @@
+//! Ship energy budget.
+//!
+//! Fighters, frigates and cruisers carry a reactor and a battery. Radars
+//! (more so when jamming), reloading guns and the Boost and Shield abilities
+//! draw energy every tick. The power allocation chosen by the AI guarantees
+//! each subsystem a share of the reactor output and any shortfall comes out
+//! of the battery. Once the battery is empty an underpowered subsystem is
+//! degraded: radars lose power, guns reload more slowly and abilities shut
+//! off.
+use crate::ship::ShipData;
+use crate::simulation::PHYSICS_TICK_LENGTH;
+use oort_api::EcmMode;
+
+/// Watts drawn per unit of radar power.
+pub const RADAR_POWER_COST: f64 = 10.0;
+/// Extra draw multiplier for a radar that is jamming.
+pub const JAMMING_POWER_FACTOR: f64 = 2.0;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Subsystem {
+    Weapons,
+    Sensors,
+    Engines,
+}
+
+const NUM_SUBSYSTEMS: usize = 3;
+
+#[derive(Clone, Debug)]
+pub struct Energy {
+    /// Reactor output in watts.
+    pub reactor: f64,
+    /// Battery capacity in joules.
+    pub capacity: f64,
+    /// Energy stored in the battery in joules.
+    pub stored: f64,
+    /// Share of reactor output reserved for each subsystem. Sums to 1.
+    allocation: [f64; NUM_SUBSYSTEMS],
+    /// Fraction of each subsystem's demand supplied during the last tick.
+    supplied: [f64; NUM_SUBSYSTEMS],
+    /// Fractional gun reload ticks accumulated while underpowered.
+    reload_progress: f64,
+}
+
+impl Energy {
+    pub fn new(reactor: f64, capacity: f64) -> Self {
+        Self {
+            reactor,
+            capacity,
+            stored: capacity,
+            allocation: [1.0 / NUM_SUBSYSTEMS as f64; NUM_SUBSYSTEMS],
+            supplied: [1.0; NUM_SUBSYSTEMS],
+            reload_progress: 0.0,
+        }
+    }
+
+    /// Sets the relative share of reactor output for each subsystem.
+    ///
+    /// The shares are normalized. Invalid or all-zero shares split the output
+    /// evenly.
+    pub fn set_allocation(&mut self, weapons: f64, sensors: f64, engines: f64) {
+        let mut allocation =
+            [weapons, sensors, engines].map(|x| if x.is_finite() && x > 0.0 { x } else { 0.0 });
+        let total: f64 = allocation.iter().sum();
+        if total > 0.0 && total.is_finite() {
+            allocation.iter_mut().for_each(|x| *x /= total);
+        } else {
+            allocation = [1.0 / NUM_SUBSYSTEMS as f64; NUM_SUBSYSTEMS];
+        }
+        self.allocation = allocation;
+    }
+
+    pub fn allocation(&self, subsystem: Subsystem) -> f64 {
+        self.allocation[subsystem as usize]
+    }
+
+    /// Fraction of the subsystem's demand that was met during the last tick.
+    pub fn supplied(&self, subsystem: Subsystem) -> f64 {
+        self.supplied[subsystem as usize]
+    }
+
+    /// Draws `demand` joules for each subsystem from the reactor and battery.
+    fn consume(&mut self, demand: [f64; NUM_SUBSYSTEMS]) {
+        let output = self.reactor * PHYSICS_TICK_LENGTH;
+        let reserved: [f64; NUM_SUBSYSTEMS] =
+            std::array::from_fn(|i| demand[i].min(output * self.allocation[i]));
+        // Unused shares are available to other subsystems before they
+        // recharge the battery.
+        let mut available = (self.stored + output - reserved.iter().sum::<f64>()).max(0.0);
+        for ((demand, reserved), supplied) in demand
+            .iter()
+            .zip(reserved.iter())
+            .zip(self.supplied.iter_mut())
+        {
+            let shortfall = demand - reserved;
+            *supplied = if shortfall <= available {
+                available -= shortfall;
+                1.0
+            } else {
+                let fraction = (reserved + available) / demand;
+                available = 0.0;
+                fraction
+            };
+        }
+        self.stored = available.min(self.capacity);
+    }
+
+    /// Returns true if the guns should advance their reload timers this tick.
+    fn reload_step(&mut self) -> bool {
+        self.reload_progress += self.supplied(Subsystem::Weapons);
+        if self.reload_progress >= 1.0 {
+            self.reload_progress -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Charges the ship for this tick's energy use. Returns true if the guns
+/// should advance their reload timers.
+pub(crate) fn tick(data: &mut ShipData) -> bool {
+    let Some(energy) = data.energy.as_mut() else {
+        return true;
+    };
+
+    let mut demand = [0.0; NUM_SUBSYSTEMS];
+    demand[Subsystem::Weapons as usize] = data
+        .guns
+        .iter()
+        .filter(|gun| gun.reload_ticks_remaining > 0)
+        .map(|gun| gun.power)
+        .sum::<f64>()
+        * PHYSICS_TICK_LENGTH;
+    demand[Subsystem::Sensors as usize] = data
+        .radars
+        .iter()
+        .map(|radar| {
+            let power = radar.power * RADAR_POWER_COST;
+            if radar.ecm_mode == EcmMode::None {
+                power
+            } else {
+                power * JAMMING_POWER_FACTOR
+            }
+        })
+        .sum::<f64>()
+        * PHYSICS_TICK_LENGTH;
+    demand[Subsystem::Engines as usize] = data
+        .abilities
+        .iter()
+        .filter(|ability| ability.active_time_remaining > 0.0)
+        .map(|ability| ability.power)
+        .sum::<f64>()
+        * PHYSICS_TICK_LENGTH;
+
+    energy.consume(demand);
+
+    if energy.supplied(Subsystem::Engines) < 1.0 {
+        for ability in data.abilities.iter_mut() {
+            if ability.power > 0.0 {
+                ability.active_time_remaining = 0.0;
+            }
+        }
+    }
+
+    energy.reload_step()
+}
+
+/// Multiplier applied to the power of the ship's radars.
+pub(crate) fn radar_power_factor(data: &ShipData) -> f64 {
+    data.energy
+        .as_ref()
+        .map(|energy| energy.supplied(Subsystem::Sensors))
+        .unwrap_or(1.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ship;
+    use crate::simulation::{Code, Simulation};
+    use nalgebra::vector;
+    use oort_api::Ability;
+    use test_log::test;
+
+    fn tick(energy: &mut Energy, demand: [f64; NUM_SUBSYSTEMS]) {
+        energy.consume(demand.map(|x| x * PHYSICS_TICK_LENGTH));
+    }
+
+    #[test]
+    fn test_allocation() {
+        let mut energy = Energy::new(300.0, 0.0);
+        assert_eq!(energy.allocation(Subsystem::Weapons), 1.0 / 3.0);
+
+        energy.set_allocation(2.0, 1.0, 1.0);
+        assert_eq!(energy.allocation(Subsystem::Weapons), 0.5);
+        assert_eq!(energy.allocation(Subsystem::Sensors), 0.25);
+
+        energy.set_allocation(0.0, f64::NAN, -1.0);
+        assert_eq!(energy.allocation(Subsystem::Engines), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn test_battery() {
+        let mut energy = Energy::new(300.0, 100.0);
+
+        // Within reactor output.
+        tick(&mut energy, [100.0, 100.0, 100.0]);
+        approx::assert_abs_diff_eq!(energy.stored, 100.0, epsilon = 1e-9);
+        assert_eq!(energy.supplied(Subsystem::Weapons), 1.0);
+
+        // Battery covers the shortfall.
+        tick(&mut energy, [600.0, 0.0, 0.0]);
+        assert_eq!(energy.supplied(Subsystem::Weapons), 1.0);
+        approx::assert_abs_diff_eq!(energy.stored, 95.0, epsilon = 1e-9);
+
+        // Unused reactor output recharges the battery.
+        tick(&mut energy, [0.0, 0.0, 0.0]);
+        approx::assert_abs_diff_eq!(energy.stored, 100.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_empty_battery() {
+        let mut energy = Energy::new(300.0, 0.0);
+        energy.set_allocation(1.0, 1.0, 0.0);
+
+        tick(&mut energy, [300.0, 300.0, 300.0]);
+        approx::assert_abs_diff_eq!(energy.supplied(Subsystem::Weapons), 0.5, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(energy.supplied(Subsystem::Sensors), 0.5, epsilon = 1e-9);
+        assert_eq!(energy.supplied(Subsystem::Engines), 0.0);
+
+        // Guns reload at half speed.
+        let reloads = (0..10).filter(|_| energy.reload_step()).count();
+        assert_eq!(reloads, 5);
+    }
+
+    #[test]
+    fn test_ship() {
+        let mut sim = Simulation::new("mechanics_test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let stored = |sim: &Simulation| sim.ship(ship0).data().energy.as_ref().unwrap().stored;
+        let capacity = stored(&sim);
+
+        // Radar and reactor are balanced.
+        sim.step();
+        assert_eq!(stored(&sim), capacity);
+
+        // Jamming while boosting drains the battery.
+        sim.ship_mut(ship0).radar_mut(0).unwrap().ecm_mode = EcmMode::Noise;
+        sim.ship_mut(ship0).activate_ability(Ability::Boost);
+        sim.step();
+        assert!(stored(&sim) < capacity);
+        assert!(sim.ship(ship0).is_ability_active(Ability::Boost));
+
+        // Boost shuts off when the battery is empty.
+        sim.ship_mut(ship0)
+            .data_mut()
+            .energy
+            .as_mut()
+            .unwrap()
+            .stored = 0.0;
+        sim.step();
+        assert!(!sim.ship(ship0).is_ability_active(Ability::Boost));
+        assert_eq!(radar_power_factor(sim.ship(ship0).data()), 1.0);
+    }
+}
@@ -0,0 +1,321 @@
+//! Passive electronic support measures (ESM).
+//!
+//! Every ship with a radar also carries a radar warning receiver. Each tick it
+//! listens for enemy radar beams (including noise jammers) that illuminate the
+//! ship and reports how many there are along with the bearing and received
+//! power of the strongest few.
+use crate::energy;
+use crate::obstacle;
+use crate::radar::{from_dbm, into_dbm};
+use crate::rng;
+use crate::ship::{ShipClass, ShipHandle};
+use crate::simulation::Simulation;
+use crate::topology;
+use nalgebra::{ComplexField, Point2, Rotation2, Vector2};
+use oort_api::prelude::esm_internal::MAX_CONTACTS;
+use oort_api::EcmMode;
+use rand::Rng;
+use rand_distr::StandardNormal;
+use std::f64::consts::TAU;
+
+const MIN_POWER_DBM: f64 = -100.0;
+const BEARING_NOISE_FACTOR: f64 = 1e1 * (TAU / 360.0);
+const POWER_NOISE_DB: f64 = 1.0;
+
+#[derive(Clone, Debug, Default)]
+pub struct Esm {
+    /// Number of enemy radars illuminating the ship.
+    pub count: usize,
+    /// The emitters with the highest received power, strongest first.
+    pub contacts: Vec<EsmContact>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct EsmContact {
+    /// Approximate bearing from the ship to the emitter.
+    pub bearing: f64,
+    /// Received power in dBm.
+    pub power: f64,
+    /// Whether the emitter is a noise jammer.
+    pub jammer: bool,
+}
+
+struct EsmEmitter {
+    team: i32,
+    position: Point2<f64>,
+    /// Unit vector along the center of the beam.
+    beam: Vector2<f64>,
+    /// Cosine of half the beam width.
+    cos_half_width: f64,
+    width: f64,
+    power: f64,
+    jammer: bool,
+    /// Squared distance beyond which no receiver can hear the emitter.
+    max_distance_squared: f64,
+}
+
+struct EsmReceiver {
+    handle: ShipHandle,
+    team: i32,
+    position: Point2<f64>,
+    rx_cross_section: f64,
+}
+
+#[inline(never)]
+pub fn tick(sim: &mut Simulation) {
+    let handle_snapshot: Vec<ShipHandle> = sim.ships.iter().cloned().collect();
+    let min_power = from_dbm(MIN_POWER_DBM);
+
+    let mut receivers = vec![];
+    for handle in handle_snapshot.iter().cloned() {
+        let ship = sim.ship(handle);
+        let ship_data = ship.data();
+        if ship_data.class == ShipClass::Planet {
+            continue;
+        }
+        let Some(radar) = ship_data.radars.first() else {
+            continue;
+        };
+        receivers.push(EsmReceiver {
+            handle,
+            team: ship_data.team,
+            position: ship.position().vector.into(),
+            rx_cross_section: radar.rx_cross_section,
+        });
+    }
+    let max_rx_cross_section = receivers
+        .iter()
+        .map(|receiver| receiver.rx_cross_section)
+        .fold(0.0, f64::max);
+
+    let mut emitters = vec![];
+    for handle in handle_snapshot.iter().cloned() {
+        let ship = sim.ship(handle);
+        let ship_data = ship.data();
+        for radar in ship_data.radars.iter() {
+            let power = radar.power * energy::radar_power_factor(ship_data);
+            emitters.push(EsmEmitter {
+                team: ship_data.team,
+                position: ship.position().vector.into(),
+                beam: Rotation2::new(radar.heading).transform_vector(&Vector2::x()),
+                cos_half_width: (radar.width * 0.5).cos(),
+                width: radar.width,
+                power,
+                jammer: radar.ecm_mode == EcmMode::Noise,
+                max_distance_squared: power * max_rx_cross_section
+                    / (TAU * radar.width * min_power),
+            });
+        }
+    }
+
+    // Reject receivers by team, distance and beam before the more expensive
+    // occlusion check.
+    let mut received: Vec<Vec<(f64, usize)>> = receivers.iter().map(|_| vec![]).collect();
+    for (emitter_idx, emitter) in emitters.iter().enumerate() {
+        for (receiver, received) in receivers.iter().zip(received.iter_mut()) {
+            if receiver.team == emitter.team {
+                continue;
+            }
+            // Toward the copy of the receiver nearest the emitter.
+            let dp = topology::displacement(sim, emitter.position, receiver.position);
+            let r_sq = dp.norm_squared();
+            if r_sq > emitter.max_distance_squared || !check_inside_beam(emitter, &dp) {
+                continue;
+            }
+            if obstacle::occluded(sim, emitter.position, emitter.position + dp) {
+                continue;
+            }
+            let power = compute_power(emitter, r_sq, receiver.rx_cross_section);
+            if power < min_power {
+                continue;
+            }
+            received.push((power, emitter_idx));
+        }
+    }
+
+    let mut rng = rng::new_rng(sim.tick());
+    for (receiver, mut received) in receivers.into_iter().zip(received) {
+        received.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let count = received.len();
+        received.truncate(MAX_CONTACTS);
+        let contacts = received
+            .into_iter()
+            .map(|(power, emitter_idx)| {
+                let emitter = &emitters[emitter_idx];
+                let power_dbm = into_dbm(power);
+                let snr = power_dbm - MIN_POWER_DBM;
+                let error_factor = ComplexField::powf(10.0f64, -snr / 10.0);
+                let dp = topology::displacement(sim, receiver.position, emitter.position);
+                let bearing = dp.y.atan2(dp.x)
+                    + rng.sample::<f64, _>(StandardNormal) * (BEARING_NOISE_FACTOR * error_factor);
+                EsmContact {
+                    bearing,
+                    power: power_dbm + rng.sample::<f64, _>(StandardNormal) * POWER_NOISE_DB,
+                    jammer: emitter.jammer,
+                }
+            })
+            .collect();
+
+        sim.ship_mut(receiver.handle).data_mut().esm = Esm { count, contacts };
+    }
+}
+
+/// Whether `dp`, relative to the emitter, is within its beam.
+fn check_inside_beam(emitter: &EsmEmitter, dp: &Vector2<f64>) -> bool {
+    emitter.beam.dot(dp) >= dp.norm() * emitter.cos_half_width
+}
+
+/// One-way signal strength from an emitter at squared distance `r_sq`. Radars
+/// see their reflections fall off with r⁴, so a passive receiver can hear a
+/// radar well beyond the range at which that radar can see it.
+fn compute_power(emitter: &EsmEmitter, r_sq: f64, rx_cross_section: f64) -> f64 {
+    emitter.power * rx_cross_section / (TAU * emitter.width * r_sq)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ship;
+    use crate::simulation::{Code, Simulation};
+    use nalgebra::vector;
+    use oort_api::EcmMode;
+    use std::f64::consts::{PI, TAU};
+    use test_log::test;
+
+    #[test]
+    fn test_basic() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![10e3, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+
+        // Enemy radar pointed away.
+        sim.ship_mut(ship1).radar_mut(0).unwrap().heading = 0.0;
+        sim.ship_mut(ship1).radar_mut(0).unwrap().width = TAU / 360.0;
+        sim.step();
+        assert_eq!(sim.ship(ship0).data().esm.count, 0);
+        assert!(sim.ship(ship0).data().esm.contacts.is_empty());
+
+        // Enemy radar pointed at us.
+        sim.ship_mut(ship1).radar_mut(0).unwrap().heading = PI;
+        sim.step();
+        let esm = sim.ship(ship0).data().esm.clone();
+        assert_eq!(esm.count, 1);
+        let contact = esm.contacts[0];
+        approx::assert_abs_diff_eq!(contact.bearing, 0.0, epsilon = 0.01);
+        assert!(!contact.jammer);
+
+        // Our own radar doesn't show up on our receiver, and the enemy
+        // receiver hears it.
+        sim.ship_mut(ship0).radar_mut(0).unwrap().heading = 0.0;
+        sim.ship_mut(ship0).radar_mut(0).unwrap().width = TAU / 360.0;
+        sim.step();
+        assert_eq!(sim.ship(ship0).data().esm.count, 1);
+        let contact = sim.ship(ship1).data().esm.contacts[0];
+        approx::assert_abs_diff_eq!(contact.bearing, PI, epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_jammer() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![0.0, 20e3],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        sim.ship_mut(ship1).radar_mut(0).unwrap().heading = -PI / 2.0;
+        sim.ship_mut(ship1).radar_mut(0).unwrap().width = TAU / 360.0;
+        sim.ship_mut(ship1).radar_mut(0).unwrap().ecm_mode = EcmMode::Noise;
+        sim.step();
+        let contact = sim.ship(ship0).data().esm.contacts[0];
+        approx::assert_abs_diff_eq!(contact.bearing, PI / 2.0, epsilon = 0.01);
+        assert!(contact.jammer);
+    }
+
+    #[test]
+    fn test_multiple() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let ship1 = ship::create(
+            &mut sim,
+            vector![0.0, 20e3],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        let ship2 = ship::create(
+            &mut sim,
+            vector![10e3, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        sim.ship_mut(ship1).radar_mut(0).unwrap().heading = -PI / 2.0;
+        sim.ship_mut(ship1).radar_mut(0).unwrap().width = TAU / 360.0;
+        sim.ship_mut(ship2).radar_mut(0).unwrap().heading = PI;
+        sim.ship_mut(ship2).radar_mut(0).unwrap().width = TAU / 360.0;
+        sim.step();
+
+        // Both emitters are reported, the closer one first.
+        let esm = sim.ship(ship0).data().esm.clone();
+        assert_eq!(esm.count, 2);
+        assert_eq!(esm.contacts.len(), 2);
+        approx::assert_abs_diff_eq!(esm.contacts[0].bearing, 0.0, epsilon = 0.01);
+        approx::assert_abs_diff_eq!(esm.contacts[1].bearing, PI / 2.0, epsilon = 0.01);
+        assert!(esm.contacts[0].power > esm.contacts[1].power);
+    }
+
+    #[test]
+    fn test_power() {
+        let check_power = |range: f64| {
+            let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            let ship1 = ship::create(
+                &mut sim,
+                vector![range, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(1),
+            );
+            sim.ship_mut(ship1).radar_mut(0).unwrap().heading = PI;
+            sim.ship_mut(ship1).radar_mut(0).unwrap().width = TAU / 360.0;
+            sim.step();
+            sim.ship(ship0).data().esm.contacts[0].power
+        };
+
+        // Power falls off with the square of distance.
+        approx::assert_abs_diff_eq!(check_power(10e3) - check_power(20e3), 6.0, epsilon = 3.0);
+    }
+}
@@ -0,0 +1,339 @@
+//! Runs the test scenarios declared with `oort_test!` in ship code.
+//!
+//! Each test is a function exported by the user's wasm module which writes a
+//! list of steps to `TEST_BUFFER` (see `oort_api::testing`). The harness reads
+//! the steps, then replays them against a fresh simulation where team 0 runs
+//! the user's code.
+use crate::scenario::Status;
+use crate::ship::{self, ShipHandle};
+use crate::simulation::{Code, Simulation};
+use crate::vm::{self, builtin, Error, WasmVm};
+use nalgebra::vector;
+use oort_api::testing::opcodes;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of ticks a single test may run.
+pub const MAX_TICKS: u32 = 60 * 60 * 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TestResult {
+    pub name: String,
+    pub passed: bool,
+    pub message: Option<String>,
+    pub ticks: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Spawn {
+        class: i32,
+        team: i32,
+        position: [f64; 2],
+        velocity: [f64; 2],
+        heading: f64,
+    },
+    Run(u32),
+    ExpectAlive(usize),
+    ExpectDestroyed(usize),
+    ExpectPositionNear {
+        ship: usize,
+        position: [f64; 2],
+        tolerance: f64,
+    },
+    ExpectHealthAbove(usize, f64),
+    ExpectHealthBelow(usize, f64),
+}
+
+/// Returns the names of the tests declared in `code`.
+pub fn list(code: &Code) -> Result<Vec<String>, Error> {
+    Ok(load_vm(code)?.test_names())
+}
+
+/// Runs every test declared in `code`.
+pub fn run_all(code: &Code) -> Result<Vec<TestResult>, Error> {
+    let mut vm = load_vm(code)?;
+    let names = vm.test_names();
+    Ok(names
+        .iter()
+        .map(|name| run_with_vm(&mut vm, code, name))
+        .collect())
+}
+
+/// Runs a single test declared in `code`.
+pub fn run(code: &Code, name: &str) -> Result<TestResult, Error> {
+    let mut vm = load_vm(code)?;
+    Ok(run_with_vm(&mut vm, code, name))
+}
+
+fn load_vm(code: &Code) -> Result<WasmVm, Error> {
+    match code {
+        Code::Builtin(name) => load_vm(&builtin::load_compiled(name).map_err(|msg| Error { msg })?),
+        Code::Wasm(_) => WasmVm::create(code),
+        #[cfg(feature = "precompile")]
+        Code::Precompiled(_) => WasmVm::create(code),
+        _ => Err(Error {
+            msg: "Tests require compiled code".to_string(),
+        }),
+    }
+}
+
+fn run_with_vm(vm: &mut WasmVm, code: &Code, name: &str) -> TestResult {
+    let mut result = TestResult {
+        name: name.to_string(),
+        passed: false,
+        message: None,
+        ticks: 0,
+    };
+    let steps = match vm.test_definition(name).and_then(|values| parse(&values)) {
+        Ok(steps) => steps,
+        Err(e) => {
+            result.message = Some(e.msg);
+            return result;
+        }
+    };
+    match execute(code, &steps, &mut result.ticks) {
+        Ok(()) => result.passed = true,
+        Err(msg) => result.message = Some(msg),
+    }
+    result
+}
+
+fn parse(values: &[f64]) -> Result<Vec<Step>, Error> {
+    let invalid = || Error {
+        msg: "Invalid test description".to_string(),
+    };
+    let mut steps = vec![];
+    let mut i = 0;
+    while i < values.len() {
+        let opcode = values[i];
+        let args = |n: usize| values.get((i + 1)..(i + 1 + n)).ok_or_else(invalid);
+        let (step, n) = if opcode == opcodes::SPAWN {
+            let a = args(7)?;
+            (
+                Step::Spawn {
+                    class: a[0] as i32,
+                    team: a[1] as i32,
+                    position: [a[2], a[3]],
+                    velocity: [a[4], a[5]],
+                    heading: a[6],
+                },
+                7,
+            )
+        } else if opcode == opcodes::RUN {
+            (Step::Run(args(1)?[0] as u32), 1)
+        } else if opcode == opcodes::EXPECT_ALIVE {
+            (Step::ExpectAlive(args(1)?[0] as usize), 1)
+        } else if opcode == opcodes::EXPECT_DESTROYED {
+            (Step::ExpectDestroyed(args(1)?[0] as usize), 1)
+        } else if opcode == opcodes::EXPECT_POSITION_NEAR {
+            let a = args(4)?;
+            (
+                Step::ExpectPositionNear {
+                    ship: a[0] as usize,
+                    position: [a[1], a[2]],
+                    tolerance: a[3],
+                },
+                4,
+            )
+        } else if opcode == opcodes::EXPECT_HEALTH_ABOVE {
+            let a = args(2)?;
+            (Step::ExpectHealthAbove(a[0] as usize, a[1]), 2)
+        } else if opcode == opcodes::EXPECT_HEALTH_BELOW {
+            let a = args(2)?;
+            (Step::ExpectHealthBelow(a[0] as usize, a[1]), 2)
+        } else {
+            return Err(invalid());
+        };
+        steps.push(step);
+        i += 1 + n;
+    }
+    Ok(steps)
+}
+
+fn execute(code: &Code, steps: &[Step], ticks: &mut u32) -> Result<(), String> {
+    let mut sim = Simulation::new("test", 0, &[code.clone()]);
+    if let Some(e) = sim.events().errors.first() {
+        return Err(e.msg.clone());
+    }
+    let mut ships: Vec<ShipHandle> = vec![];
+
+    let lookup = |ships: &[ShipHandle], index: usize| {
+        ships
+            .get(index)
+            .copied()
+            .ok_or_else(|| format!("Unknown ship {index}"))
+    };
+
+    for step in steps {
+        match *step {
+            Step::Spawn {
+                class,
+                team,
+                position,
+                velocity,
+                heading,
+            } => {
+                if !position
+                    .iter()
+                    .chain(velocity.iter())
+                    .chain(std::iter::once(&heading))
+                    .all(|v| v.is_finite())
+                {
+                    return Err("Invalid spawn state".to_string());
+                }
+                let data = vm::ship_data_for_class(&mut sim, class, team)
+                    .ok_or_else(|| format!("Invalid ship class {class} or team {team}"))?;
+                ships.push(ship::create(
+                    &mut sim,
+                    vector![position[0], position[1]],
+                    vector![velocity[0], velocity[1]],
+                    heading,
+                    data,
+                ));
+            }
+            Step::Run(n) => {
+                for _ in 0..n {
+                    if *ticks >= MAX_TICKS {
+                        return Err(format!("Exceeded maximum of {MAX_TICKS} ticks"));
+                    }
+                    sim.step();
+                    *ticks += 1;
+                    if let Some(msg) = crash_message(&sim) {
+                        return Err(format!("Ship crashed at tick {}: {msg}", *ticks));
+                    }
+                    if let Status::Failed = sim.status() {
+                        return Err("Simulation failed".to_string());
+                    }
+                }
+            }
+            Step::ExpectAlive(index) => {
+                if !sim.ships.contains(lookup(&ships, index)?) {
+                    return Err(format!(
+                        "Expected ship {index} to be alive at tick {}",
+                        *ticks
+                    ));
+                }
+            }
+            Step::ExpectDestroyed(index) => {
+                if sim.ships.contains(lookup(&ships, index)?) {
+                    return Err(format!(
+                        "Expected ship {index} to be destroyed at tick {}",
+                        *ticks
+                    ));
+                }
+            }
+            Step::ExpectPositionNear {
+                ship,
+                position,
+                tolerance,
+            } => {
+                let handle = lookup(&ships, ship)?;
+                if !sim.ships.contains(handle) {
+                    return Err(format!("Ship {ship} was destroyed"));
+                }
+                let actual = sim.ship(handle).position().vector;
+                let distance = (actual - vector![position[0], position[1]]).magnitude();
+                if distance.is_nan() || distance > tolerance {
+                    return Err(format!(
+                        "Expected ship {ship} within {tolerance} m of ({}, {}) at tick {}, was at ({:.1}, {:.1})",
+                        position[0], position[1], *ticks, actual.x, actual.y
+                    ));
+                }
+            }
+            Step::ExpectHealthAbove(index, health) => {
+                let handle = lookup(&ships, index)?;
+                if !sim.ships.contains(handle) {
+                    return Err(format!("Ship {index} was destroyed"));
+                }
+                let actual = sim.ship(handle).data().health;
+                if actual <= health {
+                    return Err(format!(
+                        "Expected ship {index} health above {health} at tick {}, was {actual}",
+                        *ticks
+                    ));
+                }
+            }
+            Step::ExpectHealthBelow(index, health) => {
+                let handle = lookup(&ships, index)?;
+                if sim.ships.contains(handle) {
+                    let actual = sim.ship(handle).data().health;
+                    if actual >= health {
+                        return Err(format!(
+                            "Expected ship {index} health below {health} at tick {}, was {actual}",
+                            *ticks
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn crash_message(sim: &Simulation) -> Option<String> {
+    sim.ships.iter().find_map(|&handle| {
+        let ship = sim.ship(handle);
+        let data = ship.data();
+        if data.team == 0 {
+            data.crash_message.clone()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let values = [
+            opcodes::SPAWN,
+            0.0,
+            0.0,
+            1.0,
+            2.0,
+            3.0,
+            4.0,
+            0.5,
+            opcodes::RUN,
+            60.0,
+            opcodes::EXPECT_POSITION_NEAR,
+            0.0,
+            10.0,
+            20.0,
+            5.0,
+            opcodes::EXPECT_HEALTH_BELOW,
+            0.0,
+            50.0,
+        ];
+        assert_eq!(
+            parse(&values).unwrap(),
+            vec![
+                Step::Spawn {
+                    class: 0,
+                    team: 0,
+                    position: [1.0, 2.0],
+                    velocity: [3.0, 4.0],
+                    heading: 0.5,
+                },
+                Step::Run(60),
+                Step::ExpectPositionNear {
+                    ship: 0,
+                    position: [10.0, 20.0],
+                    tolerance: 5.0,
+                },
+                Step::ExpectHealthBelow(0, 50.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse(&[opcodes::RUN]).is_err());
+        assert!(parse(&[opcodes::SPAWN, 0.0, 0.0]).is_err());
+        assert!(parse(&[100.0]).is_err());
+        assert!(parse(&[]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,486 @@
+//! Alternative physics step used when cross-platform determinism matters more
+//! than fidelity.
+//!
+//! Rapier's solver produces identical results on every platform only as long as
+//! every platform performs identical floating point math. The deterministic
+//! integrator avoids the solver entirely: it keeps each body's position,
+//! velocity and heading in fixed point and integrates forces with integer
+//! arithmetic, computes rotations with a strictly ordered polynomial instead of
+//! libm, and uses rapier purely for collision detection. Contacts between ships
+//! are resolved with a single impulse along the contact normal.
+//!
+//! Positions and headings are kept in units of one tick's worth of velocity, so
+//! advancing them by a tick adds the velocity exactly and slow bodies don't
+//! lose their motion to rounding.
+use crate::simulation::Simulation;
+use nalgebra::{UnitComplex, Vector2};
+use rapier2d_f64::parry::query::{PointQuery, RayCast};
+use rapier2d_f64::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+/// Fixed-point units per meter per second, newton or kilogram. Positions are
+/// kept in units of one such velocity unit times a tick.
+const SCALE: f64 = (1u64 << 20) as f64;
+/// Fixed-point units per radian per second. Headings are kept in units of one
+/// such angular velocity unit times a tick.
+const ANGLE_SCALE: f64 = (1u64 << 40) as f64;
+const TICKS_PER_SECOND: i64 = 60;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PhysicsMode {
+    /// Rapier's full rigid-body solver.
+    #[default]
+    Rapier,
+    /// Fixed-point integrator with simplified contact handling.
+    Deterministic,
+}
+
+/// Fixed-point state of a body along with the values last written to it, used
+/// to notice when something other than the integrator moved the body.
+#[derive(Clone)]
+struct FixedState {
+    position: [i64; 2],
+    velocity: [i64; 2],
+    angle: i64,
+    angular_velocity: i64,
+    translation: Vector2<f64>,
+    linvel: Vector2<f64>,
+    rotation: UnitComplex<f64>,
+    angvel: f64,
+}
+
+impl FixedState {
+    fn load(cached: Option<&FixedState>, body: &RigidBody) -> FixedState {
+        let position = match cached {
+            Some(s) if s.translation == *body.translation() => s.position,
+            _ => body.translation().map(to_fixed_position).into(),
+        };
+        let velocity = match cached {
+            Some(s) if s.linvel == *body.linvel() => s.velocity,
+            _ => body.linvel().map(to_fixed).into(),
+        };
+        let angle = match cached {
+            Some(s) if s.rotation == *body.rotation() => s.angle,
+            _ => to_fixed_angle(body.rotation().angle().rem_euclid(TAU)),
+        };
+        let angular_velocity = match cached {
+            Some(s) if s.angvel == body.angvel() => s.angular_velocity,
+            _ => to_fixed_angular_velocity(body.angvel()),
+        };
+        FixedState {
+            position,
+            velocity,
+            angle,
+            angular_velocity,
+            translation: *body.translation(),
+            linvel: *body.linvel(),
+            rotation: *body.rotation(),
+            angvel: body.angvel(),
+        }
+    }
+
+    fn store(&mut self, body: &mut RigidBody) {
+        self.translation = Vector2::new(
+            from_fixed_position(self.position[0]),
+            from_fixed_position(self.position[1]),
+        );
+        self.linvel = Vector2::new(from_fixed(self.velocity[0]), from_fixed(self.velocity[1]));
+        let (sin, cos) = sin_cos(from_fixed_angle(self.angle));
+        self.rotation = UnitComplex::from_cos_sin_unchecked(cos, sin);
+        self.angvel = from_fixed_angular_velocity(self.angular_velocity);
+        body.set_translation(self.translation, false);
+        body.set_linvel(self.linvel, false);
+        body.set_rotation(self.rotation, false);
+        body.set_angvel(self.angvel, false);
+    }
+}
+
+#[derive(Default)]
+pub struct DeterministicIntegrator {
+    states: HashMap<RigidBodyHandle, FixedState>,
+    collision_pipeline: CollisionPipeline,
+}
+
+impl DeterministicIntegrator {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns the heading of a body as tracked by the integrator.
+    ///
+    /// Returns None if the body hasn't been integrated yet or has been rotated
+    /// by something other than the integrator since.
+    pub fn heading(&self, handle: RigidBodyHandle, body: &RigidBody) -> Option<f64> {
+        self.states
+            .get(&handle)
+            .filter(|s| s.rotation == *body.rotation())
+            .map(|s| from_fixed_angle(s.angle))
+    }
+
+    fn integrate(&mut self, sim: &mut Simulation) {
+        let tau = to_fixed_angle(TAU);
+        for (handle, body) in sim.bodies.iter_mut() {
+            if !body.is_dynamic() {
+                continue;
+            }
+
+            let mut state = FixedState::load(self.states.get(&handle), body);
+
+            if !body.is_translation_locked() {
+                let mass = to_fixed(body.mass()) as i128;
+                for i in 0..2 {
+                    if mass > 0 {
+                        let force = to_fixed(body.user_force()[i]) as i128;
+                        state.velocity[i] +=
+                            div_round(force << 20, mass * TICKS_PER_SECOND as i128);
+                    }
+                    state.position[i] += state.velocity[i];
+                }
+            }
+
+            if !body.is_rotation_locked() {
+                let inv_inertia_sqrt = body
+                    .mass_properties()
+                    .local_mprops
+                    .inv_principal_inertia_sqrt;
+                if inv_inertia_sqrt > 0.0 {
+                    let inertia = to_fixed(1.0 / (inv_inertia_sqrt * inv_inertia_sqrt)) as i128;
+                    let torque = to_fixed(body.user_torque()) as i128;
+                    if inertia > 0 {
+                        state.angular_velocity +=
+                            div_round(torque << 40, inertia * TICKS_PER_SECOND as i128);
+                    }
+                }
+                state.angle = (state.angle + state.angular_velocity).rem_euclid(tau);
+            }
+
+            state.store(body);
+            self.states.insert(handle, state);
+        }
+
+        self.states
+            .retain(|handle, _| sim.bodies.get(*handle).is_some());
+    }
+
+    fn resolve_contacts(&mut self, sim: &mut Simulation) {
+        // Velocity and position changes in fixed point.
+        let mut impulses: Vec<(RigidBodyHandle, [i64; 2], [i64; 2])> = Vec::new();
+        for pair in sim.narrow_phase.contact_pairs() {
+            if !pair.has_any_active_contact {
+                continue;
+            }
+            let (Some(collider1), Some(collider2)) = (
+                sim.colliders.get(pair.collider1),
+                sim.colliders.get(pair.collider2),
+            ) else {
+                continue;
+            };
+            if collider1.is_sensor() || collider2.is_sensor() {
+                continue;
+            }
+            let (Some(handle1), Some(handle2)) = (collider1.parent(), collider2.parent()) else {
+                continue;
+            };
+            let (body1, body2) = (&sim.bodies[handle1], &sim.bodies[handle2]);
+            if !body1.is_dynamic() || !body2.is_dynamic() {
+                continue;
+            }
+            // Each body takes the share of the impulse given by the other's
+            // mass, and a body that can't translate takes none.
+            let mass = |body: &RigidBody| to_fixed(body.mass()) as i128;
+            let (share1, share2, total) =
+                match (body1.is_translation_locked(), body2.is_translation_locked()) {
+                    (true, true) => continue,
+                    (true, false) => (0, 1, 1),
+                    (false, true) => (1, 0, 1),
+                    (false, false) => (mass(body2), mass(body1), mass(body1) + mass(body2)),
+                };
+            let (state1, state2) = (
+                FixedState::load(self.states.get(&handle1), body1),
+                FixedState::load(self.states.get(&handle2), body2),
+            );
+            let restitution =
+                to_fixed((collider1.restitution() + collider2.restitution()) * 0.5) as i128;
+            for manifold in pair.manifolds.iter() {
+                let Some(depth) =
+                    manifold
+                        .points
+                        .iter()
+                        .map(|p| p.dist)
+                        .reduce(|a, b| if b < a { b } else { a })
+                else {
+                    continue;
+                };
+                let normal: [i128; 2] = manifold.data.normal.map(|x| to_fixed(x) as i128).into();
+                let approach_speed = div_round(
+                    (0..2)
+                        .map(|i| (state2.velocity[i] - state1.velocity[i]) as i128 * normal[i])
+                        .sum::<i128>(),
+                    1 << 20,
+                ) as i128;
+                let speed = if approach_speed < 0 {
+                    -approach_speed * ((1 << 20) + restitution)
+                } else {
+                    0
+                };
+                let penetration = if depth < 0.0 {
+                    to_fixed_position(-depth) as i128
+                } else {
+                    0
+                };
+                let change = |share: i128, sign: i128| {
+                    let dv = normal.map(|n| div_round(sign * n * speed * share, total << 40));
+                    let dp = normal.map(|n| div_round(sign * n * penetration * share, total << 20));
+                    (dv, dp)
+                };
+                let (dv1, dp1) = change(share1, -1);
+                let (dv2, dp2) = change(share2, 1);
+                impulses.push((handle1, dv1, dp1));
+                impulses.push((handle2, dv2, dp2));
+            }
+        }
+
+        for (handle, dv, dp) in impulses {
+            let body = &mut sim.bodies[handle];
+            let mut state = FixedState::load(self.states.get(&handle), body);
+            for i in 0..2 {
+                state.velocity[i] += dv[i];
+                state.position[i] += dp[i];
+            }
+            state.store(body);
+            self.states.insert(handle, state);
+        }
+    }
+
+    /// Reports bullets that passed completely through a ship during the tick.
+    ///
+    /// Rapier's CCD isn't used in deterministic mode, so fast bullets are swept
+    /// with a ray cast instead.
+    fn sweep_bullets(&self, sim: &Simulation, start_positions: &[(ColliderHandle, Point<f64>)]) {
+        for &(bullet_collider_handle, start) in start_positions {
+            let Some(bullet_collider) = sim.colliders.get(bullet_collider_handle) else {
+                continue;
+            };
+            let end = Point::from(bullet_collider.position().translation.vector);
+            let ray = Ray::new(start, end - start);
+            for ship in sim.ships.iter() {
+                let body = sim.ship(*ship).body();
+                let Some(&ship_collider_handle) = body.colliders().first() else {
+                    continue;
+                };
+                let ship_collider = &sim.colliders[ship_collider_handle];
+                if !bullet_collider
+                    .collision_groups()
+                    .test(ship_collider.collision_groups())
+                {
+                    continue;
+                }
+                let shape = ship_collider.shape();
+                if shape.contains_point(ship_collider.position(), &end) {
+                    // The narrow phase already reports this intersection.
+                    continue;
+                }
+                if shape
+                    .cast_ray(ship_collider.position(), &ray, 1.0, true)
+                    .is_some()
+                {
+                    sim.event_collector.handle_collision_event(
+                        &sim.bodies,
+                        &sim.colliders,
+                        CollisionEvent::Started(
+                            bullet_collider_handle,
+                            ship_collider_handle,
+                            CollisionEventFlags::SENSOR,
+                        ),
+                        None,
+                    );
+                    break;
+                }
+            }
+        }
+    }
+}
+
+pub fn step(sim: &mut Simulation) {
+    let mut integrator = std::mem::take(&mut sim.integrator);
+
+    let bullet_start_positions: Vec<(ColliderHandle, Point<f64>)> = sim
+        .bullets
+        .iter()
+        .filter_map(|handle| {
+            let body = &sim.bodies[RigidBodyHandle::from(*handle)];
+            body.colliders()
+                .first()
+                .map(|&collider| (collider, Point::from(*body.translation())))
+        })
+        .collect();
+
+    integrator.integrate(sim);
+    integrator.collision_pipeline.step(
+        sim.integration_parameters.prediction_distance,
+        &mut sim.broad_phase,
+        &mut sim.narrow_phase,
+        &mut sim.bodies,
+        &mut sim.colliders,
+        None,
+        &(),
+        &sim.event_collector,
+    );
+    integrator.resolve_contacts(sim);
+    integrator.sweep_bullets(sim, &bullet_start_positions);
+
+    sim.integrator = integrator;
+}
+
+/// Divides, rounding to the nearest integer. `d` must be positive.
+fn div_round(n: i128, d: i128) -> i64 {
+    let (q, r) = (n.div_euclid(d), n.rem_euclid(d));
+    (if 2 * r >= d { q + 1 } else { q }) as i64
+}
+
+fn to_fixed(x: f64) -> i64 {
+    (x * SCALE).round() as i64
+}
+
+fn from_fixed(x: i64) -> f64 {
+    x as f64 / SCALE
+}
+
+fn to_fixed_position(x: f64) -> i64 {
+    (x * (SCALE * TICKS_PER_SECOND as f64)).round() as i64
+}
+
+fn from_fixed_position(x: i64) -> f64 {
+    x as f64 / (SCALE * TICKS_PER_SECOND as f64)
+}
+
+fn to_fixed_angular_velocity(x: f64) -> i64 {
+    (x * ANGLE_SCALE).round() as i64
+}
+
+fn from_fixed_angular_velocity(x: i64) -> f64 {
+    x as f64 / ANGLE_SCALE
+}
+
+fn to_fixed_angle(x: f64) -> i64 {
+    (x * (ANGLE_SCALE * TICKS_PER_SECOND as f64)).round() as i64
+}
+
+fn from_fixed_angle(x: i64) -> f64 {
+    x as f64 / (ANGLE_SCALE * TICKS_PER_SECOND as f64)
+}
+
+/// Computes sine and cosine using only basic arithmetic, so the result is
+/// bit-identical on every platform.
+pub fn sin_cos(x: f64) -> (f64, f64) {
+    let quadrant = (x / FRAC_PI_2).round();
+    let r = x - quadrant * FRAC_PI_2;
+    let r2 = r * r;
+
+    let mut sin = 0.0;
+    let mut cos = 0.0;
+    let mut sin_term = r;
+    let mut cos_term = 1.0;
+    for i in 1..=8 {
+        sin += sin_term;
+        cos += cos_term;
+        let n = (2 * i) as f64;
+        sin_term = -sin_term * r2 / (n * (n + 1.0));
+        cos_term = -cos_term * r2 / ((n - 1.0) * n);
+    }
+
+    let (sin, cos) = match (quadrant as i64).rem_euclid(4) {
+        0 => (sin, cos),
+        1 => (cos, -sin),
+        2 => (-sin, -cos),
+        _ => (-cos, sin),
+    };
+    let norm = (sin * sin + cos * cos).sqrt();
+    (sin / norm, cos / norm)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ship;
+    use crate::simulation::Code;
+    use nalgebra::vector;
+    use test_log::test;
+
+    #[test]
+    fn test_sin_cos() {
+        let mut x = -10.0;
+        while x < 10.0 {
+            let (sin, cos) = sin_cos(x);
+            approx::assert_abs_diff_eq!(sin, x.sin(), epsilon = 1e-14);
+            approx::assert_abs_diff_eq!(cos, x.cos(), epsilon = 1e-14);
+            x += 0.001;
+        }
+    }
+
+    #[test]
+    fn test_fixed_point_round_trip() {
+        for x in [0.0, 1.0, -1.0, 1e-6, 12345.678, -99999.5] {
+            let p = to_fixed_position(x);
+            assert_eq!(to_fixed_position(from_fixed_position(p)), p);
+            let v = to_fixed(x);
+            assert_eq!(to_fixed(from_fixed(v)), v);
+        }
+    }
+
+    #[test]
+    fn test_acceleration() {
+        let mut sim = Simulation::new("deterministic_test", 0, &[Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        for _ in 0..60 {
+            sim.ship_mut(ship0).accelerate(vector![60.0, 0.0]);
+            sim.step();
+        }
+        // Acceleration commands take effect on the following tick.
+        approx::assert_abs_diff_eq!(sim.ship(ship0).velocity().x, 59.0, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(sim.ship(ship0).position().x, 29.5, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(sim.ship(ship0).position().y, 0.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_slow_velocity() {
+        let mut sim = Simulation::new("deterministic_test", 0, &[Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.001, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        for _ in 0..600 {
+            sim.step();
+        }
+        approx::assert_abs_diff_eq!(sim.ship(ship0).position().x, 0.01, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let mut sim = Simulation::new("deterministic_test", 0, &[Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        sim.ship_mut(ship0).body().set_angvel(1.0, true);
+        for _ in 0..60 {
+            sim.step();
+        }
+        approx::assert_abs_diff_eq!(sim.ship(ship0).heading(), 1.0, epsilon = 1e-6);
+        approx::assert_abs_diff_eq!(sim.ship(ship0).angular_velocity(), 1.0, epsilon = 1e-9);
+    }
+}
@@ -1,11 +1,21 @@
 #![allow(clippy::collapsible_if)]
 
+pub mod beam;
+pub mod boundary;
 pub mod bullet;
 pub mod collision;
 pub mod color;
 pub mod debug;
+pub mod energy;
+pub mod esm;
+pub mod harness;
 pub mod index_set;
+pub mod integrator;
+pub mod logistics;
 pub mod model;
+pub mod nebula;
+pub mod objective;
+pub mod obstacle;
 pub mod radar;
 pub mod radio;
 pub mod rng;
@@ -13,4 +23,6 @@ pub mod scenario;
 pub mod ship;
 pub mod simulation;
 pub mod snapshot;
+pub mod stats;
+pub mod topology;
 pub mod vm;
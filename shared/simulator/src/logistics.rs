@@ -0,0 +1,166 @@
+//! Logistics.
+//!
+//! A scenario can give fighters finite stores of fuel and gun ammunition.
+//! Fighters replenish them by holding station near a friendly frigate or
+//! cruiser: staying within range and roughly matching its velocity.
+use crate::index_set::HasIndex;
+use crate::ship::{ShipClass, ShipData};
+use crate::simulation::{Simulation, PHYSICS_TICK_LENGTH};
+use crate::topology;
+use nalgebra::Point2;
+
+/// Distance from a friendly frigate or cruiser within which fighters are
+/// resupplied.
+pub const RESUPPLY_RANGE: f64 = 500.0;
+/// Maximum speed relative to the frigate or cruiser at which fighters are
+/// resupplied.
+pub const RESUPPLY_MAX_SPEED: f64 = 20.0;
+
+#[derive(Clone, Debug)]
+pub struct Logistics {
+    /// Delta-v a fighter carries, in m/s.
+    pub fighter_fuel: f64,
+    /// Rounds of gun ammunition a fighter carries.
+    pub fighter_ammo: i32,
+    /// Seconds spent holding station to refill empty stores.
+    pub resupply_time: f64,
+}
+
+impl Default for Logistics {
+    fn default() -> Self {
+        Self {
+            fighter_fuel: 20e3,
+            fighter_ammo: 1500,
+            resupply_time: 10.0,
+        }
+    }
+}
+
+/// Gives a newly created fighter finite stores.
+pub(crate) fn init_ship(sim: &Simulation, data: &mut ShipData) {
+    let Some(logistics) = sim.logistics() else {
+        return;
+    };
+    if data.class == ShipClass::Fighter {
+        data.fuel = Some(logistics.fighter_fuel);
+        data.ammo = Some(logistics.fighter_ammo);
+    }
+}
+
+/// Refills the stores of fighters holding station near a friendly frigate or
+/// cruiser.
+pub(crate) fn tick(sim: &mut Simulation) {
+    let Some(logistics) = sim.logistics().cloned() else {
+        return;
+    };
+    let ticks = (logistics.resupply_time / PHYSICS_TICK_LENGTH).max(1.0);
+    let fuel_rate = logistics.fighter_fuel / ticks;
+    let ammo_rate = (logistics.fighter_ammo as f64 / ticks).ceil() as i32;
+
+    let mut carriers = vec![];
+    let mut fighters = vec![];
+    for &handle in sim.ships.iter() {
+        let ship = sim.ship(handle);
+        let entry = (
+            handle,
+            ship.data().team,
+            Point2::from(ship.position().vector),
+            ship.velocity(),
+        );
+        match ship.data().class {
+            ShipClass::Frigate | ShipClass::Cruiser => carriers.push(entry),
+            ShipClass::Fighter => fighters.push(entry),
+            _ => {}
+        }
+    }
+
+    for (handle, team, position, velocity) in fighters {
+        let holding_station = carriers.iter().any(|&(_, c_team, c_position, c_velocity)| {
+            c_team == team
+                && topology::displacement(sim, position, c_position).norm() < RESUPPLY_RANGE
+                && (velocity - c_velocity).norm() < RESUPPLY_MAX_SPEED
+        });
+        if !holding_station {
+            continue;
+        }
+        let ship_data = sim.ship_data.get_mut(handle.index()).unwrap();
+        if let Some(fuel) = ship_data.fuel.as_mut() {
+            *fuel = (*fuel + fuel_rate).min(logistics.fighter_fuel);
+        }
+        if let Some(ammo) = ship_data.ammo.as_mut() {
+            *ammo = (*ammo + ammo_rate).min(logistics.fighter_ammo);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ship;
+    use crate::simulation::Code;
+    use nalgebra::vector;
+    use test_log::test;
+
+    #[test]
+    fn test_resupply() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        sim.logistics = Some(Logistics::default());
+        ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::frigate(0),
+        );
+        let docked = ship::create(
+            &mut sim,
+            vector![0.0, 300.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let away = ship::create(
+            &mut sim,
+            vector![0.0, 3000.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        assert_eq!(sim.ship(docked).data().ammo, Some(1500));
+        for handle in [docked, away] {
+            let mut ship = sim.ship_mut(handle);
+            ship.data_mut().fuel = Some(0.0);
+            ship.data_mut().ammo = Some(0);
+        }
+
+        for _ in 0..(11.0 / PHYSICS_TICK_LENGTH) as usize {
+            sim.step();
+        }
+        assert_eq!(sim.ship(docked).data().ammo, Some(1500));
+        assert_eq!(sim.ship(docked).data().fuel, Some(20e3));
+        assert_eq!(sim.ship(away).data().ammo, Some(0));
+        assert_eq!(sim.ship(away).data().fuel, Some(0.0));
+    }
+
+    #[test]
+    fn test_out_of_ammo() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        sim.logistics = Some(Logistics {
+            fighter_ammo: 5,
+            ..Default::default()
+        });
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        for _ in 0..100 {
+            sim.ship_mut(ship0).fire(0);
+            sim.step();
+        }
+        assert_eq!(sim.ship(ship0).data().ammo, Some(0));
+        assert_eq!(sim.bullets.len(), 5);
+    }
+}
@@ -169,6 +169,44 @@ pub fn big_planet() -> Vec<Vector2<f32>> {
     scale(10000.0, &vertices)
 }
 
+pub fn chaff() -> Vec<Vector2<f32>> {
+    scale(
+        20.0,
+        &[
+            vector![1.0, 0.0],
+            vector![0.3, 0.3],
+            vector![0.0, 1.0],
+            vector![-0.3, 0.3],
+            vector![-1.0, 0.0],
+            vector![-0.3, -0.3],
+            vector![0.0, -1.0],
+            vector![0.3, -0.3],
+        ],
+    )
+}
+
+pub fn flare() -> Vec<Vector2<f32>> {
+    scale(
+        2.0,
+        &[
+            vector![1.0, 0.0],
+            vector![0.0, 1.0],
+            vector![-1.0, 0.0],
+            vector![0.0, -1.0],
+        ],
+    )
+}
+
+pub fn mine() -> Vec<Vector2<f32>> {
+    let n = 6;
+    let mut vertices = vec![];
+    for i in 0..n {
+        let rotation = Rotation2::new(i as f32 * 2.0 * std::f32::consts::PI / n as f32);
+        vertices.push(rotation.transform_vector(&vector![1.0, 0.0]));
+    }
+    scale(4.0, &vertices)
+}
+
 pub fn load(class: ShipClass) -> Vec<Vector2<f32>> {
     match class {
         ShipClass::Fighter => fighter(),
@@ -181,6 +219,9 @@ pub fn load(class: ShipClass) -> Vec<Vector2<f32>> {
         ShipClass::Torpedo => torpedo(),
         ShipClass::Planet => big_planet(),
         ShipClass::Beacon => target(),
+        ShipClass::Chaff => chaff(),
+        ShipClass::Flare => flare(),
+        ShipClass::Mine => mine(),
     }
 }
 
@@ -0,0 +1,244 @@
+//! Nebulae.
+//!
+//! A nebula is a region of space, a circle or a convex polygon, that degrades
+//! sensors and slows ships inside it. Radar contacts involving a ship inside a
+//! nebula are noisier, radio signals to or from a ship inside a nebula are
+//! attenuated, and ships inside a nebula are slowed by drag.
+use crate::ship::ShipClass;
+use crate::simulation::{Simulation, PHYSICS_TICK_LENGTH};
+use crate::snapshot::Field;
+use nalgebra::{vector, Point2, Vector4};
+use std::f64::consts::TAU;
+
+const COLOR: Vector4<f32> = Vector4::new(0.5, 0.3, 0.8, 0.15);
+
+#[derive(Clone, Debug)]
+pub enum Region {
+    Circle {
+        center: Point2<f64>,
+        radius: f64,
+    },
+    /// Vertices of a convex polygon in counter-clockwise order.
+    Polygon(Vec<Point2<f64>>),
+}
+
+impl Region {
+    pub fn contains(&self, point: Point2<f64>) -> bool {
+        match self {
+            Region::Circle { center, radius } => (point - center).norm() < *radius,
+            Region::Polygon(vertices) => {
+                let n = vertices.len();
+                (0..n).all(|i| {
+                    let a = vertices[i];
+                    let b = vertices[(i + 1) % n];
+                    (b - a).perp(&(point - a)) >= 0.0
+                })
+            }
+        }
+    }
+
+    /// Vertices of the region's outline, approximating circles with a polygon.
+    pub fn outline(&self) -> Vec<Point2<f64>> {
+        match self {
+            Region::Circle { center, radius } => {
+                let n = 48;
+                (0..n)
+                    .map(|i| {
+                        let angle = TAU * i as f64 / n as f64;
+                        center + vector![angle.cos(), angle.sin()] * *radius
+                    })
+                    .collect()
+            }
+            Region::Polygon(vertices) => vertices.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Nebula {
+    pub region: Region,
+    /// Multiplier for the measurement error of radar contacts.
+    pub radar_noise: f64,
+    /// Factor by which radio signal strength is divided.
+    pub radio_attenuation: f64,
+    /// Fraction of a ship's velocity lost per second.
+    pub drag: f64,
+}
+
+impl Nebula {
+    pub fn new(region: Region) -> Self {
+        Self {
+            region,
+            radar_noise: 10.0,
+            radio_attenuation: 100.0,
+            drag: 0.2,
+        }
+    }
+}
+
+/// Adds a nebula to the simulation.
+pub fn add_nebula(sim: &mut Simulation, nebula: Nebula) {
+    sim.nebulae.push(nebula);
+}
+
+/// Nebulae containing either of two points.
+fn affecting(sim: &Simulation, a: Point2<f64>, b: Point2<f64>) -> impl Iterator<Item = &Nebula> {
+    sim.nebulae
+        .iter()
+        .filter(move |nebula| nebula.region.contains(a) || nebula.region.contains(b))
+}
+
+/// Multiplier for the measurement error of a radar contact between two points.
+pub(crate) fn radar_noise(sim: &Simulation, a: Point2<f64>, b: Point2<f64>) -> f64 {
+    affecting(sim, a, b)
+        .map(|nebula| nebula.radar_noise)
+        .product()
+}
+
+/// Factor by which the strength of a radio signal between two points is
+/// divided.
+pub(crate) fn radio_attenuation(sim: &Simulation, a: Point2<f64>, b: Point2<f64>) -> f64 {
+    affecting(sim, a, b)
+        .map(|nebula| nebula.radio_attenuation)
+        .product()
+}
+
+/// Applies drag to ships inside nebulae.
+pub(crate) fn tick(sim: &mut Simulation) {
+    if sim.nebulae.is_empty() {
+        return;
+    }
+    let handles: Vec<_> = sim.ships.iter().cloned().collect();
+    for handle in handles {
+        let (position, class) = {
+            let ship = sim.ship(handle);
+            (Point2::from(ship.position().vector), ship.data().class)
+        };
+        if class == ShipClass::Planet {
+            continue;
+        }
+        let drag: f64 = sim
+            .nebulae
+            .iter()
+            .filter(|nebula| nebula.region.contains(position))
+            .map(|nebula| nebula.drag)
+            .sum();
+        if drag == 0.0 {
+            continue;
+        }
+        let factor = (1.0 - drag * PHYSICS_TICK_LENGTH).max(0.0);
+        let mut ship = sim.ship_mut(handle);
+        let body = ship.body();
+        let velocity = *body.linvel();
+        body.set_linvel(velocity * factor, true);
+    }
+}
+
+/// Describes the nebulae for the AI environment.
+///
+/// Each nebula is separated by `;`. A circle is `c x y radius` and a polygon
+/// is `p x0 y0 x1 y1 ...`.
+pub(crate) fn environment(sim: &Simulation) -> String {
+    sim.nebulae
+        .iter()
+        .map(|nebula| match &nebula.region {
+            Region::Circle { center, radius } => {
+                format!("c {:.0} {:.0} {:.0}", center.x, center.y, radius)
+            }
+            Region::Polygon(vertices) => {
+                let mut s = "p".to_string();
+                for v in vertices {
+                    s += &format!(" {:.0} {:.0}", v.x, v.y);
+                }
+                s
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Translucent fields covering the nebulae.
+pub fn fields(sim: &Simulation) -> Vec<Field> {
+    sim.nebulae
+        .iter()
+        .map(|nebula| Field {
+            vertices: nebula.region.outline(),
+            color: COLOR,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ship;
+    use crate::simulation::Code;
+    use nalgebra::point;
+    use test_log::test;
+
+    #[test]
+    fn test_contains() {
+        let circle = Region::Circle {
+            center: point![100.0, 0.0],
+            radius: 50.0,
+        };
+        assert!(circle.contains(point![120.0, 10.0]));
+        assert!(!circle.contains(point![0.0, 0.0]));
+
+        let square = Region::Polygon(vec![
+            point![-10.0, -10.0],
+            point![10.0, -10.0],
+            point![10.0, 10.0],
+            point![-10.0, 10.0],
+        ]);
+        assert!(square.contains(point![0.0, 5.0]));
+        assert!(!square.contains(point![0.0, 15.0]));
+    }
+
+    #[test]
+    fn test_drag() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        add_nebula(
+            &mut sim,
+            Nebula::new(Region::Circle {
+                center: point![0.0, 0.0],
+                radius: 1000.0,
+            }),
+        );
+        let inside = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![100.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let outside = ship::create(
+            &mut sim,
+            vector![0.0, 5000.0],
+            vector![100.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        for _ in 0..60 {
+            sim.step();
+        }
+        assert!(sim.ship(inside).velocity().x < 90.0);
+        approx::assert_abs_diff_eq!(sim.ship(outside).velocity().x, 100.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_radio_attenuation() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let (a, b) = (point![0.0, 0.0], point![5000.0, 0.0]);
+        assert_eq!(radio_attenuation(&sim, a, b), 1.0);
+        add_nebula(
+            &mut sim,
+            Nebula::new(Region::Circle {
+                center: b,
+                radius: 1000.0,
+            }),
+        );
+        assert_eq!(radio_attenuation(&sim, a, b), 100.0);
+        assert_eq!(radar_noise(&sim, a, b), 10.0);
+    }
+}
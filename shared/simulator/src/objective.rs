@@ -0,0 +1,206 @@
+//! Capture zones.
+//!
+//! A team captures a zone by being the only team with combat ships inside it
+//! and holds it until another team does the same. Every tick the holder of an
+//! uncontested zone earns control score, so scenarios can be won by holding
+//! ground instead of destroying the enemy.
+use crate::debug::Line;
+use crate::ship::ShipClass;
+use crate::simulation::{Simulation, PHYSICS_TICK_LENGTH};
+use nalgebra::{vector, Point2};
+use oort_api::prelude::zone_internal::MAX_ZONES;
+use oort_api::ZoneControl;
+use std::collections::BTreeMap;
+use std::f64::consts::TAU;
+
+/// Control score earned per second by each uncontested zone.
+pub const SCORE_PER_SECOND: f64 = 1.0;
+
+#[derive(Clone, Debug)]
+pub struct CaptureZone {
+    pub position: Point2<f64>,
+    pub radius: f64,
+    /// Team that captured the zone, if any.
+    pub holder: Option<i32>,
+    /// Whether ships from more than one team are inside the zone.
+    pub contested: bool,
+}
+
+impl CaptureZone {
+    pub fn new(position: Point2<f64>, radius: f64) -> Self {
+        Self {
+            position,
+            radius,
+            holder: None,
+            contested: false,
+        }
+    }
+
+    /// Control state as seen by `team`.
+    pub fn control(&self, team: i32) -> ZoneControl {
+        match self.holder {
+            _ if self.contested => ZoneControl::Contested,
+            None => ZoneControl::Neutral,
+            Some(holder) if holder == team => ZoneControl::Friendly,
+            Some(_) => ZoneControl::Enemy,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Objectives {
+    pub zones: Vec<CaptureZone>,
+    /// Control score of each team.
+    pub scores: BTreeMap<i32, f64>,
+}
+
+impl Objectives {
+    pub fn score(&self, team: i32) -> f64 {
+        self.scores.get(&team).copied().unwrap_or(0.0)
+    }
+
+    /// Highest score of any team other than `team`.
+    pub fn enemy_score(&self, team: i32) -> f64 {
+        self.scores
+            .iter()
+            .filter(|(&other, _)| other != team)
+            .map(|(_, &score)| score)
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Adds a capture zone to the simulation.
+pub fn add_zone(sim: &mut Simulation, zone: CaptureZone) {
+    assert!(
+        sim.objectives.zones.len() < MAX_ZONES,
+        "At most {MAX_ZONES} capture zones are supported"
+    );
+    sim.objectives.zones.push(zone);
+}
+
+pub(crate) fn tick(sim: &mut Simulation) {
+    if sim.objectives.zones.is_empty() {
+        return;
+    }
+
+    let ships: Vec<(Point2<f64>, i32)> = sim
+        .ships
+        .iter()
+        .map(|&handle| sim.ship(handle))
+        .filter(|ship| {
+            matches!(
+                ship.data().class,
+                ShipClass::Fighter | ShipClass::Frigate | ShipClass::Cruiser
+            )
+        })
+        .map(|ship| (Point2::from(ship.position().vector), ship.data().team))
+        .collect();
+
+    let objectives = &mut sim.objectives;
+    for zone in objectives.zones.iter_mut() {
+        let mut teams = ships
+            .iter()
+            .filter(|&&(position, _)| (position - zone.position).norm() < zone.radius)
+            .map(|&(_, team)| team);
+        let first = teams.next();
+        zone.contested = first.is_some() && teams.any(|team| Some(team) != first);
+        if !zone.contested && first.is_some() {
+            zone.holder = first;
+        }
+        if let Some(holder) = zone.holder
+            && !zone.contested
+        {
+            *objectives.scores.entry(holder).or_default() += SCORE_PER_SECOND * PHYSICS_TICK_LENGTH;
+        }
+    }
+}
+
+/// Outlines of the capture zones, colored by holder.
+pub fn lines(sim: &Simulation) -> Vec<Line> {
+    let n = 40;
+    let mut lines = vec![];
+    for zone in sim.objectives.zones.iter() {
+        let color = if zone.contested {
+            vector![1.0, 1.0, 1.0, 0.5]
+        } else {
+            match zone.holder {
+                Some(0) => vector![0.99, 0.98, 0.00, 0.5],
+                Some(1) => vector![0.99, 0.00, 0.98, 0.5],
+                Some(_) => vector![0.20, 0.85, 0.30, 0.5],
+                None => vector![0.5, 0.5, 0.5, 0.5],
+            }
+        };
+        for i in 0..n {
+            let angle_a = TAU * i as f64 / n as f64;
+            let angle_b = TAU * (i + 1) as f64 / n as f64;
+            lines.push(Line {
+                a: zone.position + vector![angle_a.cos(), angle_a.sin()] * zone.radius,
+                b: zone.position + vector![angle_b.cos(), angle_b.sin()] * zone.radius,
+                color,
+            });
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::scenario::{self, Status};
+    use crate::ship;
+    use crate::simulation::Code;
+    use nalgebra::{point, vector};
+    use test_log::test;
+
+    #[test]
+    fn test_capture() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        add_zone(&mut sim, CaptureZone::new(point![0.0, 0.0], 500.0));
+        let zone = |sim: &Simulation| sim.objectives().zones[0].clone();
+        assert_eq!(zone(&sim).control(0), ZoneControl::Neutral);
+
+        ship::create(
+            &mut sim,
+            vector![100.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        for _ in 0..60 {
+            sim.step();
+        }
+        assert_eq!(zone(&sim).control(0), ZoneControl::Friendly);
+        assert_eq!(zone(&sim).control(1), ZoneControl::Enemy);
+        approx::assert_abs_diff_eq!(sim.objectives().score(0), 1.0, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(sim.objectives().enemy_score(1), 1.0, epsilon = 1e-9);
+
+        // An enemy ship contests the zone and stops the score.
+        ship::create(
+            &mut sim,
+            vector![-100.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(1),
+        );
+        for _ in 0..60 {
+            sim.step();
+        }
+        assert_eq!(zone(&sim).control(0), ZoneControl::Contested);
+        approx::assert_abs_diff_eq!(sim.objectives().score(0), 1.0, epsilon = 1e-9);
+        assert_eq!(sim.objectives().score(1), 0.0);
+    }
+
+    #[test]
+    fn test_score_victory() {
+        let mut sim = Simulation::new("capture_points", 0, &[Code::None, Code::None]);
+        assert_eq!(sim.objectives().zones.len(), 3);
+        assert_eq!(sim.status(), Status::Running);
+
+        sim.objectives.scores.insert(1, 100.0);
+        assert_eq!(
+            scenario::check_score_victory(&sim, 100.0, scenario::TOURNAMENT_MAX_TICKS),
+            Status::Victory { team: 1 }
+        );
+        assert_eq!(sim.status(), Status::Victory { team: 1 });
+    }
+}
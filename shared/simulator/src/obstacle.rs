@@ -0,0 +1,168 @@
+//! Static obstacles.
+//!
+//! Scenarios can place convex obstacles in the world. They are fixed colliders
+//! that stop ships and bullets, and they block line of sight for radar, ESM,
+//! radio and beams.
+use crate::collision;
+use crate::debug::Line;
+use crate::simulation::Simulation;
+use nalgebra::{Point2, Vector4};
+use rapier2d_f64::prelude::*;
+
+const COLOR: Vector4<f32> = Vector4::new(0.6, 0.5, 0.4, 1.0);
+
+#[derive(Clone, Debug)]
+pub struct Obstacle {
+    /// Vertices of the convex hull in counter-clockwise order.
+    pub vertices: Vec<Point2<f64>>,
+    pub(crate) body: RigidBodyHandle,
+    collider: ColliderHandle,
+}
+
+/// Adds an obstacle shaped like the convex hull of `points`.
+pub fn add_obstacle(sim: &mut Simulation, points: &[Point2<f64>]) {
+    let collider = ColliderBuilder::convex_hull(points)
+        .expect("Obstacle must have a non-degenerate convex hull")
+        .restitution(0.5)
+        .collision_groups(collision::obstacle_interaction_groups())
+        .active_events(ActiveEvents::COLLISION_EVENTS)
+        .build();
+    let vertices = collider
+        .shape()
+        .as_convex_polygon()
+        .unwrap()
+        .points()
+        .to_vec();
+    let body = sim.bodies.insert(RigidBodyBuilder::fixed().build());
+    let collider = sim
+        .colliders
+        .insert_with_parent(collider, body, &mut sim.bodies);
+    sim.obstacles.push(Obstacle {
+        vertices,
+        body,
+        collider,
+    });
+}
+
+/// Distance along `ray` to the nearest obstacle, if it is closer than
+/// `max_toi`.
+pub(crate) fn cast_ray(sim: &Simulation, ray: &Ray, max_toi: f64) -> Option<f64> {
+    sim.obstacles
+        .iter()
+        .filter_map(|obstacle| {
+            let collider = &sim.colliders[obstacle.collider];
+            collider
+                .shape()
+                .cast_ray(collider.position(), ray, max_toi, true)
+        })
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+/// Whether an obstacle blocks the line of sight between two points.
+pub(crate) fn occluded(sim: &Simulation, a: Point2<f64>, b: Point2<f64>) -> bool {
+    if sim.obstacles.is_empty() {
+        return false;
+    }
+    let dp = b - a;
+    let distance = dp.norm();
+    if distance == 0.0 {
+        return false;
+    }
+    cast_ray(sim, &Ray::new(a, dp / distance), distance).is_some()
+}
+
+/// Outlines of the obstacles.
+pub fn lines(sim: &Simulation) -> Vec<Line> {
+    let mut lines = vec![];
+    for obstacle in sim.obstacles.iter() {
+        let n = obstacle.vertices.len();
+        for i in 0..n {
+            lines.push(Line {
+                a: obstacle.vertices[i],
+                b: obstacle.vertices[(i + 1) % n],
+                color: COLOR,
+            });
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ship;
+    use crate::simulation::Code;
+    use nalgebra::{point, vector};
+    use test_log::test;
+
+    fn add_wall(sim: &mut Simulation) {
+        add_obstacle(
+            sim,
+            &[
+                point![400.0, -500.0],
+                point![600.0, -500.0],
+                point![600.0, 500.0],
+                point![400.0, 500.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn test_occluded() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        add_wall(&mut sim);
+        assert!(occluded(&sim, point![0.0, 0.0], point![1000.0, 0.0]));
+        assert!(!occluded(&sim, point![0.0, 0.0], point![300.0, 0.0]));
+        assert!(!occluded(&sim, point![0.0, 0.0], point![0.0, 1000.0]));
+    }
+
+    #[test]
+    fn test_radar_occlusion() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        sim.step();
+        assert!(sim.ship(ship0).radar(0).unwrap().result.is_some());
+
+        add_wall(&mut sim);
+        sim.step();
+        assert!(sim.ship(ship0).radar(0).unwrap().result.is_none());
+    }
+
+    #[test]
+    fn test_bullet_blocked() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        add_wall(&mut sim);
+        let target = ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        for _ in 0..60 {
+            sim.ship_mut(ship0).fire(0);
+            sim.step();
+        }
+        assert!(sim.ships.contains(target));
+    }
+}
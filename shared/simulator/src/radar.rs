@@ -1,6 +1,6 @@
 use crate::ship::{self, ShipClass, ShipHandle};
 use crate::simulation::{Line, Simulation};
-use crate::{model, rng, simulation};
+use crate::{energy, model, nebula, obstacle, rng, simulation, topology};
 use nalgebra::{vector, ComplexField, Point2, Rotation2, Vector2};
 use oort_api::{Ability, EcmMode};
 use rand::{Rng, RngExt};
@@ -8,7 +8,7 @@ use rand_distr::StandardNormal;
 use rapier2d_f64::parry;
 use rapier2d_f64::prelude::*;
 use std::collections::{BTreeMap, HashMap};
-use std::f64::consts::TAU;
+use std::f64::consts::{PI, TAU};
 use std::ops::Range;
 use wide::{f32x4, CmpGt, CmpLt};
 
@@ -19,6 +19,15 @@ const BEARING_NOISE_FACTOR: f64 = 1e1 * (TAU / 360.0);
 const DISTANCE_NOISE_FACTOR: f64 = 1e4;
 const VELOCITY_NOISE_FACTOR: f64 = 1e2;
 
+// Radar cross section multipliers sampled at evenly spaced aspect angles from
+// nose-on (0) through broadside to tail-on (PI). The tail-on value is 1 so that
+// `ShipData::radar_cross_section` is the cross section seen from behind.
+const FIGHTER_RCS_PROFILE: &[f64] = &[0.3, 0.6, 2.0, 1.3, 1.0];
+const FRIGATE_RCS_PROFILE: &[f64] = &[0.5, 0.9, 2.0, 1.3, 1.0];
+const CRUISER_RCS_PROFILE: &[f64] = &[0.6, 1.0, 2.5, 1.5, 1.0];
+const MUNITION_RCS_PROFILE: &[f64] = &[0.2, 0.5, 1.5, 1.2, 1.0];
+const UNIFORM_RCS_PROFILE: &[f64] = &[1.0];
+
 #[derive(Clone, Debug)]
 pub struct Radar {
     pub heading: f64,
@@ -114,6 +123,8 @@ struct RadarEmitter {
     team: i32,
     radar_idx: usize,
     rays: [Vector2<f64>; 2],
+    /// Missile and torpedo seekers, the only radars that can see flares.
+    seeker: bool,
 }
 
 #[derive(Clone)]
@@ -122,8 +133,11 @@ struct RadarReflector {
     velocity: Vector2<f64>,
     heading: f64,
     radar_cross_section: f64,
+    rcs_profile: &'static [f64],
     radius: f64,
     class: ShipClass,
+    reported_class: ShipClass,
+    seeker_only: bool,
     jammers: Vec<RadarJammer>,
 }
 
@@ -135,6 +149,16 @@ struct RadarJammer {
     ecm_mode: EcmMode,
 }
 
+/// A reflector tracked by a radar, which semi-active seekers launched by the
+/// tracking ship can home on.
+struct Illumination {
+    handle: ShipHandle,
+    center: Point2<f64>,
+    power: f64,
+    width: f64,
+    reflector: RadarReflector,
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct ScanResult {
     pub class: ShipClass,
@@ -162,17 +186,20 @@ struct ReflectorGroup {
     reflectors: Vec<RadarReflector>,
 }
 
-fn into_dbm(x: f64) -> f64 {
+pub(crate) fn into_dbm(x: f64) -> f64 {
     10.0 * ComplexField::log10(x) + 30.0
 }
 
-fn from_dbm(x: f64) -> f64 {
+pub(crate) fn from_dbm(x: f64) -> f64 {
     ComplexField::powf(10.0_f64, (x - 30.0) / 10.0)
 }
 
 #[inline(never)]
 fn build_reflectors(sim: &Simulation) -> Reflectors {
     let mut reflector_groups: HashMap<ReflectorGroupKey, Vec<RadarReflector>> = HashMap::new();
+    // In a toroidal world each ship is also visible at its positions in the
+    // neighboring copies of the world, so that emitters see it across the seam.
+    let image_offsets = topology::image_offsets(sim);
 
     for handle in sim.ships.iter() {
         let ship = sim.ship(*handle);
@@ -187,6 +214,15 @@ fn build_reflectors(sim: &Simulation) -> Reflectors {
         if class == ShipClass::Planet {
             continue;
         }
+        // Countermeasures fade over their lifetime.
+        let initial_ttl = match class {
+            ShipClass::Chaff => Some(ship::CHAFF_TTL),
+            ShipClass::Flare => Some(ship::FLARE_TTL),
+            _ => None,
+        };
+        if let Some(initial_ttl) = initial_ttl {
+            radar_cross_section *= ship_data.ttl.unwrap_or(0) as f64 / initial_ttl as f64;
+        }
         // TODO: Add support for multiple radars.
         let jammers = ship_data
             .radars
@@ -196,7 +232,7 @@ fn build_reflectors(sim: &Simulation) -> Reflectors {
                 _ => Some(RadarJammer {
                     width: radar.width,
                     bearing: radar.heading,
-                    power: radar.power,
+                    power: radar.power * energy::radar_power_factor(ship_data),
                     ecm_mode: radar.ecm_mode,
                 }),
             })
@@ -205,18 +241,32 @@ fn build_reflectors(sim: &Simulation) -> Reflectors {
             team: ship_data.team,
             radius: ship_data.radar_radius,
         };
-        reflector_groups
-            .entry(group_key)
-            .or_default()
-            .push(RadarReflector {
-                position: ship.position().vector.into(),
-                velocity: ship.velocity(),
-                heading: ship.heading(),
-                radar_cross_section,
-                radius: ship_data.radar_radius as f64,
-                class,
-                jammers,
-            });
+        let reflector = RadarReflector {
+            position: ship.position().vector.into(),
+            velocity: ship.velocity(),
+            heading: ship.heading(),
+            radar_cross_section,
+            rcs_profile: if sim.aspect_dependent_rcs() {
+                rcs_profile(class)
+            } else {
+                UNIFORM_RCS_PROFILE
+            },
+            radius: ship_data.radar_radius as f64,
+            class,
+            reported_class: ship_data.radar_class.unwrap_or(class),
+            seeker_only: class == ShipClass::Flare,
+            jammers,
+        };
+        let images: Vec<_> = image_offsets[1..]
+            .iter()
+            .map(|offset| RadarReflector {
+                position: reflector.position + offset,
+                ..reflector.clone()
+            })
+            .collect();
+        let group = reflector_groups.entry(group_key).or_default();
+        group.push(reflector);
+        group.extend(images);
     }
 
     let mut result: Reflectors = Default::default();
@@ -253,6 +303,61 @@ fn build_reflectors(sim: &Simulation) -> Reflectors {
     result
 }
 
+/// Builds the emitter for one of a ship's radars, along with the range at which
+/// it reliably detects a fighter.
+fn build_emitter(sim: &Simulation, handle: ShipHandle, radar_idx: usize) -> (RadarEmitter, f64) {
+    let ship = sim.ship(handle);
+    let ship_data = ship.data();
+    let radar = ship_data.radars.get(radar_idx).unwrap();
+    let h = radar.heading;
+    let w = radar.width;
+    assert!(w < TAU / 2.0);
+    let max_rcs_multiplier = if sim.aspect_dependent_rcs() {
+        max_rcs_multiplier(CRUISER_RCS_PROFILE)
+    } else {
+        1.0
+    };
+    let max_distance = compute_max_detection_range(
+        radar,
+        ship::CRUISER_RADAR_CROSS_SECTION * max_rcs_multiplier,
+    )
+    .min(radar.max_distance)
+    .min(simulation::MAX_WORLD_SIZE);
+    let min_distance = radar.min_distance.min(max_distance);
+    let reliable_distance = compute_reliable_detection_range(radar, 10.0 /*fighter*/)
+        .min(radar.max_distance)
+        .min(simulation::MAX_WORLD_SIZE);
+
+    let start_bearing = h - 0.5 * w;
+    let end_bearing = h + 0.5 * w;
+    let ray0 = Rotation2::new(start_bearing).transform_vector(&vector![1.0, 0.0]);
+    let ray1 = Rotation2::new(end_bearing).transform_vector(&vector![1.0, 0.0]);
+    assert!(is_clockwise(ray1, ray0));
+    let rays = [ray0, ray1];
+    let emitter = RadarEmitter {
+        handle,
+        team: ship_data.team,
+        radar_idx,
+        center: ship.position().vector.into(),
+        power: radar.power * energy::radar_power_factor(ship_data),
+        reliable_rssi: radar.reliable_rssi,
+        min_rssi: radar.min_rssi,
+        rx_cross_section: radar.rx_cross_section,
+        width: w,
+        start_bearing,
+        bearing: h,
+        bearing_vector: Rotation2::new(h).transform_vector(&vector![1.0, 0.0]),
+        end_bearing,
+        min_distance,
+        max_distance,
+        square_distance_range: ComplexField::powi(radar.min_distance, 2)
+            ..ComplexField::powi(max_distance, 2),
+        rays,
+        seeker: matches!(ship_data.class, ShipClass::Missile | ShipClass::Torpedo),
+    };
+    (emitter, reliable_distance)
+}
+
 #[inline(never)]
 pub fn tick(sim: &mut Simulation) {
     let handle_snapshot: Vec<ShipHandle> = sim.ships.iter().cloned().collect();
@@ -265,62 +370,19 @@ pub fn tick(sim: &mut Simulation) {
         .cloned()
         .collect::<Vec<_>>();
     let mut reflector_shapes = HashMap::new();
+    let mut illuminations = vec![];
 
     for handle in handle_snapshot.iter().cloned() {
         let radars_idxs = 0..sim.ship(handle).data().radars.len();
         for radar_idx in radars_idxs {
-            let mut emitter;
-            let reliable_distance;
-            {
-                let ship = sim.ship(handle);
-                let ship_data = ship.data();
-                let radar = ship_data.radars.get(radar_idx).unwrap();
-                let h = radar.heading;
-                let w = radar.width;
-                assert!(w < TAU / 2.0);
-                let max_distance = compute_max_detection_range(radar, 40.0 /*cruiser*/)
-                    .min(radar.max_distance)
-                    .min(simulation::MAX_WORLD_SIZE);
-                let min_distance = radar.min_distance.min(max_distance);
-                reliable_distance = compute_reliable_detection_range(radar, 10.0 /*fighter*/)
-                    .min(radar.max_distance)
-                    .min(simulation::MAX_WORLD_SIZE);
-
-                let start_bearing = h - 0.5 * w;
-                let end_bearing = h + 0.5 * w;
-                let ray0 = Rotation2::new(start_bearing).transform_vector(&vector![1.0, 0.0]);
-                let ray1 = Rotation2::new(end_bearing).transform_vector(&vector![1.0, 0.0]);
-                assert!(is_clockwise(ray1, ray0));
-                let rays = [ray0, ray1];
-                emitter = RadarEmitter {
-                    handle,
-                    team: ship_data.team,
-                    radar_idx,
-                    center: ship.position().vector.into(),
-                    power: radar.power,
-                    reliable_rssi: radar.reliable_rssi,
-                    min_rssi: radar.min_rssi,
-                    rx_cross_section: radar.rx_cross_section,
-                    width: w,
-                    start_bearing,
-                    bearing: h,
-                    bearing_vector: Rotation2::new(h).transform_vector(&vector![1.0, 0.0]),
-                    end_bearing,
-                    min_distance,
-                    max_distance,
-                    square_distance_range: ComplexField::powi(radar.min_distance, 2)
-                        ..ComplexField::powi(max_distance, 2),
-                    rays,
-                };
-
-                if radar.ecm_mode != EcmMode::None {
-                    let mut ship = sim.ship_mut(handle);
-                    let ship_data = ship.data_mut();
-                    let radar = ship_data.radars.get_mut(radar_idx).unwrap();
-                    radar.result = None;
-                    draw_emitter(sim, &emitter, reliable_distance);
-                    continue;
-                }
+            let (mut emitter, reliable_distance) = build_emitter(sim, handle, radar_idx);
+            if sim.ship(handle).data().radars[radar_idx].ecm_mode != EcmMode::None {
+                let mut ship = sim.ship_mut(handle);
+                let ship_data = ship.data_mut();
+                let radar = ship_data.radars.get_mut(radar_idx).unwrap();
+                radar.result = None;
+                draw_emitter(sim, &emitter, reliable_distance);
+                continue;
             }
 
             let mut rng = rng::new_rng(sim.tick());
@@ -345,6 +407,12 @@ pub fn tick(sim: &mut Simulation) {
             let emitter_isometry = Isometry::new(emitter.center.coords, emitter.bearing);
 
             for reflector in candidates.iter() {
+                if reflector.seeker_only && !emitter.seeker {
+                    continue;
+                }
+                if obstacle::occluded(sim, emitter.center, reflector.position) {
+                    continue;
+                }
                 for jammer in &reflector.jammers {
                     match jammer.ecm_mode {
                         EcmMode::None => {}
@@ -453,6 +521,7 @@ pub fn tick(sim: &mut Simulation) {
                         contact_position,
                         best_rssi_dbm,
                         received_noise_dbm,
+                        nebula::radar_noise(sim, emitter.center, reflector.position),
                         &mut rng,
                     )
                 })
@@ -465,12 +534,87 @@ pub fn tick(sim: &mut Simulation) {
                 radar.result = result;
             }
 
+            if let (Some(reflector), Some(_)) = (best_reflector, &result) {
+                illuminations.push(Illumination {
+                    handle,
+                    center: emitter.center,
+                    power: emitter.power,
+                    width: emitter.width,
+                    reflector: reflector.clone(),
+                });
+            }
+
             draw_emitter(sim, &emitter, reliable_distance);
             if let Some(contact) = &result {
                 draw_contact(sim, emitter.handle, contact);
             }
         }
     }
+
+    illuminate(sim, &handle_snapshot, &illuminations);
+}
+
+/// Semi-active radar homing. Each missile and torpedo listens with its own
+/// radar antenna for reflections of the beams of the ship that launched it.
+/// It only hears targets that its parent is currently tracking, so the link
+/// breaks when the parent's beam moves away.
+#[inline(never)]
+fn illuminate(sim: &mut Simulation, handles: &[ShipHandle], illuminations: &[Illumination]) {
+    let mut rng = rng::new_rng(sim.tick());
+    for &handle in handles {
+        let parent = {
+            let ship_data = sim.ship(handle).data();
+            if !matches!(ship_data.class, ShipClass::Missile | ShipClass::Torpedo)
+                || ship_data.radars.is_empty()
+            {
+                continue;
+            }
+            ship_data.parent
+        };
+
+        let mut result = None;
+        if let Some(parent) = parent {
+            let (receiver, _) = build_emitter(sim, handle, 0);
+            let mut best: Option<(f64, &RadarReflector)> = None;
+            for illumination in illuminations.iter().filter(|x| x.handle == parent) {
+                let reflector = &illumination.reflector;
+                if !check_inside_beam_raw(
+                    &receiver.center,
+                    receiver.bearing,
+                    receiver.width,
+                    &reflector.position,
+                ) || obstacle::occluded(sim, reflector.position, receiver.center)
+                {
+                    continue;
+                }
+                let rssi = compute_bistatic_rssi(illumination, &receiver)
+                    * ComplexField::powf(1.2f64, rng.random_range(-1.0..1.0));
+                if rssi > best.map_or(receiver.min_rssi, |(rssi, _)| rssi) {
+                    best = Some((rssi, reflector));
+                }
+            }
+
+            let received_noise =
+                BACKGROUND_NOISE * ComplexField::powf(2.0f64, rng.random_range(-1.0..1.0));
+            if let Some((rssi, reflector)) = best {
+                let rssi_dbm = into_dbm(rssi);
+                let noise_dbm = into_dbm(received_noise);
+                if rssi_dbm - noise_dbm >= 3.0 {
+                    result = Some(make_scan_result(
+                        &receiver,
+                        reflector,
+                        reflector.position,
+                        rssi_dbm,
+                        noise_dbm,
+                        nebula::radar_noise(sim, receiver.center, reflector.position),
+                        &mut rng,
+                    ));
+                }
+            }
+        }
+
+        sim.ship_mut(handle).data_mut().illuminated = result;
+    }
 }
 
 #[inline(never)]
@@ -532,10 +676,11 @@ fn make_scan_result(
     contact_position: Point2<f64>,
     rssi_dbm: f64,
     noise_dbm: f64,
+    nebula_noise: f64,
     rng: &mut impl Rng,
 ) -> ScanResult {
     let signal_db = rssi_dbm - noise_dbm;
-    let error_factor = ComplexField::powf(10.0f64, -signal_db / 10.0);
+    let error_factor = ComplexField::powf(10.0f64, -signal_db / 10.0) * nebula_noise;
     let dp = contact_position - emitter.center;
     let beam_rot = Rotation2::new(emitter.bearing);
     let reflector_rot = Rotation2::rotation_between(&Vector2::x(), &dp);
@@ -561,7 +706,7 @@ fn make_scan_result(
             * (VELOCITY_NOISE_FACTOR * error_factor);
 
     ScanResult {
-        class: reflector.class,
+        class: reflector.reported_class,
         position,
         velocity,
         rssi: rssi_dbm,
@@ -625,11 +770,59 @@ fn check_inside_beam_raw(
 }
 
 fn compute_rssi(emitter: &RadarEmitter, reflector: &RadarReflector) -> f64 {
-    let r_sq = nalgebra::distance_squared(&emitter.center, &reflector.position);
-    emitter.power * reflector.radar_cross_section * emitter.rx_cross_section
+    let dp = emitter.center - reflector.position;
+    let r_sq = dp.norm_squared();
+    let aspect = Rotation2::new(reflector.heading)
+        .transform_vector(&Vector2::x())
+        .angle(&dp);
+    let radar_cross_section =
+        reflector.radar_cross_section * rcs_multiplier(reflector.rcs_profile, aspect);
+    emitter.power * radar_cross_section * emitter.rx_cross_section
         / (TAU * emitter.width * r_sq * r_sq)
 }
 
+/// Signal strength received by a semi-active seeker from a reflector lit up by
+/// another ship's radar.
+fn compute_bistatic_rssi(illumination: &Illumination, receiver: &RadarEmitter) -> f64 {
+    let reflector = &illumination.reflector;
+    let tx_r_sq = (illumination.center - reflector.position).norm_squared();
+    let dp = receiver.center - reflector.position;
+    let rx_r_sq = dp.norm_squared();
+    let aspect = Rotation2::new(reflector.heading)
+        .transform_vector(&Vector2::x())
+        .angle(&dp);
+    let radar_cross_section =
+        reflector.radar_cross_section * rcs_multiplier(reflector.rcs_profile, aspect);
+    illumination.power * radar_cross_section * receiver.rx_cross_section
+        / (TAU * illumination.width * tx_r_sq * rx_r_sq)
+}
+
+fn rcs_profile(class: ShipClass) -> &'static [f64] {
+    match class {
+        ShipClass::Fighter => FIGHTER_RCS_PROFILE,
+        ShipClass::Frigate => FRIGATE_RCS_PROFILE,
+        ShipClass::Cruiser => CRUISER_RCS_PROFILE,
+        ShipClass::Missile | ShipClass::Torpedo => MUNITION_RCS_PROFILE,
+        _ => UNIFORM_RCS_PROFILE,
+    }
+}
+
+/// Interpolates the cross section multiplier for an aspect angle between 0
+/// (nose-on) and PI (tail-on).
+fn rcs_multiplier(profile: &[f64], aspect: f64) -> f64 {
+    if profile.len() < 2 {
+        return profile.first().copied().unwrap_or(1.0);
+    }
+    let x = (aspect.abs().min(PI) / PI) * (profile.len() - 1) as f64;
+    let i = (x.floor() as usize).min(profile.len() - 2);
+    let t = x - i as f64;
+    profile[i] * (1.0 - t) + profile[i + 1] * t
+}
+
+fn max_rcs_multiplier(profile: &[f64]) -> f64 {
+    profile.iter().copied().fold(1.0, f64::max)
+}
+
 fn compute_max_detection_range(radar: &Radar, target_cross_section: f64) -> f64 {
     ComplexField::powf(
         radar.power * target_cross_section * radar.rx_cross_section
@@ -828,6 +1021,42 @@ mod test {
         assert!(sim.ship(ship0).radar(0).unwrap().result.is_none());
     }
 
+    #[test]
+    fn test_countermeasure_class() {
+        for launcher in [ship::fighter(1), ship::frigate(1)] {
+            let class = launcher.class;
+            let mut sim = Simulation::new("mechanics_test", 0, &[Code::None, Code::None]);
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            let ship1 = ship::create(
+                &mut sim,
+                vector![1000.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                launcher,
+            );
+            sim.ship_mut(ship0).radar_mut(0).unwrap().heading = 0.0;
+            sim.ship_mut(ship0).radar_mut(0).unwrap().width = TAU / 60.0;
+            sim.ship_mut(ship1).launch_countermeasure(ShipClass::Chaff);
+            sim.step();
+
+            // Leave only the chaff in the beam.
+            sim.ship_mut(ship1)
+                .body()
+                .set_translation(vector![0.0, 1e5], true);
+            sim.step();
+            assert_eq!(
+                sim.ship(ship0).radar(0).unwrap().scan().unwrap().class,
+                class
+            );
+        }
+    }
+
     #[test]
     fn test_radar_radius() {
         let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
@@ -987,6 +1216,63 @@ mod test {
         assert!(!check_detection(Fighter, Cruiser, 150e3));
     }
 
+    #[test]
+    fn test_rcs_multiplier() {
+        use super::{rcs_multiplier, rcs_profile, FIGHTER_RCS_PROFILE};
+
+        let profile = [0.5, 2.0, 1.0];
+        approx::assert_abs_diff_eq!(rcs_multiplier(&profile, 0.0), 0.5);
+        approx::assert_abs_diff_eq!(rcs_multiplier(&profile, PI / 4.0), 1.25);
+        approx::assert_abs_diff_eq!(rcs_multiplier(&profile, PI / 2.0), 2.0);
+        approx::assert_abs_diff_eq!(rcs_multiplier(&profile, 3.0 * PI / 4.0), 1.5);
+        approx::assert_abs_diff_eq!(rcs_multiplier(&profile, PI), 1.0);
+        approx::assert_abs_diff_eq!(rcs_multiplier(&profile, 2.0 * PI), 1.0);
+        approx::assert_abs_diff_eq!(rcs_multiplier(&profile, -PI / 2.0), 2.0);
+        approx::assert_abs_diff_eq!(rcs_multiplier(&[3.0], 1.0), 3.0);
+        approx::assert_abs_diff_eq!(rcs_multiplier(&[], 1.0), 1.0);
+
+        assert_eq!(rcs_profile(ShipClass::Fighter), FIGHTER_RCS_PROFILE);
+        assert_eq!(rcs_profile(ShipClass::Target), &[1.0]);
+    }
+
+    #[test]
+    fn test_aspect_detection() {
+        let check_detection = |target_heading| {
+            let mut sim = Simulation::new("mechanics_test", 0, &[Code::None, Code::None]);
+            let ship0 = ship::create(
+                &mut sim,
+                vector![0.0, 0.0],
+                vector![0.0, 0.0],
+                0.0,
+                ship::fighter(0),
+            );
+            ship::create(
+                &mut sim,
+                vector![60e3, 0.0],
+                vector![0.0, 0.0],
+                target_heading,
+                ship::fighter(1),
+            );
+            sim.ship_mut(ship0).radar_mut(0).unwrap().heading = 0.0;
+            sim.ship_mut(ship0).radar_mut(0).unwrap().width = TAU / 360.0;
+
+            (0..100)
+                .map(|_| {
+                    sim.step();
+                    sim.ship(ship0).radar(0).unwrap().result.is_some()
+                })
+                .filter(|x| *x)
+                .count()
+                > 50
+        };
+
+        // Nose-on.
+        assert!(!check_detection(PI));
+        // Broadside.
+        assert!(check_detection(PI / 2.0));
+        assert!(check_detection(-PI / 2.0));
+    }
+
     #[test]
     fn test_jamming() {
         let check_detection = |range| {
@@ -1226,4 +1512,45 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_semi_active_homing() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::frigate(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![20e3, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        let missile0 = ship::create(
+            &mut sim,
+            vector![2000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ShipData {
+                parent: Some(ship0),
+                ..ship::missile(0)
+            },
+        );
+        sim.ship_mut(ship0).radar_mut(0).unwrap().heading = 0.0;
+        sim.ship_mut(ship0).radar_mut(0).unwrap().width = TAU / 360.0;
+        sim.ship_mut(missile0).radar_mut(0).unwrap().heading = 0.0;
+        sim.step();
+        let contact = sim.ship(missile0).data().illuminated.unwrap();
+        assert_eq!(contact.class, ShipClass::Target);
+        approx::assert_abs_diff_eq!(contact.position, vector![20e3, 0.0], epsilon = 100.0);
+
+        // The link breaks when the parent's beam moves away.
+        sim.ship_mut(ship0).radar_mut(0).unwrap().heading = PI / 2.0;
+        sim.step();
+        assert!(sim.ship(missile0).data().illuminated.is_none());
+    }
 }
@@ -1,5 +1,8 @@
+use crate::nebula;
+use crate::obstacle;
 use crate::ship::ShipHandle;
 use crate::simulation::Simulation;
+use crate::topology;
 use nalgebra::Point2;
 use oort_api::Message;
 use std::collections::BTreeMap;
@@ -89,7 +92,12 @@ pub fn tick(sim: &mut Simulation) {
             let mut best_msg = None;
             let mut best_rssi = rx.min_rssi;
             for tx in senders.get(&channel).unwrap_or(&Vec::new()) {
-                let rssi = compute_rssi(tx, rx);
+                if obstacle::occluded(sim, tx.position, rx.position) {
+                    continue;
+                }
+                let r_sq = topology::displacement(sim, tx.position, rx.position).norm_squared();
+                let rssi = compute_rssi(tx, rx, r_sq)
+                    / nebula::radio_attenuation(sim, tx.position, rx.position);
                 if rssi > best_rssi {
                     best_rssi = rssi;
                     best_msg = Some(tx.msg);
@@ -112,11 +120,10 @@ pub fn tick(sim: &mut Simulation) {
 }
 
 /// Computes signal strength between a sender and reciever based on
-/// their distance from each other and the sender's power.
+/// the squared distance between them and the sender's power.
 ///
 /// Reference: https://en.wikipedia.org/wiki/Received_signal_strength_indicator
-fn compute_rssi(sender: &RadioSender, receiver: &RadioReceiver) -> f64 {
-    let r_sq = nalgebra::distance_squared(&sender.position, &receiver.position);
+fn compute_rssi(sender: &RadioSender, receiver: &RadioReceiver, r_sq: f64) -> f64 {
     sender.power * receiver.rx_cross_section / (TAU * r_sq)
 }
 
@@ -80,4 +80,8 @@ impl Scenario for AsteroidDuel {
     fn is_tournament(&self) -> bool {
         true
     }
+
+    fn collision_damage(&self) -> bool {
+        true
+    }
 }
@@ -88,4 +88,8 @@ impl Scenario for Belt {
     fn is_tournament(&self) -> bool {
         true
     }
+
+    fn collision_damage(&self) -> bool {
+        true
+    }
 }
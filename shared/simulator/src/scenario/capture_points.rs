@@ -0,0 +1,83 @@
+use super::prelude::*;
+use crate::objective::{self, CaptureZone};
+
+const TARGET_SCORE: f64 = 100.0;
+
+/// Two teams of fighters fight over three capture zones. The first team to
+/// reach the target score wins.
+pub struct CapturePoints {}
+
+impl CapturePoints {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Scenario for CapturePoints {
+    fn name(&self) -> String {
+        "capture_points".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Capture Points".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let placements = place_teams(&mut rng, self.world_size());
+
+        for (team, placement) in placements.into_iter().enumerate() {
+            let Placement { position, heading } = placement;
+            let fighter_separation = 500.0;
+            for i in 0..4 {
+                ship::create(
+                    sim,
+                    vector![
+                        position.x,
+                        position.y + (i as f64 - 1.5) * fighter_separation
+                    ],
+                    vector![0.0, 0.0],
+                    heading,
+                    fighter(team as i32),
+                );
+            }
+        }
+
+        let spacing = self.world_size() * 0.25;
+        for y in [-spacing, 0.0, spacing] {
+            objective::add_zone(sim, CaptureZone::new(point![0.0, y], 1500.0));
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        check_score_victory(sim, TARGET_SCORE, TOURNAMENT_MAX_TICKS)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn is_tournament(&self) -> bool {
+        true
+    }
+
+    fn aspect_dependent_rcs(&self) -> bool {
+        true
+    }
+
+    fn countermeasures(&self) -> bool {
+        true
+    }
+
+    fn energy(&self) -> bool {
+        true
+    }
+
+    fn collision_damage(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,66 @@
+use super::prelude::*;
+use crate::vm::director::DirectorVm;
+use crate::vm::Error;
+
+/// A scenario scripted by a wasm director module. See `vm::director` for the
+/// interface the module implements.
+pub struct Director {
+    name: String,
+    vm: DirectorVm,
+    error: Option<String>,
+}
+
+impl Director {
+    pub fn new(name: &str, wasm: &[u8]) -> Result<Self, Error> {
+        Ok(Self {
+            name: name.to_string(),
+            vm: DirectorVm::create(wasm)?,
+            error: None,
+        })
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn handle_error(&mut self, result: Result<(), Error>) {
+        if let Err(e) = result {
+            log::warn!("Director {} failed: {}", self.name, e.msg);
+            self.error = Some(e.msg);
+        }
+    }
+}
+
+impl Scenario for Director {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let result = self.vm.init(sim, seed);
+        self.handle_error(result);
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        if self.error.is_none() {
+            let result = self.vm.tick(sim);
+            self.handle_error(result);
+        }
+    }
+
+    fn status(&self, _: &Simulation) -> Status {
+        if self.error.is_some() {
+            Status::Failed
+        } else {
+            self.vm.status()
+        }
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        self.vm.lines()
+    }
+}
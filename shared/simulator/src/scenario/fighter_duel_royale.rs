@@ -0,0 +1,80 @@
+use super::prelude::*;
+
+pub struct FighterDuelRoyale {}
+
+impl FighterDuelRoyale {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Scenario for FighterDuelRoyale {
+    fn name(&self) -> String {
+        "fighter_duel_royale".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Fighter Duel Royale".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let placements = place_teams(&mut rng, self.world_size());
+
+        for (team, placement) in placements.into_iter().enumerate() {
+            let Placement { position, heading } = placement;
+            ship::create(
+                sim,
+                position,
+                vector![0.0, 0.0],
+                heading,
+                fighter(team as i32),
+            );
+        }
+    }
+
+    fn boundary(&self) -> Option<Boundary> {
+        let initial = self.world_size() * 0.75;
+        Some(Boundary::new(
+            vec![
+                (0, initial),
+                (4000, initial),
+                (7000, 5000.0),
+                (9000, 1000.0),
+            ],
+            0.1,
+        ))
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        check_tournament_victory(sim)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn is_tournament(&self) -> bool {
+        true
+    }
+
+    fn aspect_dependent_rcs(&self) -> bool {
+        true
+    }
+
+    fn countermeasures(&self) -> bool {
+        true
+    }
+
+    fn energy(&self) -> bool {
+        true
+    }
+
+    fn collision_damage(&self) -> bool {
+        true
+    }
+}
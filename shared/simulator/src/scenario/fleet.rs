@@ -2,21 +2,36 @@ use nalgebra::UnitComplex;
 
 use super::prelude::*;
 
-pub struct Fleet {}
+pub struct Fleet {
+    logistics: bool,
+}
 
 impl Fleet {
     pub fn new() -> Self {
-        Self {}
+        Self { logistics: false }
+    }
+
+    /// The same battle with limited fighter fuel and ammunition.
+    pub fn with_logistics() -> Self {
+        Self { logistics: true }
     }
 }
 
 impl Scenario for Fleet {
     fn name(&self) -> String {
-        "fleet".into()
+        if self.logistics {
+            "fleet_logistics".into()
+        } else {
+            "fleet".into()
+        }
     }
 
     fn human_name(&self) -> String {
-        "Fleet".into()
+        if self.logistics {
+            "Fleet Logistics".into()
+        } else {
+            "Fleet".into()
+        }
     }
 
     fn init(&mut self, sim: &mut Simulation, seed: u32) {
@@ -70,6 +85,10 @@ impl Scenario for Fleet {
         check_capital_ship_tournament_victory(sim)
     }
 
+    fn logistics(&self) -> Option<Logistics> {
+        self.logistics.then(Logistics::default)
+    }
+
     fn initial_code(&self) -> Vec<Code> {
         vec![empty_ai(), reference_ai()]
     }
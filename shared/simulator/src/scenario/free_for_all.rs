@@ -0,0 +1,103 @@
+use super::prelude::*;
+
+const NUM_TEAMS: usize = 4;
+
+/// Four teams of fighters spawned around a ring. Last team standing wins.
+pub struct FreeForAll {
+    /// Tick at which each team lost its last ship, indexed by team ID.
+    eliminated: Vec<Option<u32>>,
+}
+
+impl FreeForAll {
+    pub fn new() -> Self {
+        Self {
+            eliminated: vec![None; NUM_TEAMS],
+        }
+    }
+}
+
+impl Scenario for FreeForAll {
+    fn name(&self) -> String {
+        "free_for_all".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Free-for-All".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let placements = place_teams_on_ring(&mut rng, self.world_size(), NUM_TEAMS);
+        let fighter_separation = 500.0;
+
+        for (team, placement) in placements.into_iter().enumerate() {
+            let Placement { position, heading } = placement;
+            let side = Rotation2::new(heading).transform_vector(&vector![0.0, 1.0]);
+            for s in [-1.0, 0.0, 1.0] {
+                ship::create(
+                    sim,
+                    position + side * s * fighter_separation,
+                    vector![0.0, 0.0],
+                    heading,
+                    fighter(team as i32),
+                );
+            }
+        }
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        let points = team_points(sim, NUM_TEAMS);
+        for (eliminated, points) in self.eliminated.iter_mut().zip(points) {
+            if eliminated.is_none() && points <= 0.0 {
+                *eliminated = Some(sim.tick());
+            }
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        check_free_for_all_victory(sim, NUM_TEAMS, TOURNAMENT_MAX_TICKS)
+    }
+
+    /// Surviving teams are ranked by remaining health, eliminated teams by
+    /// how long they lasted.
+    fn ranking(&self, sim: &Simulation) -> Vec<usize> {
+        let points = team_points(sim, NUM_TEAMS);
+        let scores: Vec<(u32, f64)> = self
+            .eliminated
+            .iter()
+            .zip(points)
+            .map(|(eliminated, points)| (eliminated.unwrap_or(u32::MAX), points))
+            .collect();
+        rank_by_score(&scores)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        let mut codes = vec![reference_ai(); NUM_TEAMS];
+        codes[0] = empty_ai();
+        codes
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn is_tournament(&self) -> bool {
+        true
+    }
+
+    fn aspect_dependent_rcs(&self) -> bool {
+        true
+    }
+
+    fn countermeasures(&self) -> bool {
+        true
+    }
+
+    fn energy(&self) -> bool {
+        true
+    }
+
+    fn collision_damage(&self) -> bool {
+        true
+    }
+}
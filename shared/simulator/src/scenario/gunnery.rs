@@ -12,19 +12,7 @@ impl Scenario for GunneryScenario {
     }
 
     fn init(&mut self, sim: &mut Simulation, seed: u32) {
-        let mut ship_data = frigate(0);
-        ship_data.guns.pop();
-        ship_data.guns.pop();
-        ship_data.missile_launchers.pop();
-        ship_data.acceleration = vector![0.0, 0.0];
-        ship_data.fuel = Some(0.0);
-        ship::create(
-            sim,
-            vector![-9000.0, 0.0],
-            vector![0.0, 0.0],
-            0.0,
-            ship_data,
-        );
+        create_gunship(sim);
         let mut rng = new_rng(seed);
         for _ in 0..4 {
             ship::create(
@@ -56,14 +44,86 @@ impl Scenario for GunneryScenario {
     }
 
     fn tick(&mut self, sim: &mut Simulation) {
-        let handles = sim.ships.iter().cloned().collect::<Vec<_>>();
-        for handle in handles {
-            let mut ship = sim.ship_mut(handle);
-            if ship.readonly().position().y > self.world_size() * 0.49 {
-                let new_position =
-                    vector![ship.readonly().position().x, -ship.readonly().position().y];
-                ship.body().set_translation(new_position, false);
-            }
+        wrap_targets(sim, self.world_size());
+    }
+}
+
+/// Harder variant of the gunnery challenge where the targets are big
+/// asteroids. Each one breaks into smaller asteroids that must also be
+/// destroyed.
+pub struct AsteroidGunneryScenario {}
+
+impl Scenario for AsteroidGunneryScenario {
+    fn name(&self) -> String {
+        "gunnery_asteroids".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Gunnery (Asteroids)".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        create_gunship(sim);
+        let mut rng = new_rng(seed);
+        for i in 0..2 {
+            let mut data = big_asteroid(rng.random_range(0..30));
+            data.team = 1;
+            ship::create(
+                sim,
+                vector![
+                    8000.0 + rng.random_range(-500.0..500.0),
+                    -9000.0 + i as f64 * 3000.0
+                ],
+                vector![
+                    0.0 + rng.random_range(-10.0..10.0),
+                    700.0 + rng.random_range(-300.0..300.0)
+                ],
+                rng.random_range(0.0..(2.0 * std::f64::consts::PI)),
+                data,
+            );
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        check_tutorial_victory(sim, DEFAULT_TUTORIAL_MAX_TICKS * 4)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![builtin("challenge/gunnery_initial")]
+    }
+
+    fn solution(&self) -> Code {
+        builtin("challenge/gunnery_solution")
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        wrap_targets(sim, self.world_size());
+    }
+}
+
+fn create_gunship(sim: &mut Simulation) {
+    let mut ship_data = frigate(0);
+    ship_data.guns.pop();
+    ship_data.guns.pop();
+    ship_data.missile_launchers.pop();
+    ship_data.acceleration = vector![0.0, 0.0];
+    ship_data.fuel = Some(0.0);
+    ship::create(
+        sim,
+        vector![-9000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ship_data,
+    );
+}
+
+fn wrap_targets(sim: &mut Simulation, world_size: f64) {
+    let handles = sim.ships.iter().cloned().collect::<Vec<_>>();
+    for handle in handles {
+        let mut ship = sim.ship_mut(handle);
+        if ship.readonly().position().y > world_size * 0.49 {
+            let new_position = vector![ship.readonly().position().x, -ship.readonly().position().y];
+            ship.body().set_translation(new_position, false);
         }
     }
 }
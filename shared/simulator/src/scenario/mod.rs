@@ -1,12 +1,18 @@
 mod asteroid_duel;
 mod belt;
+mod capture_points;
 mod cruiser_defense;
 mod cruiser_duel;
+mod director;
 mod fighter_duel;
+mod fighter_duel_royale;
 mod fleet;
+mod free_for_all;
 mod frigate_duel;
 mod gunnery;
 mod mini_fleet;
+mod nebula;
+mod obstacle_duel;
 mod orbit;
 mod planetary_defense;
 mod primitive_duel;
@@ -16,6 +22,7 @@ mod sandbox;
 mod squadrons;
 mod stress;
 mod test;
+mod toroidal_duel;
 mod tutorial_acceleration;
 mod tutorial_acceleration2;
 mod tutorial_cruiser;
@@ -31,9 +38,13 @@ mod tutorial_search;
 mod tutorial_squadron;
 mod welcome;
 
+use crate::boundary::Boundary;
+use crate::integrator::PhysicsMode;
+use crate::logistics::Logistics;
 use crate::ship::{asteroid, fighter, ShipAccessor, ShipClass, ShipData};
 use crate::simulation::{Code, Line, Simulation};
-use nalgebra::{vector, Vector2};
+use crate::topology::Topology;
+use nalgebra::{vector, Rotation2, Vector2};
 use rand::{seq::SliceRandom, Rng, RngExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -44,17 +55,22 @@ pub mod prelude {
     pub use super::Status;
     pub use super::{builtin, empty_ai, reference_ai};
     pub use super::{
-        check_capital_ship_tournament_victory, check_tournament_victory, check_tutorial_victory,
+        check_capital_ship_tournament_victory, check_free_for_all_victory, check_score_victory,
+        check_tournament_victory, check_tutorial_victory,
     };
     pub use super::{fighter_without_missiles, fighter_without_missiles_or_radar, target_asteroid};
-    pub use super::{place_teams, Placement};
+    pub use super::{place_teams, place_teams_on_ring, rank_by_score, team_points, Placement};
     pub use super::{DEFAULT_TUTORIAL_MAX_TICKS, TOURNAMENT_MAX_TICKS};
+    pub use crate::boundary::Boundary;
+    pub use crate::integrator::PhysicsMode;
+    pub use crate::logistics::Logistics;
     pub use crate::rng::{new_rng, SeededRng};
     pub use crate::ship::{
         self, asteroid, beacon, big_asteroid, cruiser, fighter, frigate, missile, target, torpedo,
         ShipHandle,
     };
     pub use crate::simulation::{Code, Line, Simulation};
+    pub use crate::topology::Topology;
     pub use nalgebra::{point, vector, Point2, Rotation2, Vector2};
     pub use rand::{Rng, RngExt};
     pub use std::f64::consts::{PI, TAU};
@@ -120,6 +136,18 @@ pub trait Scenario {
         false
     }
 
+    /// Finishing position of each team, indexed by team ID. Lower is better
+    /// and tied teams share a position.
+    fn ranking(&self, sim: &Simulation) -> Vec<usize> {
+        let num_teams = self.initial_code().len();
+        match self.status(sim) {
+            Status::Victory { team } => (0..num_teams)
+                .map(|i| if i as i32 == team { 0 } else { 1 })
+                .collect(),
+            _ => vec![0; num_teams],
+        }
+    }
+
     fn score_time(&self, sim: &Simulation) -> f64 {
         sim.time()
     }
@@ -128,13 +156,69 @@ pub trait Scenario {
         vec![]
     }
 
+    /// Whether the world is walled or wraps around at the edges.
+    fn topology(&self) -> Topology {
+        Topology::Walled
+    }
+
+    /// Physics step used by the simulation. The deterministic integrator
+    /// produces identical results in the browser and natively.
+    fn physics_mode(&self) -> PhysicsMode {
+        PhysicsMode::Rapier
+    }
+
+    /// Optional arena boundary that shrinks over time.
+    fn boundary(&self) -> Option<Boundary> {
+        None
+    }
+
+    /// Optional finite fuel and ammunition for fighters, replenished at
+    /// friendly capital ships.
+    fn logistics(&self) -> Option<Logistics> {
+        None
+    }
+
+    /// Whether a ship's radar cross section depends on the aspect it is seen
+    /// from.
+    fn aspect_dependent_rcs(&self) -> bool {
+        false
+    }
+
+    /// Whether ships have a reactor and battery powering their subsystems.
+    fn energy(&self) -> bool {
+        false
+    }
+
+    /// Whether ships carry chaff and flares.
+    fn countermeasures(&self) -> bool {
+        false
+    }
+
+    /// Whether collisions between ships of different teams, or with
+    /// obstacles, cause damage.
+    fn collision_damage(&self) -> bool {
+        false
+    }
+
     fn world_size(&self) -> f64 {
         40000.0
     }
 }
 
 pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
-    let scenario: Option<Box<dyn Scenario>> = match name {
+    let scenario = load_builtin(name);
+    if let Some(scenario) = scenario.as_ref() {
+        assert_eq!(scenario.name(), name);
+    }
+    scenario
+}
+
+fn load_builtin(name: &str) -> Option<Box<dyn Scenario>> {
+    if let Some(name) = name.strip_prefix("deterministic_") {
+        return load_builtin(name)
+            .map(|scenario| Box::new(test::Deterministic::new(scenario)) as Box<dyn Scenario>);
+    }
+    match name {
         // Tutorials
         "tutorial_guns" => Some(Box::new(tutorial_guns::TutorialGuns {})),
         "tutorial_acceleration" => {
@@ -157,21 +241,30 @@ pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
         "primitive_duel" => Some(Box::new(primitive_duel::PrimitiveDuel::new())),
         "radar_duel" => Some(Box::new(radar_duel::RadarDuel::new())),
         "fighter_duel" => Some(Box::new(fighter_duel::FighterDuel::new())),
+        "fighter_duel_royale" => Some(Box::new(fighter_duel_royale::FighterDuelRoyale::new())),
         "frigate_duel" => Some(Box::new(frigate_duel::FrigateDuel::new())),
         "cruiser_duel" => Some(Box::new(cruiser_duel::CruiserDuel::new())),
         "asteroid_duel" => Some(Box::new(asteroid_duel::AsteroidDuel::new())),
         "squadrons" => Some(Box::new(squadrons::Squadrons::new())),
         "mini_fleet" => Some(Box::new(mini_fleet::MiniFleet::new())),
         "fleet" => Some(Box::new(fleet::Fleet::new())),
+        "fleet_logistics" => Some(Box::new(fleet::Fleet::with_logistics())),
+        "free_for_all" => Some(Box::new(free_for_all::FreeForAll::new())),
+        "capture_points" => Some(Box::new(capture_points::CapturePoints::new())),
+        "toroidal_duel" => Some(Box::new(toroidal_duel::ToroidalDuel::new())),
+        "obstacle_duel" => Some(Box::new(obstacle_duel::ObstacleDuel::new())),
+        "nebula" => Some(Box::new(nebula::NebulaScenario::new())),
         "belt" => Some(Box::new(belt::Belt::new())),
         "orbit" => Some(Box::new(orbit::Orbit::new())),
         // Challenge
         "gunnery" => Some(Box::new(gunnery::GunneryScenario {})),
+        "gunnery_asteroids" => Some(Box::new(gunnery::AsteroidGunneryScenario {})),
         "cruiser_defense" => Some(Box::new(cruiser_defense::CruiserDefense::new())),
         "planetary_defense" => Some(Box::new(planetary_defense::PlanetaryDefense::new())),
         "race" => Some(Box::new(race::Race::new())),
         // Testing
         "test" => Some(Box::new(test::TestScenario {})),
+        "mechanics_test" => Some(Box::new(test::MechanicsTestScenario {})),
         "basic" => Some(Box::new(test::BasicScenario {})),
         "missile_test" => Some(Box::new(test::MissileTest::new())),
         "frigate_vs_cruiser" => Some(Box::new(test::FrigateVsCruiser::new())),
@@ -187,11 +280,7 @@ pub fn load_safe(name: &str) -> Option<Box<dyn Scenario>> {
         "welcome" => Some(Box::new(welcome::Welcome::new())),
         "sandbox" => Some(Box::new(sandbox::Sandbox::new())),
         _ => None,
-    };
-    if let Some(scenario) = scenario.as_ref() {
-        assert_eq!(scenario.name(), name);
     }
-    scenario
 }
 
 pub fn load(name: &str) -> Box<dyn Scenario> {
@@ -201,6 +290,22 @@ pub fn load(name: &str) -> Box<dyn Scenario> {
     }
 }
 
+/// Loads a scenario scripted by a wasm director module, which is passed like
+/// team code. `name` can't be the name of a built-in scenario.
+pub fn load_director(name: &str, code: &Code) -> Result<Box<dyn Scenario>, crate::vm::Error> {
+    if load_safe(name).is_some() {
+        return Err(crate::vm::Error {
+            msg: format!("Scenario {name:?} already exists"),
+        });
+    }
+    let Code::Wasm(wasm) = code else {
+        return Err(crate::vm::Error {
+            msg: "Director must be a wasm module".to_string(),
+        });
+    };
+    Ok(Box::new(director::Director::new(name, wasm)?))
+}
+
 pub fn list() -> Vec<(String, Vec<String>)> {
     [
         ("Introduction", vec!["welcome"]),
@@ -224,7 +329,13 @@ pub fn list() -> Vec<(String, Vec<String>)> {
         ),
         (
             "Challenge",
-            vec!["gunnery", "cruiser_defense", "planetary_defense", "race"],
+            vec![
+                "gunnery",
+                "gunnery_asteroids",
+                "cruiser_defense",
+                "planetary_defense",
+                "race",
+            ],
         ),
         ("Tournament", vec!["fighter_duel", "mini_fleet"]),
         (
@@ -235,8 +346,15 @@ pub fn list() -> Vec<(String, Vec<String>)> {
                 "asteroid_duel",
                 "squadrons",
                 "fleet",
+                "free_for_all",
+                "capture_points",
+                "toroidal_duel",
+                "obstacle_duel",
+                "nebula",
                 "belt",
                 "orbit",
+                "fighter_duel_royale",
+                "fleet_logistics",
             ],
         ),
     ]
@@ -289,7 +407,14 @@ pub fn check_victory_with_filter(
 
 pub fn check_tutorial_victory(sim: &Simulation, max_ticks: u32) -> Status {
     match check_victory_with_filter(sim, max_ticks, |ship| {
-        ![ShipClass::Missile, ShipClass::Torpedo].contains(&ship.data().class)
+        ![
+            ShipClass::Missile,
+            ShipClass::Torpedo,
+            ShipClass::Chaff,
+            ShipClass::Flare,
+            ShipClass::Mine,
+        ]
+        .contains(&ship.data().class)
     }) {
         x @ Status::Victory { team: 0 } => x,
         Status::Victory { .. } => Status::Failed,
@@ -311,6 +436,78 @@ pub fn check_capital_ship_tournament_victory(sim: &Simulation) -> Status {
     })
 }
 
+/// A team wins by reaching `target_score` control points or by destroying
+/// the other team. When time runs out the higher score wins.
+pub fn check_score_victory(sim: &Simulation, target_score: f64, max_ticks: u32) -> Status {
+    match check_victory_with_filter(sim, u32::MAX, |ship| {
+        [ShipClass::Fighter, ShipClass::Frigate, ShipClass::Cruiser].contains(&ship.data().class)
+            && ship.data().team < 2
+    }) {
+        Status::Running => {}
+        status => return status,
+    }
+
+    let scores = [sim.objectives().score(0), sim.objectives().score(1)];
+    if scores.iter().all(|&score| score < target_score) && sim.tick() < max_ticks - 1 {
+        Status::Running
+    } else if scores[0] > scores[1] {
+        Status::Victory { team: 0 }
+    } else if scores[1] > scores[0] {
+        Status::Victory { team: 1 }
+    } else {
+        Status::Draw
+    }
+}
+
+/// Remaining health of each team's combat ships, indexed by team ID.
+pub fn team_points(sim: &Simulation, num_teams: usize) -> Vec<f64> {
+    let mut points = vec![0.0; num_teams];
+    for &handle in sim.ships.iter() {
+        let ship = sim.ship(handle);
+        let data = ship.data();
+        if [ShipClass::Fighter, ShipClass::Frigate, ShipClass::Cruiser].contains(&data.class)
+            && (data.team as usize) < num_teams
+        {
+            points[data.team as usize] += data.health;
+        }
+    }
+    points
+}
+
+/// Last team standing wins. If time runs out the team with the most
+/// remaining health wins.
+pub fn check_free_for_all_victory(sim: &Simulation, num_teams: usize, max_ticks: u32) -> Status {
+    let points = team_points(sim, num_teams);
+    let alive: Vec<usize> = (0..num_teams).filter(|&i| points[i] > 0.0).collect();
+    if alive.is_empty() {
+        Status::Draw
+    } else if alive.len() == 1 {
+        Status::Victory {
+            team: alive[0] as i32,
+        }
+    } else if sim.tick() >= max_ticks - 1 {
+        let ranking = rank_by_score(&points);
+        let winners: Vec<usize> = (0..num_teams).filter(|&i| ranking[i] == 0).collect();
+        if winners.len() == 1 {
+            Status::Victory {
+                team: winners[0] as i32,
+            }
+        } else {
+            Status::Draw
+        }
+    } else {
+        Status::Running
+    }
+}
+
+/// Converts scores (higher is better) into finishing positions.
+pub fn rank_by_score<T: PartialOrd>(scores: &[T]) -> Vec<usize> {
+    scores
+        .iter()
+        .map(|score| scores.iter().filter(|other| *other > score).count())
+        .collect()
+}
+
 pub fn fighter_without_missiles(team: i32) -> ShipData {
     let mut data = fighter(team);
     data.missile_launchers.pop();
@@ -351,3 +548,20 @@ pub fn place_teams(rng: &mut dyn Rng, world_size: f64) -> Vec<Placement> {
     placements.shuffle(rng);
     placements
 }
+
+/// Spreads `num_teams` teams evenly around a ring, facing the center.
+pub fn place_teams_on_ring(rng: &mut dyn Rng, world_size: f64, num_teams: usize) -> Vec<Placement> {
+    let radius = world_size * 0.4;
+    let offset = rng.random_range(0.0..std::f64::consts::TAU);
+    let mut placements: Vec<Placement> = (0..num_teams)
+        .map(|i| {
+            let angle = offset + i as f64 * std::f64::consts::TAU / num_teams as f64;
+            Placement {
+                position: Rotation2::new(angle).transform_vector(&vector![radius, 0.0]),
+                heading: angle + std::f64::consts::PI,
+            }
+        })
+        .collect();
+    placements.shuffle(rng);
+    placements
+}
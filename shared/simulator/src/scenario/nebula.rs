@@ -0,0 +1,103 @@
+use super::prelude::*;
+use crate::nebula::{self, Nebula, Region};
+
+/// Squadrons of fighters and minelaying frigates fight around nebulae that
+/// hide ships from radar and slow them down.
+pub struct NebulaScenario {}
+
+impl NebulaScenario {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Scenario for NebulaScenario {
+    fn name(&self) -> String {
+        "nebula".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Nebula".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let placements = place_teams(&mut rng, self.world_size());
+
+        for (team, placement) in placements.into_iter().enumerate() {
+            let Placement { position, heading } = placement;
+            for i in 0..4 {
+                ship::create(
+                    sim,
+                    vector![position.x, position.y + (i as f64 - 1.5) * 500.0],
+                    vector![0.0, 0.0],
+                    heading,
+                    fighter(team as i32),
+                );
+            }
+            ship::create(
+                sim,
+                vector![position.x - position.x.signum() * 1000.0, position.y],
+                vector![0.0, 0.0],
+                heading,
+                ship::frigate_minelayer(team as i32),
+            );
+        }
+
+        nebula::add_nebula(
+            sim,
+            Nebula::new(Region::Circle {
+                center: point![0.0, 0.0],
+                radius: rng.random_range(3000.0..5000.0),
+            }),
+        );
+        // Mirrored bands above and below the center.
+        let x = rng.random_range(2000.0..6000.0);
+        for signum in [-1.0, 1.0] {
+            let y0 = signum * 8000.0;
+            let y1 = signum * 11000.0;
+            let mut vertices = vec![
+                point![-x, y0],
+                point![x, y0],
+                point![x + 2000.0, y1],
+                point![-x - 2000.0, y1],
+            ];
+            if signum < 0.0 {
+                vertices.reverse();
+            }
+            nebula::add_nebula(sim, Nebula::new(Region::Polygon(vertices)));
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        check_tournament_victory(sim)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn is_tournament(&self) -> bool {
+        true
+    }
+
+    fn aspect_dependent_rcs(&self) -> bool {
+        true
+    }
+
+    fn countermeasures(&self) -> bool {
+        true
+    }
+
+    fn energy(&self) -> bool {
+        true
+    }
+
+    fn collision_damage(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,88 @@
+use super::prelude::*;
+use crate::obstacle;
+
+/// Fighter duel among rocks that block bullets, radar and radio.
+pub struct ObstacleDuel {}
+
+impl ObstacleDuel {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Scenario for ObstacleDuel {
+    fn name(&self) -> String {
+        "obstacle_duel".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Obstacle Duel".into()
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        let placements = place_teams(&mut rng, self.world_size());
+
+        for (team, placement) in placements.into_iter().enumerate() {
+            let Placement { position, heading } = placement;
+            ship::create(
+                sim,
+                position,
+                vector![0.0, 0.0],
+                heading,
+                fighter(team as i32),
+            );
+        }
+
+        // Rocks stay clear of the starting positions near the edges.
+        let bound = self.world_size() * 0.3;
+        for _ in 0..8 {
+            let center = point![
+                rng.random_range(-bound..bound),
+                rng.random_range(-bound..bound)
+            ];
+            let radius = rng.random_range(1000.0..3000.0);
+            let n = 7;
+            let vertices: Vec<_> = (0..n)
+                .map(|i| {
+                    let angle = TAU * i as f64 / n as f64;
+                    let r = radius * rng.random_range(0.7..1.0);
+                    center + vector![angle.cos(), angle.sin()] * r
+                })
+                .collect();
+            obstacle::add_obstacle(sim, &vertices);
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        check_tournament_victory(sim)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn is_tournament(&self) -> bool {
+        true
+    }
+
+    fn aspect_dependent_rcs(&self) -> bool {
+        true
+    }
+
+    fn countermeasures(&self) -> bool {
+        true
+    }
+
+    fn energy(&self) -> bool {
+        true
+    }
+
+    fn collision_damage(&self) -> bool {
+        true
+    }
+}
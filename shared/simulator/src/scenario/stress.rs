@@ -91,6 +91,8 @@ impl Scenario for BulletStressScenario {
                     team: 0,
                     color: color::to_u32(vector![1.00, 0.63, 0.00, 0.30]),
                     ttl: 100.0,
+                    shooter: None,
+                    fragment: false,
                 },
             );
         }
@@ -15,6 +15,37 @@ impl Scenario for TestScenario {
     }
 }
 
+/// Like `test`, with the optional mechanics that scenarios opt into enabled.
+pub struct MechanicsTestScenario {}
+
+impl Scenario for MechanicsTestScenario {
+    fn name(&self) -> String {
+        "mechanics_test".into()
+    }
+
+    fn init(&mut self, _sim: &mut Simulation, _seed: u32) {}
+
+    fn aspect_dependent_rcs(&self) -> bool {
+        true
+    }
+
+    fn countermeasures(&self) -> bool {
+        true
+    }
+
+    fn energy(&self) -> bool {
+        true
+    }
+
+    fn collision_damage(&self) -> bool {
+        true
+    }
+
+    fn world_size(&self) -> f64 {
+        simulation::MAX_WORLD_SIZE
+    }
+}
+
 pub struct BasicScenario {}
 
 impl Scenario for BasicScenario {
@@ -241,7 +272,7 @@ impl Scenario for FrigatePointDefense {
     fn init(&mut self, sim: &mut Simulation, seed: u32) {
         let mut rng = new_rng(seed);
 
-        let mut data = frigate(0);
+        let mut data = ship::frigate_point_defense(0);
         data.missile_launchers.clear();
         ship::create(sim, vector![0.0, 0.0], vector![0.0, 0.0], 0.0, data);
 
@@ -317,3 +348,89 @@ impl Scenario for RadarTest {
         }
     }
 }
+
+/// Runs a built-in scenario with the deterministic integrator. Loaded by
+/// prefixing the scenario's name with `deterministic_`.
+pub struct Deterministic {
+    scenario: Box<dyn Scenario>,
+}
+
+impl Deterministic {
+    pub fn new(scenario: Box<dyn Scenario>) -> Self {
+        Self { scenario }
+    }
+}
+
+impl Scenario for Deterministic {
+    fn name(&self) -> String {
+        format!("deterministic_{}", self.scenario.name())
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        self.scenario.init(sim, seed)
+    }
+
+    fn tick(&mut self, sim: &mut Simulation) {
+        self.scenario.tick(sim)
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        self.scenario.status(sim)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        self.scenario.initial_code()
+    }
+
+    fn solution(&self) -> Code {
+        self.scenario.solution()
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        self.scenario.lines()
+    }
+
+    fn ranking(&self, sim: &Simulation) -> Vec<usize> {
+        self.scenario.ranking(sim)
+    }
+
+    fn score_time(&self, sim: &Simulation) -> f64 {
+        self.scenario.score_time(sim)
+    }
+
+    fn topology(&self) -> Topology {
+        self.scenario.topology()
+    }
+
+    fn physics_mode(&self) -> PhysicsMode {
+        PhysicsMode::Deterministic
+    }
+
+    fn boundary(&self) -> Option<Boundary> {
+        self.scenario.boundary()
+    }
+
+    fn logistics(&self) -> Option<Logistics> {
+        self.scenario.logistics()
+    }
+
+    fn aspect_dependent_rcs(&self) -> bool {
+        self.scenario.aspect_dependent_rcs()
+    }
+
+    fn countermeasures(&self) -> bool {
+        self.scenario.countermeasures()
+    }
+
+    fn energy(&self) -> bool {
+        self.scenario.energy()
+    }
+
+    fn collision_damage(&self) -> bool {
+        self.scenario.collision_damage()
+    }
+
+    fn world_size(&self) -> f64 {
+        self.scenario.world_size()
+    }
+}
@@ -0,0 +1,78 @@
+use super::prelude::*;
+
+/// Fighter duel in a world without walls. Ships and bullets leaving one edge
+/// reappear on the opposite edge.
+pub struct ToroidalDuel {}
+
+impl ToroidalDuel {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Scenario for ToroidalDuel {
+    fn name(&self) -> String {
+        "toroidal_duel".into()
+    }
+
+    fn human_name(&self) -> String {
+        "Toroidal Duel".into()
+    }
+
+    fn world_size(&self) -> f64 {
+        20e3
+    }
+
+    fn topology(&self) -> Topology {
+        Topology::Toroidal
+    }
+
+    fn init(&mut self, sim: &mut Simulation, seed: u32) {
+        let mut rng = new_rng(seed);
+        // Start the teams closer to each other directly than across the seam.
+        let placements = place_teams(&mut rng, self.world_size() / 2.0);
+
+        for (team, placement) in placements.into_iter().enumerate() {
+            let Placement { position, heading } = placement;
+            ship::create(
+                sim,
+                position,
+                vector![0.0, 0.0],
+                heading,
+                fighter(team as i32),
+            );
+        }
+    }
+
+    fn status(&self, sim: &Simulation) -> Status {
+        check_tournament_victory(sim)
+    }
+
+    fn initial_code(&self) -> Vec<Code> {
+        vec![empty_ai(), reference_ai()]
+    }
+
+    fn solution(&self) -> Code {
+        reference_ai()
+    }
+
+    fn is_tournament(&self) -> bool {
+        true
+    }
+
+    fn aspect_dependent_rcs(&self) -> bool {
+        true
+    }
+
+    fn countermeasures(&self) -> bool {
+        true
+    }
+
+    fn energy(&self) -> bool {
+        true
+    }
+
+    fn collision_damage(&self) -> bool {
+        true
+    }
+}
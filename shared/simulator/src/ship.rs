@@ -1,15 +1,21 @@
 use super::index_set::{HasIndex, Index};
 use super::rng::new_rng;
+use crate::beam::{self, Beam};
 use crate::color;
+use crate::energy::{self, Energy};
+use crate::esm::Esm;
+use crate::integrator::PhysicsMode;
+use crate::logistics;
 use crate::model;
-use crate::radar::Radar;
+use crate::radar::{Radar, ScanResult};
 use crate::radio::Radio;
 use crate::rng;
 use crate::simulation::{self, PHYSICS_TICK_LENGTH};
 use crate::simulation::{Particle, Simulation};
-use crate::{bullet, collision};
+use crate::stats;
+use crate::{bullet, collision, topology};
 use bullet::BulletData;
-use nalgebra::{vector, Rotation2, UnitComplex, Vector2};
+use nalgebra::{vector, Point2, Rotation2, UnitComplex, Vector2};
 use oort_api::{Ability, Class, ClassStats};
 use rand::RngExt;
 use rapier2d_f64::prelude::*;
@@ -45,6 +51,9 @@ pub enum ShipClass {
     Torpedo,
     Planet,
     Beacon,
+    Chaff,
+    Flare,
+    Mine,
 }
 
 impl ShipClass {
@@ -60,10 +69,20 @@ impl ShipClass {
             ShipClass::Torpedo => "torpedo",
             ShipClass::Planet => "planet",
             ShipClass::Beacon => "beacon",
+            ShipClass::Chaff => "chaff",
+            ShipClass::Flare => "flare",
+            ShipClass::Mine => "mine",
         }
     }
 }
 
+impl ShipClass {
+    /// Chaff and flares are passive decoys with no ship code.
+    pub fn is_countermeasure(&self) -> bool {
+        matches!(self, ShipClass::Chaff | ShipClass::Flare)
+    }
+}
+
 impl FromStr for ShipClass {
     type Err = anyhow::Error;
 
@@ -79,6 +98,9 @@ impl FromStr for ShipClass {
             "torpedo" => Ok(ShipClass::Torpedo),
             "planet" => Ok(ShipClass::Planet),
             "beacon" => Ok(ShipClass::Beacon),
+            "chaff" => Ok(ShipClass::Chaff),
+            "flare" => Ok(ShipClass::Flare),
+            "mine" => Ok(ShipClass::Mine),
             _ => Err(anyhow::anyhow!("Unknown ship class {:?}", s)),
         }
     }
@@ -101,6 +123,10 @@ pub struct Gun {
     pub burst_size: i32,
     pub ttl: f32,
     pub bullet_mass: f64,
+    /// Energy drawn while reloading, in watts.
+    pub power: f64,
+    /// Fires a hitscan beam instead of bullets.
+    pub beam: Option<Beam>,
 }
 
 #[derive(Debug, Clone)]
@@ -111,6 +137,23 @@ pub struct MissileLauncher {
     pub initial_speed: f64,
     pub offset: Vector2<f64>,
     pub angle: f64,
+    /// Number of munitions left, or `None` if unlimited.
+    pub count: Option<u32>,
+}
+
+impl MissileLauncher {
+    /// Uses up a round and starts reloading. Returns false if the launcher
+    /// isn't ready or is empty.
+    fn try_launch(&mut self) -> bool {
+        if self.reload_ticks_remaining > 0 || self.count == Some(0) {
+            return false;
+        }
+        self.reload_ticks_remaining = self.reload_ticks;
+        if let Some(count) = self.count.as_mut() {
+            *count -= 1;
+        }
+        true
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -120,6 +163,8 @@ pub struct ShipAbility {
     pub reload_time: f64,
     pub active_time_remaining: f64,
     pub reload_time_remaining: f64,
+    /// Energy drawn while active, in watts.
+    pub power: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -149,15 +194,26 @@ pub struct ShipData {
     pub crash_message: Option<String>,
     pub ttl: Option<u64>,
     pub fuel: Option<f64>,
+    /// Rounds of gun ammunition left, or `None` if unlimited.
+    pub ammo: Option<i32>,
     pub guns: Vec<Gun>,
     pub missile_launchers: Vec<MissileLauncher>,
+    pub countermeasure_launchers: Vec<MissileLauncher>,
     pub radars: Vec<Radar>,
     pub radar_cross_section: f64,
     pub radar_radius: i32,
     pub radios: Vec<Radio>,
+    pub esm: Esm,
+    pub energy: Option<Energy>,
     pub abilities: Vec<ShipAbility>,
     pub target: Option<Box<Target>>,
     pub warhead: Warhead,
+    /// Ship that launched this one.
+    pub parent: Option<ShipHandle>,
+    /// Class reported to enemy radars in place of `class`.
+    pub radar_class: Option<ShipClass>,
+    /// Contact illuminated by the parent's radar, for semi-active homing.
+    pub illuminated: Option<ScanResult>,
 }
 
 impl From<ClassStats> for ShipData {
@@ -199,15 +255,22 @@ impl Default for ShipData {
             crash_message: None,
             ttl: None,
             fuel: None,
+            ammo: None,
             guns: vec![],
             missile_launchers: vec![],
+            countermeasure_launchers: vec![],
             radars: vec![],
             radar_cross_section: 10.0,
             radar_radius: 1,
             radios: vec![],
+            esm: Default::default(),
+            energy: None,
             abilities: vec![],
             target: None,
             warhead: Default::default(),
+            parent: None,
+            radar_class: None,
+            illuminated: None,
         }
     }
 }
@@ -230,6 +293,8 @@ impl Default for Gun {
             burst_size: 1,
             ttl: 10.0,
             bullet_mass: 1.0,
+            power: 0.0,
+            beam: None,
         }
     }
 }
@@ -242,6 +307,7 @@ impl Default for ShipAbility {
             reload_time: 0.0,
             active_time_remaining: 0.0,
             reload_time_remaining: 0.0,
+            power: 0.0,
         }
     }
 }
@@ -278,10 +344,76 @@ pub fn vulcan_gun() -> Gun {
         speed: 1000.0,
         inaccuracy: 0.0025,
         bullet_mass: 0.1,
+        power: 500e3,
         ..Default::default()
     }
 }
 
+pub fn point_defense_laser() -> Gun {
+    Gun {
+        magazine_size: 1,
+        magazine_reload_ticks: 0,
+        reload_ticks: 1,
+        max_angle: TAU,
+        power: 3e6,
+        beam: Some(Beam {
+            damage: 3.0,
+            range: 1500.0,
+            heat_per_shot: 0.02,
+            cooling: 0.01,
+            heat: 0.0,
+        }),
+        ..Default::default()
+    }
+}
+
+pub const CHAFF_TTL: u64 = 5 * 60;
+pub const FLARE_TTL: u64 = 3 * 60;
+
+/// Chaff and flare launchers firing backwards from `offset`, each holding
+/// `count` rounds.
+fn countermeasure_launchers(offset: Vector2<f64>, count: u32) -> Vec<MissileLauncher> {
+    let launcher = MissileLauncher {
+        class: ShipClass::Chaff,
+        reload_ticks: 60,
+        reload_ticks_remaining: 0,
+        initial_speed: 30.0,
+        offset,
+        angle: TAU / 2.0,
+        count: Some(count),
+    };
+    vec![
+        launcher.clone(),
+        MissileLauncher {
+            class: ShipClass::Flare,
+            initial_speed: 60.0,
+            ..launcher
+        },
+    ]
+}
+
+pub const MINE_TTL: u64 = 120 * 60;
+/// Ticks after launch before a mine's fuse is live.
+pub const MINE_ARMING_TICKS: u64 = 3 * 60;
+/// Distance from an enemy hull at which a mine detonates.
+pub const MINE_FUSE_RADIUS: f64 = 100.0;
+/// Rate at which a mine sheds its velocity as it comes to a halt, per second.
+const MINE_BRAKING: f64 = 2.0;
+
+/// Mine launcher dropping mines behind the ship from `offset`, holding `count`
+/// mines.
+fn mine_launcher(offset: Vector2<f64>, count: u32) -> MissileLauncher {
+    MissileLauncher {
+        class: ShipClass::Mine,
+        reload_ticks: 60,
+        reload_ticks_remaining: 0,
+        initial_speed: 30.0,
+        offset,
+        angle: TAU / 2.0,
+        count: Some(count),
+    }
+}
+
 pub fn fighter(team: i32) -> ShipData {
     ShipData {
         class: ShipClass::Fighter,
@@ -298,7 +430,9 @@ pub fn fighter(team: i32) -> ShipData {
             initial_speed: 100.0,
             offset: vector![20.0, 0.0],
             angle: 0.0,
+            count: None,
         }],
+        countermeasure_launchers: countermeasure_launchers(vector![-10.0, 0.0], 4),
         radars: vec![Radar {
             power: 20e3,
             rx_cross_section: 5.0,
@@ -308,10 +442,12 @@ pub fn fighter(team: i32) -> ShipData {
         radar_cross_section: 10.0,
         radar_radius: 10,
         radios: vec![radio(), radio()],
+        energy: Some(Energy::new(1e6, 10e6)),
         abilities: vec![ShipAbility {
             ability: Ability::Boost,
             active_time: 2.0,
             reload_time: 10.0,
+            power: 1e6,
             ..Default::default()
         }],
         ..ShipData::from(Class::Fighter.default_stats())
@@ -332,6 +468,7 @@ pub fn frigate(team: i32) -> ShipData {
                 offset: vector![40.0, 0.0],
                 bullet_mass: 1.0,
                 ttl: 60.0,
+                power: 4e6,
                 ..Default::default()
             },
             Gun {
@@ -352,7 +489,9 @@ pub fn frigate(team: i32) -> ShipData {
             initial_speed: 100.0,
             offset: vector![120.0, 0.0],
             angle: 0.0,
+            count: None,
         }],
+        countermeasure_launchers: countermeasure_launchers(vector![-100.0, 0.0], 10),
         radars: vec![Radar {
             power: 100e3,
             rx_cross_section: 10.0,
@@ -361,10 +500,33 @@ pub fn frigate(team: i32) -> ShipData {
         radar_cross_section: 30.0,
         radar_radius: 120,
         radios: vec![radio(), radio(), radio(), radio()],
+        energy: Some(Energy::new(8e6, 50e6)),
         ..ShipData::from(Class::Frigate.default_stats())
     }
 }
 
+/// Frigate loadout with a mine launcher as weapon 4.
+pub fn frigate_minelayer(team: i32) -> ShipData {
+    let mut data = frigate(team);
+    data.missile_launchers
+        .push(mine_launcher(vector![-110.0, 0.0], 6));
+    data
+}
+
+/// Frigate loadout with lasers in place of the turreted guns.
+pub fn frigate_point_defense(team: i32) -> ShipData {
+    let mut data = frigate(team);
+    data.guns[1] = Gun {
+        offset: vector![0.0, 30.0],
+        ..point_defense_laser()
+    };
+    data.guns[2] = Gun {
+        offset: vector![0.0, -30.0],
+        ..point_defense_laser()
+    };
+    data
+}
+
 pub const CRUISER_RADAR_CROSS_SECTION: f64 = 40.0;
 
 pub fn cruiser(team: i32) -> ShipData {
@@ -375,6 +537,7 @@ pub fn cruiser(team: i32) -> ShipData {
         initial_speed: 100.0,
         offset: vector![0.0, 0.0],
         angle: 0.0,
+        count: None,
     };
     ShipData {
         class: ShipClass::Cruiser,
@@ -392,6 +555,7 @@ pub fn cruiser(team: i32) -> ShipData {
             burst_size: 6,
             ttl: 120.0,
             bullet_mass: 2.0,
+            power: 8e6,
             ..Default::default()
         }],
         missile_launchers: vec![
@@ -412,8 +576,10 @@ pub fn cruiser(team: i32) -> ShipData {
                 initial_speed: 100.0,
                 offset: vector![260.0, 0.0],
                 angle: 0.0,
+                count: None,
             },
         ],
+        countermeasure_launchers: countermeasure_launchers(vector![-200.0, 0.0], 20),
         radars: vec![
             Radar {
                 power: 200e3,
@@ -438,16 +604,26 @@ pub fn cruiser(team: i32) -> ShipData {
             radio(),
             radio(),
         ],
+        energy: Some(Energy::new(15e6, 100e6)),
         abilities: vec![ShipAbility {
             ability: Ability::Shield,
             active_time: 1.0,
             reload_time: 5.0,
+            power: 10e6,
             ..Default::default()
         }],
         ..ShipData::from(Class::Cruiser.default_stats())
     }
 }
 
+/// Cruiser loadout with a mine launcher as weapon 4.
+pub fn cruiser_minelayer(team: i32) -> ShipData {
+    let mut data = cruiser(team);
+    data.missile_launchers
+        .push(mine_launcher(vector![-230.0, 0.0], 12));
+    data
+}
+
 pub fn asteroid(variant: i32) -> ShipData {
     ShipData {
         class: ShipClass::Asteroid { variant },
@@ -470,6 +646,11 @@ pub fn big_asteroid(variant: i32) -> ShipData {
     }
 }
 
+/// Number of asteroids a big asteroid breaks into when destroyed.
+pub const ASTEROID_FRAGMENTS: usize = 5;
+/// Distance from the center of a big asteroid at which its fragments spawn.
+const ASTEROID_FRAGMENT_OFFSET: f64 = 250.0;
+
 pub fn target(team: i32) -> ShipData {
     ShipData {
         class: ShipClass::Target,
@@ -546,6 +727,63 @@ pub fn torpedo(team: i32) -> ShipData {
     }
 }
 
+/// A cloud of radar reflectors. Its cross section fades as it disperses.
+pub fn chaff(team: i32) -> ShipData {
+    ShipData {
+        class: ShipClass::Chaff,
+        team,
+        health: 1.0,
+        radar_cross_section: 100.0,
+        radar_radius: 20,
+        ttl: Some(CHAFF_TTL),
+        warhead: Warhead {
+            count: 0,
+            ..Default::default()
+        },
+        ..ShipData::from(Class::Target.default_stats())
+    }
+}
+
+/// A hot decoy which only missile and torpedo seekers can see. It burns out
+/// over its lifetime.
+pub fn flare(team: i32) -> ShipData {
+    ShipData {
+        class: ShipClass::Flare,
+        team,
+        health: 1.0,
+        radar_cross_section: 50.0,
+        radar_radius: 3,
+        ttl: Some(FLARE_TTL),
+        warhead: Warhead {
+            count: 0,
+            ..Default::default()
+        },
+        ..ShipData::from(Class::Target.default_stats())
+    }
+}
+
+/// A stationary proximity-fused mine. It has no ship code: once armed it
+/// detonates when an enemy comes within range, spraying fragments in every
+/// direction.
+pub fn mine(team: i32) -> ShipData {
+    ShipData {
+        class: ShipClass::Mine,
+        team,
+        health: 20.0,
+        radar_cross_section: 0.05,
+        radar_radius: 3,
+        ttl: Some(MINE_TTL),
+        warhead: Warhead {
+            count: 100,
+            mass: 0.25,
+            width: TAU,
+            speed: 1e3,
+            ttl: 0.2,
+        },
+        ..ShipData::from(Class::Mine.default_stats())
+    }
+}
+
 pub fn beacon(team: i32) -> ShipData {
     ShipData {
         class: ShipClass::Beacon,
@@ -590,7 +828,10 @@ pub fn create(
         .restitution(restitution)
         .collision_groups(if data.class == ShipClass::Planet {
             collision::planet_interaction_groups()
-        } else if data.class == ShipClass::Beacon {
+        } else if matches!(
+            data.class,
+            ShipClass::Beacon | ShipClass::Chaff | ShipClass::Flare
+        ) {
             collision::beacon_interaction_groups()
         } else {
             collision::ship_interaction_groups(team)
@@ -603,9 +844,19 @@ pub fn create(
     for gun in data.guns.iter_mut() {
         gun.magazine_remaining = gun.magazine_size;
     }
+    if !sim.countermeasures() {
+        data.countermeasure_launchers.clear();
+    }
+    if !sim.energy() {
+        data.energy = None;
+    }
+    logistics::init_ship(sim, &mut data);
+    stats::register(sim, handle, team, data.class);
 
     sim.ships.insert(handle);
-    sim.new_ships.push((data.team, handle));
+    if !data.class.is_countermeasure() && data.class != ShipClass::Mine {
+        sim.new_ships.push((data.team, handle));
+    }
     sim.ship_data.insert(handle.index(), data);
 
     handle
@@ -637,7 +888,17 @@ impl<'a> ShipAccessor<'a> {
     }
 
     pub fn heading(&self) -> Real {
-        self.body().rotation().angle().rem_euclid(TAU)
+        let body = self.body();
+        if self.simulation.physics_mode() == PhysicsMode::Deterministic {
+            if let Some(heading) = self
+                .simulation
+                .integrator
+                .heading(RigidBodyHandle(self.handle.index()), body)
+            {
+                return heading;
+            }
+        }
+        body.rotation().angle().rem_euclid(TAU)
     }
 
     pub fn angular_velocity(&self) -> Real {
@@ -770,7 +1031,20 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             if gun.reload_ticks_remaining > 0 {
                 return;
             }
+            // Exit if out of ammunition
+            if gun.beam.is_none() && ship_data.ammo.is_some_and(|ammo| ammo < gun.burst_size) {
+                return;
+            }
+            // Exit if beam is overheated
+            if let Some(beam) = gun.beam.as_mut()
+                && !beam.try_heat()
+            {
+                return;
+            }
             gun.reload_ticks_remaining = gun.reload_ticks;
+            if let (None, Some(ammo)) = (&gun.beam, ship_data.ammo.as_mut()) {
+                *ammo -= gun.burst_size;
+            }
             gun.magazine_remaining -= gun.burst_size;
             if gun.magazine_remaining <= 0 {
                 gun.magazine_remaining = gun.magazine_size;
@@ -778,6 +1052,12 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             }
             gun.clone()
         };
+        let shots = if gun.beam.is_some() {
+            1
+        } else {
+            gun.burst_size
+        };
+        stats::record_shots(self.simulation, self.handle, shots as u32);
 
         let mut rng =
             rng::new_rng(self.simulation.tick() ^ u64::from(self.handle) as u32 ^ index as u32);
@@ -790,6 +1070,23 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             .rem_euclid(TAU)
             .clamp(gun.min_angle, gun.max_angle);
 
+        if let Some(beam) = gun.beam.as_ref() {
+            let body = self.body();
+            let rot = body.position().rotation * UnitComplex::new(relative_heading);
+            let origin = body.position().translation.vector
+                + body.position().rotation.transform_vector(&gun.offset);
+            let direction = rot.transform_vector(&vector![1.0, 0.0]);
+            beam::fire(
+                self.simulation,
+                team,
+                Some(self.handle),
+                origin,
+                direction,
+                beam,
+            );
+            return;
+        }
+
         for _ in 0..gun.burst_size {
             let relative_heading = if gun.inaccuracy > 0.0 {
                 relative_heading + rng.random_range(-gun.inaccuracy..gun.inaccuracy)
@@ -816,6 +1113,8 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
                     team,
                     color,
                     ttl: gun.ttl + t as f32,
+                    shooter: Some(self.handle),
+                    fragment: false,
                 },
             );
             t += dt;
@@ -828,16 +1127,43 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             if let Some(missile_launcher) =
                 ship_data.missile_launchers.get_mut(index as usize).as_mut()
             {
-                if missile_launcher.reload_ticks_remaining > 0 {
+                if !missile_launcher.try_launch() {
                     return;
                 }
-                missile_launcher.reload_ticks_remaining = missile_launcher.reload_ticks;
                 missile_launcher.clone()
             } else {
                 return;
             }
         };
+        if matches!(
+            missile_launcher.class,
+            ShipClass::Missile | ShipClass::Torpedo
+        ) {
+            stats::record_missile_launch(self.simulation, self.handle);
+        }
+        self.launch(&missile_launcher);
+    }
 
+    pub fn launch_countermeasure(&mut self, class: ShipClass) {
+        let launcher = {
+            let ship_data = self.data_mut();
+            if let Some(launcher) = ship_data
+                .countermeasure_launchers
+                .iter_mut()
+                .find(|x| x.class == class)
+            {
+                if !launcher.try_launch() {
+                    return;
+                }
+                launcher.clone()
+            } else {
+                return;
+            }
+        };
+        self.launch(&launcher);
+    }
+
+    fn launch(&mut self, missile_launcher: &MissileLauncher) {
         let speed = missile_launcher.initial_speed;
         let offset = missile_launcher.offset;
         let body = self.body();
@@ -846,17 +1172,20 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         let rot2 = rot * UnitComplex::new(missile_launcher.angle);
         let v = body.linvel() + rot2.transform_vector(&vector![speed, 0.0]);
         let team = self.data().team;
-        create(
-            self.simulation,
-            p,
-            v,
-            rot2.angle(),
-            match missile_launcher.class {
-                ShipClass::Missile => missile(team),
-                ShipClass::Torpedo => torpedo(team),
-                _ => unimplemented!(),
-            },
-        );
+        let mut data = match missile_launcher.class {
+            ShipClass::Missile => missile(team),
+            ShipClass::Torpedo => torpedo(team),
+            ShipClass::Chaff => chaff(team),
+            ShipClass::Flare => flare(team),
+            ShipClass::Mine => mine(team),
+            _ => unimplemented!(),
+        };
+        data.parent = Some(self.handle);
+        // Countermeasures show up on radar as the ship that launched them.
+        if data.class.is_countermeasure() {
+            data.radar_class = Some(self.data().class);
+        }
+        create(self.simulation, p, v, rot2.angle(), data);
     }
 
     pub fn aim(&mut self, index: i64, heading: f64) {
@@ -876,6 +1205,7 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
 
         let warhead = self.data().warhead.clone();
         let team = self.data().team;
+        let shooter = self.data().parent.or(Some(self.handle));
         let p =
             self.body().position().translation.vector - self.body().linvel() * PHYSICS_TICK_LENGTH;
         let mut rng = new_rng(0);
@@ -895,6 +1225,8 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
                     team,
                     color: color::to_u32(color),
                     ttl: warhead.ttl,
+                    shooter,
+                    fragment: true,
                 },
             );
             self.simulation.events.particles.push(Particle {
@@ -906,7 +1238,61 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         }
     }
 
+    /// Breaks up a destroyed asteroid. Big asteroids split into smaller
+    /// asteroids that keep the parent's momentum and small asteroids leave
+    /// debris behind.
+    fn fragment(&mut self) {
+        let p = self.body().position().translation.vector;
+        let v = *self.body().linvel();
+        let team = self.data().team;
+        let class = self.data().class;
+        let sim = &mut *self.simulation;
+        match class {
+            ShipClass::BigAsteroid { .. } => {
+                let angle = sim.rng.random_range(0.0..TAU);
+                for i in 0..ASTEROID_FRAGMENTS {
+                    let rot = Rotation2::new(angle + i as f64 * TAU / ASTEROID_FRAGMENTS as f64);
+                    let dir = rot.transform_vector(&vector![1.0, 0.0]);
+                    let speed = sim.rng.random_range(20.0..100.0);
+                    let heading = sim.rng.random_range(0.0..TAU);
+                    let mut data = asteroid(sim.rng.random_range(0..30));
+                    data.team = team;
+                    create(
+                        sim,
+                        p + dir * ASTEROID_FRAGMENT_OFFSET,
+                        v + dir * speed,
+                        heading,
+                        data,
+                    );
+                }
+            }
+            ShipClass::Asteroid { .. } => {
+                // Debris is cosmetic, so it must not perturb the gameplay rng.
+                let mut rng = new_rng(sim.tick());
+                for _ in 0..20 {
+                    let rot = Rotation2::new(rng.random_range(0.0..TAU));
+                    let dir = rot.transform_vector(&vector![1.0, 0.0]);
+                    let offset = rng.random_range(0.0..50.0);
+                    let speed = rng.random_range(0.0..100.0);
+                    let brightness = rng.random_range(0.4..0.7);
+                    sim.events.particles.push(Particle {
+                        position: p + dir * offset,
+                        velocity: v + dir * speed,
+                        color: vector![brightness, brightness, brightness, 1.0],
+                        lifetime: 2.0,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
     pub fn activate_ability(&mut self, ability: oort_api::Ability) {
+        match ability {
+            Ability::Chaff => return self.launch_countermeasure(ShipClass::Chaff),
+            Ability::Flare => return self.launch_countermeasure(ShipClass::Flare),
+            _ => {}
+        }
         if let Some(ship_ability) = self
             .data_mut()
             .abilities
@@ -934,16 +1320,24 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
 
     pub fn tick(&mut self) {
         // Weapons
-        // Handle reload timers
+        // Draw energy and handle reload timers
         {
             let ship_data = self.data_mut();
+            let reload = energy::tick(ship_data);
             for gun in ship_data.guns.iter_mut() {
-                if gun.reload_ticks_remaining > 0 {
+                if reload && gun.reload_ticks_remaining > 0 {
                     gun.reload_ticks_remaining -= 1;
                 }
+                if let Some(beam) = gun.beam.as_mut() {
+                    beam.cool();
+                }
             }
 
-            for missile_launcher in ship_data.missile_launchers.iter_mut() {
+            for missile_launcher in ship_data
+                .missile_launchers
+                .iter_mut()
+                .chain(ship_data.countermeasure_launchers.iter_mut())
+            {
                 if missile_launcher.reload_ticks_remaining > 0 {
                     missile_launcher.reload_ticks_remaining -= 1;
                 }
@@ -989,6 +1383,35 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
             self.data_mut().angular_acceleration = 0.0;
         }
 
+        // Flares
+        // Throw off sparks while burning
+        if self.data().class == ShipClass::Flare {
+            let burn = self.data().ttl.unwrap_or(0) as f32 / FLARE_TTL as f32;
+            let position = self.body().position().translation.vector;
+            let velocity = *self.body().linvel();
+            self.simulation.events.particles.push(Particle {
+                position,
+                velocity,
+                color: vector![1.0, 0.6, 0.2, burn],
+                lifetime: 0.3,
+            });
+        }
+
+        // Mines
+        // Coast to a halt, then detonate once armed and an enemy is close
+        if self.data().class == ShipClass::Mine {
+            let velocity = *self.body().linvel();
+            let factor = (1.0 - MINE_BRAKING * PHYSICS_TICK_LENGTH).max(0.0);
+            self.body().set_linvel(velocity * factor, true);
+            let armed = self
+                .data()
+                .ttl
+                .is_some_and(|ttl| MINE_TTL - ttl >= MINE_ARMING_TICKS);
+            if armed && mine_fuse_triggered(self.simulation, self.handle) {
+                self.explode();
+            }
+        }
+
         // TTL
         // Destroy ship if it exceeds TTL
         {
@@ -1014,6 +1437,7 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         // Destruction
         // If a ship has been destroyed, remove it from the simulation
         if self.data().destroyed {
+            self.fragment();
             if let Some(team_ctrl) = self.simulation.get_team_controller(self.data().team) {
                 team_ctrl.borrow_mut().remove_ship(self.handle);
             }
@@ -1032,13 +1456,49 @@ impl<'a: 'b, 'b> ShipAccessorMut<'a> {
         }
     }
 
-    pub fn handle_collision(&mut self) {
-        if self.data().class == ShipClass::Missile || self.data().class == ShipClass::Torpedo {
+    /// Handles running into `other`, or into an obstacle if `None`.
+    pub fn handle_collision(&mut self, other: Option<ShipHandle>) {
+        let detonate = match self.data().class {
+            ShipClass::Missile | ShipClass::Torpedo => true,
+            ShipClass::Mine => other.is_some_and(|other| {
+                triggers_mine(self.simulation.ship(other).data(), self.data().team)
+            }),
+            _ => false,
+        };
+        if detonate {
             self.explode();
         }
     }
 }
 
+/// Whether `data` is an enemy ship or munition for a mine of `team`.
+fn triggers_mine(data: &ShipData, team: i32) -> bool {
+    data.team != team
+        && matches!(
+            data.class,
+            ShipClass::Fighter
+                | ShipClass::Frigate
+                | ShipClass::Cruiser
+                | ShipClass::Missile
+                | ShipClass::Torpedo
+        )
+}
+
+/// Whether an enemy ship or munition is within a mine's fuse radius of its
+/// hull.
+fn mine_fuse_triggered(sim: &Simulation, mine: ShipHandle) -> bool {
+    let ship = sim.ship(mine);
+    let position = Point2::from(ship.position().vector);
+    let team = ship.data().team;
+    sim.ships.iter().any(|&handle| {
+        let other = sim.ship(handle);
+        let other_data = other.data();
+        triggers_mine(other_data, team)
+            && topology::displacement(sim, position, other.position().vector.into()).norm()
+                < MINE_FUSE_RADIUS + other_data.radar_radius as f64
+    })
+}
+
 #[cfg(test)]
 mod test {
     use crate::ship;
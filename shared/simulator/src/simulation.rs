@@ -1,14 +1,23 @@
+use crate::boundary::{self, Boundary};
 use crate::bullet::{self, BulletData, BulletHandle};
 use crate::collision;
 use crate::debug;
 pub use crate::debug::Line;
+use crate::esm;
 use crate::index_set::{HasIndex, IndexSet};
+use crate::integrator::{self, DeterministicIntegrator, PhysicsMode};
+use crate::logistics::{self, Logistics};
+use crate::nebula::{self, Nebula};
+use crate::objective::{self, Objectives};
+use crate::obstacle::{self, Obstacle};
 use crate::radar;
 use crate::radio;
 use crate::scenario;
 use crate::scenario::Scenario;
 use crate::ship::{ShipAccessor, ShipAccessorMut, ShipData, ShipHandle, Target};
 use crate::snapshot::*;
+use crate::stats::{self, MatchStats};
+use crate::topology::{self, Topology};
 use crate::vm;
 use crate::vm::TeamController;
 use crossbeam::channel::Sender;
@@ -48,14 +57,14 @@ pub struct Simulation {
     pub(crate) impulse_joints: ImpulseJointSet,
     pub(crate) multibody_joints: MultibodyJointSet,
     pub(crate) colliders: ColliderSet,
-    integration_parameters: IntegrationParameters,
+    pub(crate) integration_parameters: IntegrationParameters,
     physics_pipeline: PhysicsPipeline,
     pub(crate) island_manager: IslandManager,
-    broad_phase: BroadPhase,
-    narrow_phase: NarrowPhase,
+    pub(crate) broad_phase: BroadPhase,
+    pub(crate) narrow_phase: NarrowPhase,
     ccd_solver: CCDSolver,
-    event_collector: CollisionEventHandler,
-    contact_recv: crossbeam::channel::Receiver<CollisionEvent>,
+    pub(crate) event_collector: CollisionEventHandler,
+    contact_recv: crossbeam::channel::Receiver<(CollisionEvent, f64)>,
     pub(crate) events: SimEvents,
     tick: u32,
     pub cheats: bool,
@@ -63,12 +72,43 @@ pub struct Simulation {
     timing: Timing,
     pub(crate) rng: ChaCha8Rng,
     world_size: f64,
+    topology: Topology,
+    physics_mode: PhysicsMode,
+    pub(crate) integrator: DeterministicIntegrator,
+    pub(crate) objectives: Objectives,
+    pub(crate) boundary: Option<Boundary>,
+    pub(crate) logistics: Option<Logistics>,
+    aspect_dependent_rcs: bool,
+    countermeasures: bool,
+    energy: bool,
+    collision_damage: bool,
+    pub(crate) obstacles: Vec<Obstacle>,
+    pub(crate) nebulae: Vec<Nebula>,
+    pub(crate) stats: MatchStats,
 }
 
 impl Simulation {
     pub fn new(scenario_name: &str, seed: u32, codes: &[Code]) -> Box<Simulation> {
-        let mut scenario = scenario::load(scenario_name);
+        Self::with_scenario(scenario::load(scenario_name), seed, codes)
+    }
+
+    /// Creates a simulation of a scenario scripted by the wasm director module
+    /// in `director`. See `scenario::load_director`.
+    pub fn new_with_director(
+        scenario_name: &str,
+        director: &Code,
+        seed: u32,
+        codes: &[Code],
+    ) -> Result<Box<Simulation>, vm::Error> {
+        let scenario = scenario::load_director(scenario_name, director)?;
+        Ok(Self::with_scenario(scenario, seed, codes))
+    }
 
+    fn with_scenario(
+        mut scenario: Box<dyn Scenario>,
+        seed: u32,
+        codes: &[Code],
+    ) -> Box<Simulation> {
         log::debug!("seed {seed}");
 
         // Create channel to communicate collision events
@@ -104,6 +144,19 @@ impl Simulation {
             timing: Default::default(),
             rng: crate::rng::new_rng(seed),
             world_size: scenario.world_size(),
+            topology: scenario.topology(),
+            physics_mode: scenario.physics_mode(),
+            integrator: DeterministicIntegrator::new(),
+            objectives: Objectives::default(),
+            boundary: scenario.boundary(),
+            logistics: scenario.logistics(),
+            aspect_dependent_rcs: scenario.aspect_dependent_rcs(),
+            countermeasures: scenario.countermeasures(),
+            energy: scenario.energy(),
+            collision_damage: scenario.collision_damage(),
+            obstacles: Vec::new(),
+            nebulae: Vec::new(),
+            stats: MatchStats::default(),
         });
 
         for (team, code) in codes.iter().enumerate() {
@@ -112,7 +165,9 @@ impl Simulation {
             }
         }
 
-        collision::add_walls(&mut sim);
+        if sim.topology == Topology::Walled {
+            collision::add_walls(&mut sim);
+        }
 
         // Configure the simulation according to the scenario
         scenario.init(&mut sim, seed);
@@ -145,10 +200,63 @@ impl Simulation {
         self.world_size
     }
 
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn physics_mode(&self) -> PhysicsMode {
+        self.physics_mode
+    }
+
     pub fn status(&self) -> scenario::Status {
         self.scenario.as_ref().unwrap().status(self)
     }
 
+    /// Finishing position of each team, indexed by team ID.
+    pub fn ranking(&self) -> Vec<usize> {
+        self.scenario.as_ref().unwrap().ranking(self)
+    }
+
+    pub fn objectives(&self) -> &Objectives {
+        &self.objectives
+    }
+
+    pub fn boundary(&self) -> Option<&Boundary> {
+        self.boundary.as_ref()
+    }
+
+    pub fn logistics(&self) -> Option<&Logistics> {
+        self.logistics.as_ref()
+    }
+
+    pub fn aspect_dependent_rcs(&self) -> bool {
+        self.aspect_dependent_rcs
+    }
+
+    pub fn countermeasures(&self) -> bool {
+        self.countermeasures
+    }
+
+    pub fn energy(&self) -> bool {
+        self.energy
+    }
+
+    pub fn stats(&self) -> &MatchStats {
+        &self.stats
+    }
+
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.obstacles
+    }
+
+    pub fn nebulae(&self) -> &[Nebula] {
+        &self.nebulae
+    }
+
+    pub fn collision_damage(&self) -> bool {
+        self.collision_damage
+    }
+
     pub fn ship(self: &Simulation, handle: ShipHandle) -> ShipAccessor<'_> {
         ShipAccessor {
             simulation: self,
@@ -179,25 +287,32 @@ impl Simulation {
         }
 
         let physics_timer = Timer::new();
-        let gravity = vector![0.0, 0.0];
-        let physics_hooks = ();
-        self.physics_pipeline.step(
-            &gravity,
-            &self.integration_parameters,
-            &mut self.island_manager,
-            &mut self.broad_phase,
-            &mut self.narrow_phase,
-            &mut self.bodies,
-            &mut self.colliders,
-            &mut self.impulse_joints,
-            &mut self.multibody_joints,
-            &mut self.ccd_solver,
-            None,
-            &physics_hooks,
-            &self.event_collector,
-        );
+        match self.physics_mode {
+            PhysicsMode::Rapier => {
+                let gravity = vector![0.0, 0.0];
+                let physics_hooks = ();
+                self.physics_pipeline.step(
+                    &gravity,
+                    &self.integration_parameters,
+                    &mut self.island_manager,
+                    &mut self.broad_phase,
+                    &mut self.narrow_phase,
+                    &mut self.bodies,
+                    &mut self.colliders,
+                    &mut self.impulse_joints,
+                    &mut self.multibody_joints,
+                    &mut self.ccd_solver,
+                    None,
+                    &physics_hooks,
+                    &self.event_collector,
+                );
+            }
+            PhysicsMode::Deterministic => integrator::step(self),
+        }
         self.timing.physics = physics_timer.elapsed();
 
+        topology::tick(self);
+
         let collision_timer = Timer::new();
         let collision_events: Vec<_> = self.contact_recv.try_iter().collect();
         collision::handle_collisions(self, &collision_events);
@@ -205,6 +320,7 @@ impl Simulation {
 
         let radar_timer = Timer::new();
         radar::tick(self);
+        esm::tick(self);
         self.timing.radar += radar_timer.elapsed();
 
         // Transmit radio messages
@@ -212,6 +328,12 @@ impl Simulation {
         radio::tick(self);
         self.timing.radio += radio_timer.elapsed();
 
+        objective::tick(self);
+        boundary::tick(self);
+        nebula::tick(self);
+        logistics::tick(self);
+        stats::tick(self);
+
         let vm_timer = Timer::new();
         let mut teams: Vec<_> = self
             .team_controllers
@@ -338,23 +460,34 @@ impl Simulation {
     ///
     /// Collects the statuses of bullets and ships
     pub fn snapshot(&self, nonce: u32) -> Snapshot {
+        let status = self.status();
         let mut snapshot = Snapshot {
             nonce,
             tick: self.tick,
             time: self.time(),
             score_time: self.score_time(),
-            status: self.status(),
+            status,
             ships: vec![],
             bullets: vec![],
-            scenario_lines: self.scenario.as_ref().unwrap().lines(),
+            scenario_lines: [
+                self.scenario.as_ref().unwrap().lines(),
+                objective::lines(self),
+                boundary::lines(self),
+                obstacle::lines(self),
+            ]
+            .concat(),
+            fields: nebula::fields(self),
             debug_lines: self.events.debug_lines.clone(),
             debug_text: self.events.debug_text.clone(),
             drawn_text: self.events.drawn_text.clone(),
             particles: self.events.particles.clone(),
+            beams: self.events.beams.clone(),
             errors: self.events.errors.clone(),
             cheats: self.cheats,
             timing: self.timing.clone(),
             world_size: self.world_size,
+            gas_used: self.gas_used(0),
+            stats: (status != scenario::Status::Running).then(|| self.stats.clone()),
         };
 
         for &handle in self.ships.iter() {
@@ -366,6 +499,7 @@ impl Simulation {
             let class = ship.data().class;
             let health = ship.data().health;
             let fuel = ship.data().fuel;
+            let energy = ship.data().energy.as_ref().map(|energy| energy.stored);
             snapshot.ships.push(ShipSnapshot {
                 id,
                 position,
@@ -377,6 +511,7 @@ impl Simulation {
                 class,
                 health,
                 fuel,
+                energy,
                 active_abilities: ship.active_abilities(),
             });
         }
@@ -395,6 +530,14 @@ impl Simulation {
         snapshot
     }
 
+    /// Total gas used by a team's ships so far.
+    pub fn gas_used(&self, team: i32) -> u64 {
+        self.team_controllers
+            .get(&team)
+            .map(|x| x.borrow().gas_used())
+            .unwrap_or(0)
+    }
+
     pub fn get_team_controller(&mut self, team: i32) -> Option<Rc<RefCell<Box<TeamController>>>> {
         self.team_controllers.get_mut(&team).map(|x| x.clone())
     }
@@ -406,6 +549,12 @@ impl Simulation {
             self.scenario.as_ref().unwrap().name(),
         );
         environment.insert("WORLD_SIZE".to_string(), format!("{}", self.world_size));
+        if self.topology == Topology::Toroidal {
+            environment.insert("WORLD_TOPOLOGY".to_string(), "toroidal".to_string());
+        }
+        if !self.nebulae.is_empty() {
+            environment.insert("NEBULAE".to_string(), nebula::environment(self));
+        }
         if let Some(team_ctrl) = self.get_team_controller(team) {
             team_ctrl
                 .borrow_mut()
@@ -415,12 +564,14 @@ impl Simulation {
     }
 }
 
+/// Forwards collision events along with the relative speed of the two bodies
+/// at the moment of contact, before the contact is resolved.
 pub struct CollisionEventHandler {
-    collision_event_sender: Sender<CollisionEvent>,
+    collision_event_sender: Sender<(CollisionEvent, f64)>,
 }
 
 impl CollisionEventHandler {
-    pub fn new(collision_event_sender: Sender<CollisionEvent>) -> CollisionEventHandler {
+    pub fn new(collision_event_sender: Sender<(CollisionEvent, f64)>) -> CollisionEventHandler {
         CollisionEventHandler {
             collision_event_sender,
         }
@@ -430,12 +581,28 @@ impl CollisionEventHandler {
 impl EventHandler for CollisionEventHandler {
     fn handle_collision_event(
         &self,
-        _bodies: &RigidBodySet,
-        _colliders: &ColliderSet,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
         event: CollisionEvent,
-        _contact_pair: Option<&rapier2d_f64::geometry::ContactPair>,
+        contact_pair: Option<&rapier2d_f64::geometry::ContactPair>,
     ) {
-        let _ = self.collision_event_sender.send(event);
+        let velocity = |handle: ColliderHandle| {
+            colliders
+                .get(handle)
+                .and_then(|collider| collider.parent())
+                .and_then(|body| bodies.get(body))
+                .map(|body| *body.linvel())
+                .unwrap_or_else(Vector2::zeros)
+        };
+        let dv = velocity(event.collider2()) - velocity(event.collider1());
+        // A glancing blow only transfers the velocity along the contact normal.
+        let manifold =
+            contact_pair.and_then(|pair| pair.manifolds.iter().find(|m| !m.points.is_empty()));
+        let impact_speed = match manifold {
+            Some(manifold) => dv.dot(&manifold.data.normal).abs(),
+            None => dv.norm(),
+        };
+        let _ = self.collision_event_sender.send((event, impact_speed));
     }
 
     fn handle_contact_force_event(
@@ -461,6 +628,7 @@ pub struct Particle {
 pub struct SimEvents {
     pub errors: Vec<vm::Error>,
     pub particles: Vec<Particle>,
+    pub beams: Vec<Line>,
     pub debug_lines: Vec<(u64, Vec<Line>)>,
     pub debug_text: BTreeMap<u64, String>,
     pub drawn_text: BTreeMap<Option<u64>, Vec<Text>>,
@@ -471,6 +639,7 @@ impl SimEvents {
         Self {
             errors: vec![],
             particles: vec![],
+            beams: vec![],
             debug_lines: Vec::new(),
             debug_text: BTreeMap::new(),
             drawn_text: BTreeMap::new(),
@@ -480,6 +649,7 @@ impl SimEvents {
     pub fn clear(&mut self) {
         self.errors.clear();
         self.particles.clear();
+        self.beams.clear();
         self.debug_lines.clear();
         self.debug_text.clear();
         self.drawn_text.clear();
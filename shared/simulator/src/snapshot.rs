@@ -1,8 +1,9 @@
 use crate::scenario::Status;
 use crate::ship::ShipClass;
 use crate::simulation::{Line, Particle};
+use crate::stats::MatchStats;
 use crate::vm;
-use nalgebra::{Point2, Vector2};
+use nalgebra::{Point2, Vector2, Vector4};
 use oort_api::{Ability, Text};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -17,7 +18,9 @@ pub struct Snapshot {
     pub ships: Vec<ShipSnapshot>,
     pub bullets: Vec<BulletSnapshot>,
     pub scenario_lines: Vec<Line>,
+    pub fields: Vec<Field>,
     pub particles: Vec<Particle>,
+    pub beams: Vec<Line>,
     pub errors: Vec<vm::Error>,
     pub cheats: bool,
     pub debug_lines: Vec<(u64, Vec<Line>)>,
@@ -25,6 +28,9 @@ pub struct Snapshot {
     pub drawn_text: BTreeMap<Option<u64>, Vec<Text>>,
     pub timing: Timing,
     pub world_size: f64,
+    pub gas_used: u64,
+    /// Only present once the match has ended.
+    pub stats: Option<MatchStats>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -39,9 +45,17 @@ pub struct ShipSnapshot {
     pub class: ShipClass,
     pub health: f64,
     pub fuel: Option<f64>,
+    pub energy: Option<f64>,
     pub active_abilities: Vec<Ability>,
 }
 
+/// A translucent convex region drawn beneath everything else.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Field {
+    pub vertices: Vec<Point2<f64>>,
+    pub color: Vector4<f32>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct BulletSnapshot {
     pub position: Point2<f64>,
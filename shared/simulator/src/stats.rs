@@ -0,0 +1,274 @@
+//! Match statistics.
+//!
+//! The simulator keeps a tally of what every ship did over the course of a
+//! match. Damage from bullets and beams is credited to the ship that fired
+//! them, and damage from a warhead is credited to the ship that launched the
+//! missile or torpedo. Team statistics are the sum over the team's ships.
+use crate::index_set::HasIndex;
+use crate::ship::{ShipClass, ShipHandle};
+use crate::simulation::{Simulation, PHYSICS_TICK_LENGTH};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct CombatStats {
+    /// Bullets fired and beam shots, counting each round of a burst.
+    pub shots_fired: u32,
+    /// Bullets and beams that damaged an enemy.
+    pub hits: u32,
+    /// Warhead fragments that damaged an enemy.
+    pub warhead_hits: u32,
+    pub damage_dealt: f64,
+    /// Damage from any source, including collisions and the arena boundary.
+    pub damage_taken: f64,
+    /// Enemy ships destroyed, not counting missiles and torpedoes.
+    pub kills: u32,
+    pub missiles_launched: u32,
+    /// Enemy missiles and torpedoes destroyed.
+    pub missiles_intercepted: u32,
+    /// Distance in meters. Munitions don't contribute.
+    pub distance_travelled: f64,
+}
+
+impl CombatStats {
+    pub fn add(&mut self, other: &CombatStats) {
+        self.shots_fired += other.shots_fired;
+        self.hits += other.hits;
+        self.warhead_hits += other.warhead_hits;
+        self.damage_dealt += other.damage_dealt;
+        self.damage_taken += other.damage_taken;
+        self.kills += other.kills;
+        self.missiles_launched += other.missiles_launched;
+        self.missiles_intercepted += other.missiles_intercepted;
+        self.distance_travelled += other.distance_travelled;
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ShipStats {
+    pub team: i32,
+    pub class: ShipClass,
+    pub stats: CombatStats,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct MatchStats {
+    /// Indexed by ship ID. Ships stay here after they are destroyed.
+    pub ships: BTreeMap<u64, ShipStats>,
+}
+
+impl MatchStats {
+    /// Statistics summed over each team's ships, indexed by team ID.
+    pub fn teams(&self) -> BTreeMap<i32, CombatStats> {
+        let mut teams: BTreeMap<i32, CombatStats> = BTreeMap::new();
+        for ship in self.ships.values() {
+            teams.entry(ship.team).or_default().add(&ship.stats);
+        }
+        teams
+    }
+
+    pub fn team(&self, team: i32) -> CombatStats {
+        self.teams().remove(&team).unwrap_or_default()
+    }
+
+    pub fn ship(&self, handle: ShipHandle) -> Option<&ShipStats> {
+        self.ships.get(&handle.into())
+    }
+
+    fn get_mut(&mut self, handle: ShipHandle) -> Option<&mut CombatStats> {
+        self.ships
+            .get_mut(&handle.into())
+            .map(|ship| &mut ship.stats)
+    }
+}
+
+fn is_munition(class: ShipClass) -> bool {
+    matches!(
+        class,
+        ShipClass::Missile | ShipClass::Torpedo | ShipClass::Mine
+    ) || class.is_countermeasure()
+}
+
+/// Starts tracking a newly created ship.
+pub(crate) fn register(sim: &mut Simulation, handle: ShipHandle, team: i32, class: ShipClass) {
+    sim.stats.ships.insert(
+        handle.into(),
+        ShipStats {
+            team,
+            class,
+            stats: CombatStats::default(),
+        },
+    );
+}
+
+pub(crate) fn record_shots(sim: &mut Simulation, shooter: ShipHandle, count: u32) {
+    if let Some(stats) = sim.stats.get_mut(shooter) {
+        stats.shots_fired += count;
+    }
+}
+
+pub(crate) fn record_missile_launch(sim: &mut Simulation, shooter: ShipHandle) {
+    if let Some(stats) = sim.stats.get_mut(shooter) {
+        stats.missiles_launched += 1;
+    }
+}
+
+/// Records damage that has already been subtracted from the target's health.
+///
+/// The shooter is credited with a kill or interception only for the hit that
+/// took the target's health below zero.
+pub(crate) fn record_damage(
+    sim: &mut Simulation,
+    shooter: Option<ShipHandle>,
+    target: ShipHandle,
+    damage: f64,
+) {
+    record_hit(sim, shooter, target, damage, false);
+}
+
+/// Like `record_damage`, for a warhead fragment.
+pub(crate) fn record_fragment_damage(
+    sim: &mut Simulation,
+    shooter: Option<ShipHandle>,
+    target: ShipHandle,
+    damage: f64,
+) {
+    record_hit(sim, shooter, target, damage, true);
+}
+
+fn record_hit(
+    sim: &mut Simulation,
+    shooter: Option<ShipHandle>,
+    target: ShipHandle,
+    damage: f64,
+    fragment: bool,
+) {
+    let (class, health) = {
+        let data = sim.ship_data.get(target.index()).unwrap();
+        (data.class, data.health)
+    };
+    if let Some(stats) = sim.stats.get_mut(target) {
+        stats.damage_taken += damage;
+    }
+    let Some(stats) = shooter.and_then(|shooter| sim.stats.get_mut(shooter)) else {
+        return;
+    };
+    if fragment {
+        stats.warhead_hits += 1;
+    } else {
+        stats.hits += 1;
+    }
+    stats.damage_dealt += damage;
+    if health <= 0.0 && health + damage > 0.0 {
+        match class {
+            ShipClass::Missile | ShipClass::Torpedo => stats.missiles_intercepted += 1,
+            _ if is_munition(class) => {}
+            _ => stats.kills += 1,
+        }
+    }
+}
+
+/// Accumulates the distance travelled by each ship.
+pub(crate) fn tick(sim: &mut Simulation) {
+    let mut distances = vec![];
+    for &handle in sim.ships.iter() {
+        let ship = sim.ship(handle);
+        if !is_munition(ship.data().class) {
+            distances.push((handle, ship.velocity().norm() * PHYSICS_TICK_LENGTH));
+        }
+    }
+    for (handle, distance) in distances {
+        if let Some(stats) = sim.stats.get_mut(handle) {
+            stats.distance_travelled += distance;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ship;
+    use crate::simulation::Code;
+    use nalgebra::vector;
+    use test_log::test;
+
+    #[test]
+    fn test_gun_stats() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![100.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let target = ship::create(
+            &mut sim,
+            vector![1000.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        for _ in 0..120 {
+            if sim.ships.contains(ship0) {
+                sim.ship_mut(ship0).fire(0);
+            }
+            sim.step();
+        }
+        assert!(!sim.ships.contains(target));
+
+        let stats = &sim.stats().ship(ship0).unwrap().stats;
+        assert!(stats.shots_fired > 0);
+        assert!(stats.hits > 0);
+        assert!(stats.hits <= stats.shots_fired);
+        assert!(stats.damage_dealt > 0.0);
+        assert_eq!(stats.kills, 1);
+        approx::assert_abs_diff_eq!(stats.distance_travelled, 200.0, epsilon = 1.0);
+
+        let target_stats = &sim.stats().ship(target).unwrap().stats;
+        assert_eq!(target_stats.damage_taken, stats.damage_dealt);
+        assert_eq!(sim.stats().team(0), *stats);
+    }
+
+    #[test]
+    fn test_warhead_stats() {
+        let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+        let ship0 = ship::create(
+            &mut sim,
+            vector![0.0, 1000.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        let missile = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::ShipData {
+                parent: Some(ship0),
+                ..ship::missile(0)
+            },
+        );
+        let target = ship::create(
+            &mut sim,
+            vector![50.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        sim.ship_mut(missile).explode();
+        for _ in 0..30 {
+            sim.step();
+        }
+        assert!(!sim.ships.contains(target));
+
+        // Fragments are credited to the launching ship, separately from
+        // gun hits.
+        let stats = &sim.stats().ship(ship0).unwrap().stats;
+        assert_eq!(stats.shots_fired, 0);
+        assert_eq!(stats.hits, 0);
+        assert!(stats.warhead_hits > 0);
+        assert_eq!(stats.kills, 1);
+    }
+}
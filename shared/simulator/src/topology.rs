@@ -0,0 +1,139 @@
+//! World topology.
+//!
+//! By default the world is a square surrounded by walls. A scenario can
+//! instead make it toroidal: there are no walls, and ships and bullets leaving
+//! one edge reappear on the opposite edge. Sensors and radios then measure
+//! distances along the shortest path, which may cross the seam.
+use crate::bullet::BulletHandle;
+use crate::index_set::HasIndex;
+use crate::simulation::Simulation;
+use nalgebra::{Point2, Vector2};
+use rapier2d_f64::prelude::*;
+
+pub use oort_api::Topology;
+
+/// Wraps a position into the world, with coordinates in
+/// `[-world_size / 2, world_size / 2)`.
+pub fn wrap_position(position: Vector2<f64>, world_size: f64) -> Vector2<f64> {
+    position.map(|x| (x + world_size / 2.0).rem_euclid(world_size) - world_size / 2.0)
+}
+
+/// Shortest displacement from `from` to `to`.
+pub fn displacement(sim: &Simulation, from: Point2<f64>, to: Point2<f64>) -> Vector2<f64> {
+    let dp = to - from;
+    match sim.topology() {
+        Topology::Walled => dp,
+        Topology::Toroidal => wrap_position(dp, sim.world_size()),
+    }
+}
+
+/// Offsets of the real world, first, and the copies of it adjacent to the
+/// real one.
+pub(crate) fn image_offsets(sim: &Simulation) -> Vec<Vector2<f64>> {
+    match sim.topology() {
+        Topology::Walled => vec![Vector2::zeros()],
+        Topology::Toroidal => {
+            let s = sim.world_size();
+            let mut offsets = vec![Vector2::zeros()];
+            for x in [-s, 0.0, s] {
+                for y in [-s, 0.0, s] {
+                    if x != 0.0 || y != 0.0 {
+                        offsets.push(Vector2::new(x, y));
+                    }
+                }
+            }
+            offsets
+        }
+    }
+}
+
+/// Moves ships that left the world to the opposite edge.
+pub(crate) fn tick(sim: &mut Simulation) {
+    if sim.topology() != Topology::Toroidal {
+        return;
+    }
+    let world_size = sim.world_size();
+    let handles: Vec<_> = sim.ships.iter().cloned().collect();
+    for handle in handles {
+        let mut ship = sim.ship_mut(handle);
+        let position = ship.readonly().position().vector;
+        let wrapped = wrap_position(position, world_size);
+        if wrapped != position {
+            ship.body().set_translation(wrapped, true);
+        }
+    }
+}
+
+/// Moves a bullet that left the world to the opposite edge.
+pub(crate) fn wrap_bullet(sim: &mut Simulation, handle: BulletHandle) {
+    let world_size = sim.world_size();
+    let body = sim.bodies.get_mut(RigidBodyHandle(handle.index())).unwrap();
+    let position = *body.translation();
+    body.set_translation(wrap_position(position, world_size), true);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ship;
+    use crate::simulation::Code;
+    use nalgebra::{point, vector};
+    use test_log::test;
+
+    #[test]
+    fn test_wrap_position() {
+        let s = 1000.0;
+        assert_eq!(wrap_position(vector![0.0, 0.0], s), vector![0.0, 0.0]);
+        assert_eq!(wrap_position(vector![501.0, 0.0], s), vector![-499.0, 0.0]);
+        assert_eq!(wrap_position(vector![0.0, -600.0], s), vector![0.0, 400.0]);
+        assert_eq!(wrap_position(vector![1600.0, 0.0], s), vector![-400.0, 0.0]);
+    }
+
+    #[test]
+    fn test_wrap_ship() {
+        let mut sim = Simulation::new("toroidal_duel", 0, &[Code::None, Code::None]);
+        let edge = sim.world_size() / 2.0;
+        let ship = ship::create(
+            &mut sim,
+            vector![edge - 10.0, 0.0],
+            vector![1000.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        sim.step();
+        let x = sim.ship(ship).position().x;
+        assert!(x < -edge + 10.0, "x={x}");
+        assert!(sim.ships.contains(ship));
+
+        approx::assert_abs_diff_eq!(
+            displacement(&sim, point![edge - 100.0, 0.0], point![-edge + 100.0, 0.0]),
+            vector![200.0, 0.0],
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_radar_across_seam() {
+        let mut sim = Simulation::new("toroidal_duel", 0, &[Code::None, Code::None]);
+        let edge = sim.world_size() / 2.0;
+        let ship0 = ship::create(
+            &mut sim,
+            vector![edge - 500.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::fighter(0),
+        );
+        ship::create(
+            &mut sim,
+            vector![-edge + 500.0, 0.0],
+            vector![0.0, 0.0],
+            0.0,
+            ship::target(1),
+        );
+        sim.ship_mut(ship0).radar_mut(0).unwrap().heading = 0.0;
+        sim.ship_mut(ship0).radar_mut(0).unwrap().width = 0.1;
+        sim.step();
+        let contact = sim.ship(ship0).radar(0).unwrap().result.unwrap();
+        approx::assert_abs_diff_eq!(contact.position, vector![edge + 500.0, 0.0], epsilon = 50.0);
+    }
+}
@@ -0,0 +1,270 @@
+//! Sandbox for scenario "director" modules.
+//!
+//! A director is a wasm module that scripts a scenario: it spawns ships, watches
+//! the world and decides when the scenario is over. It runs under the same gas
+//! limiter as ship code but gets its own set of host imports under the
+//! `oort_director` namespace instead of the shared system state memory.
+//!
+//! Exports:
+//! - `director_init(seed: i32)`: called once when the scenario is created.
+//! - `director_tick()`: called once per tick after the ships have run.
+//!
+//! Imports (`oort_director`):
+//! - `current_tick() -> i32`
+//! - `ship_count() -> i32`, plus `ship_id`, `ship_team`, `ship_class`,
+//!   `ship_position_x`, `ship_position_y`, `ship_velocity_x`, `ship_velocity_y`,
+//!   `ship_heading` and `ship_health`, each taking an index below `ship_count()`.
+//! - `spawn_ship(class: i32, team: i32, x, y, vx, vy, heading: f64)`
+//! - `draw_line(x0, y0, x1, y1: f64, color: i32)` and `clear_lines()`
+//! - `set_status(status: i32, team: i32)`: 0 = running, 1 = victory for `team`,
+//!   2 = failed, 3 = draw.
+//!
+//! Ships are the ones that existed when the call started; ships spawned during
+//! a call appear on the next call.
+use super::{limiter, ship_data_for_class, translate_class, translate_error, Error, GAS_PER_TICK};
+use crate::color;
+use crate::scenario::Status;
+use crate::ship;
+use crate::simulation::{Line, Simulation};
+use nalgebra::{point, vector};
+use oort_api::Class;
+use wasmer::{imports, Function, FunctionEnv, FunctionEnvMut, Instance, Module, Store};
+
+const MAX_SPAWNS_PER_TICK: usize = 1000;
+const MAX_LINES: usize = 4096;
+
+#[derive(Clone, Debug)]
+struct DirectorShip {
+    id: i64,
+    team: i32,
+    class: Class,
+    position: [f64; 2],
+    velocity: [f64; 2],
+    heading: f64,
+    health: f64,
+}
+
+#[derive(Clone, Debug)]
+struct Spawn {
+    class: i32,
+    team: i32,
+    position: [f64; 2],
+    velocity: [f64; 2],
+    heading: f64,
+}
+
+struct DirectorEnv {
+    tick: u32,
+    ships: Vec<DirectorShip>,
+    spawns: Vec<Spawn>,
+    lines: Vec<Line>,
+    status: Status,
+}
+
+impl DirectorEnv {
+    fn ship(&self, index: i32) -> Option<&DirectorShip> {
+        usize::try_from(index).ok().and_then(|i| self.ships.get(i))
+    }
+}
+
+pub struct DirectorVm {
+    store: Store,
+    env: FunctionEnv<DirectorEnv>,
+    director_init: wasmer::TypedFunction<i32, ()>,
+    director_tick: wasmer::TypedFunction<(), ()>,
+    reset_gas: wasmer::TypedFunction<i32, ()>,
+    get_gas: wasmer::TypedFunction<(), i32>,
+}
+
+impl DirectorVm {
+    pub fn create(wasm: &[u8]) -> Result<DirectorVm, Error> {
+        #[cfg(target_arch = "wasm32")]
+        let mut store = Store::default();
+        #[cfg(not(target_arch = "wasm32"))]
+        let mut store = Store::new(wasmer_compiler_cranelift::Cranelift::new());
+        let wasm = limiter::rewrite(wasm)?;
+        let module = translate_error(Module::new(&store, wasm))?;
+
+        let env = FunctionEnv::new(
+            &mut store,
+            DirectorEnv {
+                tick: 0,
+                ships: Vec::new(),
+                spawns: Vec::new(),
+                lines: Vec::new(),
+                status: Status::Running,
+            },
+        );
+
+        macro_rules! ship_getter {
+            ($ty:ty, $f:expr) => {
+                Function::new_typed_with_env(
+                    &mut store,
+                    &env,
+                    |env: FunctionEnvMut<DirectorEnv>, index: i32| -> $ty {
+                        env.data().ship(index).map($f).unwrap_or_default()
+                    },
+                )
+            };
+        }
+
+        let import_object = imports! {
+            "oort_director" => {
+                "current_tick" => Function::new_typed_with_env(&mut store, &env, |env: FunctionEnvMut<DirectorEnv>| -> i32 {
+                    env.data().tick as i32
+                }),
+                "ship_count" => Function::new_typed_with_env(&mut store, &env, |env: FunctionEnvMut<DirectorEnv>| -> i32 {
+                    env.data().ships.len() as i32
+                }),
+                "ship_id" => ship_getter!(i64, |s| s.id),
+                "ship_team" => ship_getter!(i32, |s| s.team),
+                "ship_class" => ship_getter!(i32, |s| s.class as i32),
+                "ship_position_x" => ship_getter!(f64, |s| s.position[0]),
+                "ship_position_y" => ship_getter!(f64, |s| s.position[1]),
+                "ship_velocity_x" => ship_getter!(f64, |s| s.velocity[0]),
+                "ship_velocity_y" => ship_getter!(f64, |s| s.velocity[1]),
+                "ship_heading" => ship_getter!(f64, |s| s.heading),
+                "ship_health" => ship_getter!(f64, |s| s.health),
+                "spawn_ship" => Function::new_typed_with_env(&mut store, &env, |mut env: FunctionEnvMut<DirectorEnv>, class: i32, team: i32, x: f64, y: f64, vx: f64, vy: f64, heading: f64| {
+                    let data = env.data_mut();
+                    if data.spawns.len() < MAX_SPAWNS_PER_TICK {
+                        data.spawns.push(Spawn {
+                            class,
+                            team,
+                            position: [x, y],
+                            velocity: [vx, vy],
+                            heading,
+                        });
+                    }
+                }),
+                "draw_line" => Function::new_typed_with_env(&mut store, &env, |mut env: FunctionEnvMut<DirectorEnv>, x0: f64, y0: f64, x1: f64, y1: f64, color: i32| {
+                    let data = env.data_mut();
+                    if data.lines.len() < MAX_LINES && [x0, y0, x1, y1].iter().all(|v| v.is_finite()) {
+                        data.lines.push(Line {
+                            a: point![x0, y0],
+                            b: point![x1, y1],
+                            color: color::from_u24(color as u32),
+                        });
+                    }
+                }),
+                "clear_lines" => Function::new_typed_with_env(&mut store, &env, |mut env: FunctionEnvMut<DirectorEnv>| {
+                    env.data_mut().lines.clear();
+                }),
+                "set_status" => Function::new_typed_with_env(&mut store, &env, |mut env: FunctionEnvMut<DirectorEnv>, status: i32, team: i32| {
+                    env.data_mut().status = match status {
+                        1 => Status::Victory { team },
+                        2 => Status::Failed,
+                        3 => Status::Draw,
+                        _ => Status::Running,
+                    };
+                }),
+            }
+        };
+        let instance = Instance::new(&mut store, &module, &import_object)?;
+
+        let director_init =
+            translate_error(instance.exports.get_typed_function(&store, "director_init"))?;
+        let director_tick =
+            translate_error(instance.exports.get_typed_function(&store, "director_tick"))?;
+        let reset_gas = translate_error(instance.exports.get_typed_function(&store, "reset_gas"))?;
+        let get_gas = translate_error(instance.exports.get_typed_function(&store, "get_gas"))?;
+
+        Ok(DirectorVm {
+            store,
+            env,
+            director_init,
+            director_tick,
+            reset_gas,
+            get_gas,
+        })
+    }
+
+    pub fn init(&mut self, sim: &mut Simulation, seed: u32) -> Result<(), Error> {
+        self.prepare(sim)?;
+        let result = self.director_init.call(&mut self.store, seed as i32);
+        self.finish(sim, result)
+    }
+
+    pub fn tick(&mut self, sim: &mut Simulation) -> Result<(), Error> {
+        self.prepare(sim)?;
+        let result = self.director_tick.call(&mut self.store);
+        self.finish(sim, result)
+    }
+
+    pub fn status(&self) -> Status {
+        self.env.as_ref(&self.store).status
+    }
+
+    pub fn lines(&self) -> Vec<Line> {
+        self.env.as_ref(&self.store).lines.clone()
+    }
+
+    /// Copies the world state into the director's environment.
+    fn prepare(&mut self, sim: &Simulation) -> Result<(), Error> {
+        translate_error(self.reset_gas.call(&mut self.store, GAS_PER_TICK))?;
+        let env = self.env.as_mut(&mut self.store);
+        env.tick = sim.tick();
+        env.ships.clear();
+        for &handle in sim.ships.iter() {
+            let ship = sim.ship(handle);
+            let data = ship.data();
+            let (index, generation) = handle.0.into_raw_parts();
+            env.ships.push(DirectorShip {
+                id: ((generation as i64) << 32) | index as i64,
+                team: data.team,
+                class: translate_class(data.class),
+                position: [ship.position().x, ship.position().y],
+                velocity: [ship.velocity().x, ship.velocity().y],
+                heading: ship.heading(),
+                health: data.health,
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies the commands issued by the director during the last call.
+    fn finish(
+        &mut self,
+        sim: &mut Simulation,
+        result: Result<(), wasmer::RuntimeError>,
+    ) -> Result<(), Error> {
+        let spawns = std::mem::take(&mut self.env.as_mut(&mut self.store).spawns);
+        if let Err(e) = result {
+            if let Ok(gas) = self.get_gas.call(&mut self.store) {
+                if gas <= 0 {
+                    return Err(Error {
+                        msg: "Director exceeded maximum number of instructions".to_string(),
+                    });
+                }
+            }
+            return Err(Error {
+                msg: format!("Director runtime error: {e:?}"),
+            });
+        }
+
+        for spawn in spawns {
+            let Some(data) = ship_data_for_class(sim, spawn.class, spawn.team) else {
+                log::warn!("Director tried to spawn invalid ship {spawn:?}");
+                continue;
+            };
+            if !spawn
+                .position
+                .iter()
+                .chain(spawn.velocity.iter())
+                .chain(std::iter::once(&spawn.heading))
+                .all(|v| v.is_finite())
+            {
+                log::warn!("Director tried to spawn ship with invalid state {spawn:?}");
+                continue;
+            }
+            ship::create(
+                sim,
+                vector![spawn.position[0], spawn.position[1]],
+                vector![spawn.velocity[0], spawn.velocity[1]],
+                spawn.heading,
+                data,
+            );
+        }
+        Ok(())
+    }
+}
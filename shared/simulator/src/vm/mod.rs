@@ -2,15 +2,17 @@
 // TODO add methods to WasmVm for each exported function
 // TODO shift pointers according to headroom + base
 pub mod builtin;
+pub mod director;
 mod limiter;
+mod module_cache;
 
 use crate::color;
 use crate::debug;
 use crate::rng::new_rng;
-use crate::ship::{ShipClass, ShipHandle};
+use crate::ship::{self, ShipClass, ShipData, ShipHandle};
 use crate::simulation::{Code, Particle, Simulation, PHYSICS_TICK_LENGTH};
 use nalgebra::{point, vector, Rotation2};
-use oort_api::{ActiveAbilities, Class, EcmMode, Line, SystemState, Text};
+use oort_api::{Ability, ActiveAbilities, Class, EcmMode, Line, SystemState, Text};
 use rand::RngExt;
 use serde::{Deserialize, Serialize};
 use std::cell::{Ref, RefCell, RefMut};
@@ -19,7 +21,7 @@ use std::collections::HashMap;
 use std::f64::consts::TAU;
 use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
-use wasmer::{imports, Function, Instance, MemoryView, Module, Store, WasmPtr};
+use wasmer::{imports, Function, Instance, MemoryView, Store, WasmPtr};
 
 pub type Vec2 = nalgebra::Vector2<f64>;
 pub type Environment = BTreeMap<String, String>;
@@ -28,6 +30,9 @@ const SUBMEMORY_SIZE: u32 = 2 << 20;
 const GAS_PER_TICK: i32 = 1_000_000;
 const MAX_DEBUG_LINES: u32 = 1024;
 const MAX_DRAWN_TEXT: u32 = 128;
+const TEST_PREFIX: &str = "oort_test_";
+/// System state length of ship code built before `system_state_size` was exported.
+const LEGACY_SYSTEM_STATE_SIZE: u32 = SystemState::Id as u32 + 1;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Error {
@@ -95,6 +100,7 @@ pub struct TeamController {
     next_id: u32,
     free_submemories: Vec<(u32, u32)>, // (index, base_address)
     environment: Environment,
+    gas_used: u64,
 }
 
 impl TeamController {
@@ -105,6 +111,7 @@ impl TeamController {
             next_id: 1,
             free_submemories: Vec::new(),
             environment: Environment::new(),
+            gas_used: 0,
         }))
     }
 
@@ -226,14 +233,18 @@ impl TeamController {
             let store = vm.store();
             let memory_view = vm.memory.view(store.deref());
             let ptr = ship_controller.system_state_ptr;
-            let slice = ptr
-                .slice(&memory_view, SystemState::Size as u32)
+            let len = vm.system_state_size;
+            let slice = ptr.slice(&memory_view, len).expect("system state write");
+            slice
+                .write_slice(&state.state[..len as usize])
                 .expect("system state write");
-            slice.write_slice(&state.state).expect("system state write");
         }
 
         // Run user's ship tick function
         let result = vm.tick_ship.call(vm.store_mut().deref_mut(), &[]);
+        if let Ok(gas) = vm.get_gas.call(vm.store_mut().deref_mut()) {
+            self.gas_used += (GAS_PER_TICK - gas.max(0)) as u64;
+        }
         if let Err(e) = result {
             // If gas has run out, throw an error
             if let Ok(gas) = vm.get_gas.call(vm.store_mut().deref_mut()) {
@@ -283,12 +294,11 @@ impl TeamController {
             let store = vm.store();
             let memory_view = vm.memory.view(store.deref());
             let ptr = ship_controller.system_state_ptr;
-            let slice = ptr
-                .slice(&memory_view, SystemState::Size as u32)
-                .expect("system state read");
+            let len = vm.system_state_size;
+            let slice = ptr.slice(&memory_view, len).expect("system state read");
 
             slice
-                .read_slice(&mut state.state)
+                .read_slice(&mut state.state[..len as usize])
                 .expect("system state read");
             apply_system_state(sim, handle, state);
 
@@ -344,6 +354,11 @@ impl TeamController {
         Ok(())
     }
 
+    /// Total instructions executed by this team's ships so far, as counted by the gas limiter.
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
+
     /// Writes `environment` to each ship's memory
     pub fn update_environment(&mut self, environment: &Environment) -> Result<(), Error> {
         self.environment = environment.clone();
@@ -362,6 +377,12 @@ pub struct WasmVm {
     system_state_offset: u32,
     environment_offset: u32,
     panic_buffer_offset: u32,
+    /// Number of system state slots shared with the ship code.
+    system_state_size: u32,
+    test_buffer_offset: Option<u32>,
+    tests: Vec<(String, wasmer::Function)>,
+    /// Submemory reused by every `test_definition` call.
+    test_submemory: Option<(u32, u32)>,
     tick_ship: wasmer::Function,
     reset_gas: wasmer::Function,
     get_gas: wasmer::TypedFunction<(), i32>,
@@ -372,28 +393,8 @@ pub struct WasmVm {
 
 impl WasmVm {
     pub fn create(code: &Code) -> Result<WasmVm, Error> {
-        #[cfg(target_arch = "wasm32")]
-        let mut store = Store::default();
-        #[cfg(not(target_arch = "wasm32"))]
-        let mut store = Store::new(wasmer_compiler_cranelift::Cranelift::new());
-        let module = match code {
-            Code::Wasm(wasm) => {
-                let wasm = wasm_submemory::rewrite(wasm, SUBMEMORY_SIZE)?;
-
-                // Add gas tracking and functions
-                let wasm = limiter::rewrite(&wasm)?;
-                let old_level = log::max_level();
-                log::set_max_level(log::LevelFilter::Info);
-                let res = Module::new(&store, wasm);
-                log::set_max_level(old_level);
-                translate_error(res)?
-            }
-            #[cfg(feature = "precompile")]
-            Code::Precompiled(bytes) => {
-                translate_error(unsafe { Module::deserialize(&store, bytes.clone()) })?
-            }
-            _ => unreachable!(),
-        };
+        let mut store = module_cache::new_store();
+        let module = module_cache::load(code)?;
         let import_object = imports! {
             "wasi_snapshot_preview1" => {
                 "fd_write" => Function::new_typed(&mut store, |_a: i32, _b: i32, _c: i32, _d: i32, _e: i32| -> i32 { -1 }),
@@ -417,6 +418,21 @@ impl WasmVm {
             .get(&mut store)
             .i32()
             .unwrap() as u32;
+        let test_buffer_offset: Option<u32> = instance
+            .exports
+            .get_global("TEST_BUFFER")
+            .ok()
+            .and_then(|global| global.get(&mut store).i32())
+            .map(|offset| offset as u32);
+        let tests = instance
+            .exports
+            .iter()
+            .functions()
+            .filter_map(|(name, function)| {
+                name.strip_prefix(TEST_PREFIX)
+                    .map(|test_name| (test_name.to_string(), function.clone()))
+            })
+            .collect();
 
         // The compiler service creates a file that includes this global `tick` function, which calls the
         // user's `ship.tick()` function. You can find that file in `shared/ai/src/tick.rs`,
@@ -433,12 +449,27 @@ impl WasmVm {
 
         translate_runtime_error(reset_gas.call(&mut store, &[GAS_PER_TICK.into()]))?;
 
+        // Ship code built against an older oort_api has a smaller
+        // SYSTEM_STATE and doesn't export its size. Newer slots are appended
+        // after `Id`, so such code gets the prefix it knows about.
+        let system_state_size = instance
+            .exports
+            .get_typed_function::<(), u32>(&store, "system_state_size")
+            .ok()
+            .and_then(|f| f.call(&mut store).ok())
+            .unwrap_or(LEGACY_SYSTEM_STATE_SIZE)
+            .min(SystemState::Size as u32);
+
         Ok(WasmVm {
             store: Rc::new(RefCell::new(store)),
             memory,
             system_state_offset,
             environment_offset,
             panic_buffer_offset,
+            system_state_size,
+            test_buffer_offset,
+            tests,
+            test_submemory: None,
             tick_ship,
             reset_gas,
             get_gas,
@@ -448,6 +479,64 @@ impl WasmVm {
         })
     }
 
+    /// Names of the tests declared with `oort_test!`.
+    pub fn test_names(&self) -> Vec<String> {
+        self.tests.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Runs a test's setup function in a fresh submemory and returns the
+    /// description it wrote to `TEST_BUFFER`.
+    pub fn test_definition(&mut self, name: &str) -> Result<Vec<f64>, Error> {
+        let Some(test_buffer_offset) = self.test_buffer_offset else {
+            return Err(Error {
+                msg: "Missing TEST_BUFFER".to_string(),
+            });
+        };
+        let Some(function) = self
+            .tests
+            .iter()
+            .find(|(test_name, _)| test_name == name)
+            .map(|(_, function)| function.clone())
+        else {
+            return Err(Error {
+                msg: format!("Unknown test {name:?}"),
+            });
+        };
+
+        let (index, base_address) = match self.test_submemory {
+            Some((index, base_address)) => {
+                self.reset_submemory(index)?;
+                (index, base_address)
+            }
+            None => {
+                let submemory = self.add_submemory()?;
+                self.test_submemory = Some(submemory);
+                submemory
+            }
+        };
+        self.select_submemory(index)?;
+        translate_runtime_error(
+            self.reset_gas
+                .call(self.store_mut().deref_mut(), &[GAS_PER_TICK.into()]),
+        )?;
+        translate_runtime_error(function.call(self.store_mut().deref_mut(), &[]))?;
+
+        let store = self.store();
+        let memory_view = self.memory.view(store.deref());
+        let offset = base_address + test_buffer_offset;
+        let length = WasmVm::read_vec::<f64>(&memory_view, offset, 1)
+            .map(|v| v[0])
+            .filter(|&len| len >= 0.0 && len < oort_api::testing::TEST_BUFFER_SIZE as f64)
+            .ok_or_else(|| Error {
+                msg: "Invalid test description".to_string(),
+            })? as u32;
+        let values =
+            WasmVm::read_vec::<f64>(&memory_view, offset, length + 1).ok_or_else(|| Error {
+                msg: "Invalid test description".to_string(),
+            })?;
+        Ok(values[1..].to_vec())
+    }
+
     fn store(&self) -> Ref<'_, Store> {
         self.store.borrow()
     }
@@ -625,6 +714,33 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
         }
     }
 
+    if let Some(contact) = sim.ship(handle).data().illuminated {
+        state.set(SystemState::IlluminatedContactFound, 1.0);
+        state.set(
+            SystemState::IlluminatedContactClass,
+            translate_class(contact.class) as u32 as f64,
+        );
+        state.set(SystemState::IlluminatedContactPositionX, contact.position.x);
+        state.set(SystemState::IlluminatedContactPositionY, contact.position.y);
+        state.set(SystemState::IlluminatedContactVelocityX, contact.velocity.x);
+        state.set(SystemState::IlluminatedContactVelocityY, contact.velocity.y);
+        state.set(SystemState::IlluminatedContactRssi, contact.rssi);
+        state.set(SystemState::IlluminatedContactSnr, contact.snr);
+    } else {
+        state.set(SystemState::IlluminatedContactFound, 0.0);
+    }
+
+    {
+        let esm = &sim.ship(handle).data().esm;
+        state.set(SystemState::EsmCount, esm.count as f64);
+        for (i, contact) in esm.contacts.iter().enumerate() {
+            let idxs = oort_api::prelude::esm_internal::esm_indices(i);
+            state.set(idxs.bearing, contact.bearing);
+            state.set(idxs.power, contact.power);
+            state.set(idxs.jammer, contact.jammer as u32 as f64);
+        }
+    }
+
     {
         let ship = sim.ship(handle);
         let data = ship.data();
@@ -646,6 +762,17 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
         );
         state.set(SystemState::Health, data.health);
         state.set(SystemState::Fuel, data.fuel.unwrap_or(f64::INFINITY));
+        state.set(
+            SystemState::Ammo,
+            data.ammo.map_or(f64::INFINITY, |ammo| ammo as f64),
+        );
+        state.set(
+            SystemState::Energy,
+            data.energy
+                .as_ref()
+                .map(|energy| energy.stored)
+                .unwrap_or(f64::INFINITY),
+        );
     }
 
     for (i, radio) in sim.ship(handle).data().radios.iter().enumerate() {
@@ -665,11 +792,35 @@ fn generate_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut L
 
     state.set(SystemState::CurrentTick, sim.tick() as f64);
 
+    {
+        let team = sim.ship(handle).data().team;
+        let objectives = sim.objectives();
+        state.set(SystemState::ZoneCount, objectives.zones.len() as f64);
+        for (i, zone) in objectives.zones.iter().enumerate() {
+            let idxs = oort_api::prelude::zone_internal::zone_indices(i);
+            state.set(idxs.position[0], zone.position.x);
+            state.set(idxs.position[1], zone.position.y);
+            state.set(idxs.radius, zone.radius);
+            state.set(idxs.control, zone.control(team) as u32 as f64);
+        }
+        state.set(SystemState::Score, objectives.score(team));
+        state.set(SystemState::EnemyScore, objectives.enemy_score(team));
+    }
+
+    {
+        let (radius, next_radius) = sim.boundary().map_or((f64::INFINITY, f64::INFINITY), |b| {
+            (b.radius(sim.tick()), b.next_radius(sim.tick()))
+        });
+        state.set(SystemState::BoundaryRadius, radius);
+        state.set(SystemState::NextBoundaryRadius, next_radius);
+    }
+
     for (i, idx) in [
         SystemState::ReloadTicks0,
         SystemState::ReloadTicks1,
         SystemState::ReloadTicks2,
         SystemState::ReloadTicks3,
+        SystemState::ReloadTicks4,
     ]
     .iter()
     .enumerate()
@@ -699,6 +850,7 @@ fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut Loca
         (SystemState::Aim1, SystemState::Fire1),
         (SystemState::Aim2, SystemState::Fire2),
         (SystemState::Aim3, SystemState::Fire3),
+        (SystemState::Aim4, SystemState::Fire4),
     ]
     .iter()
     .enumerate()
@@ -726,8 +878,17 @@ fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut Loca
         radar.set_ecm_mode(translate_ecm_mode(state.get(idxs.ecm_mode)));
     }
 
+    // Set power allocation
+    if let Some(energy) = sim.ship_mut(handle).data_mut().energy.as_mut() {
+        energy.set_allocation(
+            state.get(SystemState::PowerAllocationWeapons),
+            state.get(SystemState::PowerAllocationSensors),
+            state.get(SystemState::PowerAllocationEngines),
+        );
+    }
+
     // Activate abilities
-    let active_abilities = ActiveAbilities(state.get_u64(SystemState::ActivateAbility));
+    let mut active_abilities = ActiveAbilities(state.get_u64(SystemState::ActivateAbility));
     for &ability in oort_api::ABILITIES {
         let current = sim.ship(handle).is_ability_active(ability);
         let requested = active_abilities.get_ability(ability);
@@ -739,6 +900,10 @@ fn apply_system_state(sim: &mut Simulation, handle: ShipHandle, state: &mut Loca
             }
         }
     }
+    // Each activation launches a single countermeasure.
+    active_abilities.unset_ability(Ability::Chaff);
+    active_abilities.unset_ability(Ability::Flare);
+    state.set_u64(SystemState::ActivateAbility, active_abilities.0);
 
     // Explode
     if state.get(SystemState::Explode) > 0.0 {
@@ -778,10 +943,28 @@ fn translate_class(class: ShipClass) -> Class {
         ShipClass::Target => Class::Target,
         ShipClass::Missile => Class::Missile,
         ShipClass::Torpedo => Class::Torpedo,
+        ShipClass::Mine => Class::Mine,
         _ => Class::Unknown,
     }
 }
 
+/// Creates the ship data for an API ship class, as used by directors and tests.
+pub(crate) fn ship_data_for_class(sim: &mut Simulation, class: i32, team: i32) -> Option<ShipData> {
+    if !(0..10).contains(&team) {
+        return None;
+    }
+    Some(match class {
+        x if x == Class::Fighter as i32 => ship::fighter(team),
+        x if x == Class::Frigate as i32 => ship::frigate(team),
+        x if x == Class::Cruiser as i32 => ship::cruiser(team),
+        x if x == Class::Asteroid as i32 => ship::asteroid(sim.rng.random_range(0..30)),
+        x if x == Class::Target as i32 => ship::target(team),
+        x if x == Class::Missile as i32 => ship::missile(team),
+        x if x == Class::Torpedo as i32 => ship::torpedo(team),
+        _ => return None,
+    })
+}
+
 fn translate_ecm_mode(v: f64) -> EcmMode {
     let v = v as u32;
     if v == EcmMode::None as u32 {
@@ -830,12 +1013,15 @@ fn validate_texts(texts: &[Text]) -> bool {
         .all(|t| validate_floats(&[t.x, t.y]) && t.length as usize <= t.text.len())
 }
 
+/// Makes room for at least `count` compiled modules, for tools that run more
+/// distinct AIs than the cache holds by default.
+pub fn reserve_module_cache(count: usize) {
+    module_cache::reserve(count);
+}
+
 #[cfg(feature = "precompile")]
 pub fn precompile(wasm: &[u8]) -> Result<Code, Error> {
-    let wasm = wasm_submemory::rewrite(wasm, SUBMEMORY_SIZE)?;
-    let wasm = limiter::rewrite(&wasm)?;
-    let store = Store::default();
-    let module = translate_error(Module::new(&store, wasm))?;
+    let module = module_cache::load(&Code::Wasm(wasm.to_vec()))?;
     Ok(Code::Precompiled(translate_error(module.serialize())?))
 }
 
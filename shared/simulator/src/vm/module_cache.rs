@@ -0,0 +1,184 @@
+//! Process-wide cache of compiled AI modules.
+//!
+//! Tools like `tournament` create thousands of simulations with the same few
+//! AIs, and restarting a scenario in the browser reuses the same code.
+//! Rewriting and compiling a module costs far more than instantiating it, so
+//! modules are compiled once on a shared engine and looked up by the SHA-256
+//! of their bytes. On the web each worker has its own cache.
+use super::{limiter, translate_error, Error, SUBMEMORY_SIZE};
+use crate::simulation::Code;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use wasmer::{Module, Store};
+
+/// Number of modules kept by default. Tools that run more distinct AIs than
+/// this should call `reserve`.
+const DEFAULT_CAPACITY: usize = 64;
+
+type Key = [u8; 32];
+
+/// Compiled modules, evicting the least recently used one when full.
+struct ModuleCache {
+    capacity: usize,
+    /// Incremented on every lookup to order entries by last use.
+    clock: u64,
+    entries: HashMap<Key, (u64, Module)>,
+}
+
+impl ModuleCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            clock: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &Key) -> Option<Module> {
+        self.clock += 1;
+        let (last_used, module) = self.entries.get_mut(key)?;
+        *last_used = self.clock;
+        Some(module.clone())
+    }
+
+    fn insert(&mut self, key: Key, module: Module) {
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            let lru = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(key, _)| *key);
+            if let Some(lru) = lru {
+                self.entries.remove(&lru);
+            }
+        }
+        self.clock += 1;
+        self.entries.insert(key, (self.clock, module));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+lazy_static::lazy_static! {
+    static ref ENGINE: wasmer::Engine = wasmer_compiler_cranelift::Cranelift::new().into();
+    static ref CACHE: std::sync::Mutex<ModuleCache> =
+        std::sync::Mutex::new(ModuleCache::new(DEFAULT_CAPACITY));
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static CACHE: std::cell::RefCell<ModuleCache> =
+        std::cell::RefCell::new(ModuleCache::new(DEFAULT_CAPACITY));
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Modules compiled by the current thread.
+    static COMPILES: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn with_cache<T>(f: impl FnOnce(&mut ModuleCache) -> T) -> T {
+    f(&mut CACHE.lock().unwrap())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn with_cache<T>(f: impl FnOnce(&mut ModuleCache) -> T) -> T {
+    CACHE.with(|cache| f(&mut cache.borrow_mut()))
+}
+
+/// Creates a store on the engine shared by all cached modules.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn new_store() -> Store {
+    Store::new(ENGINE.clone())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn new_store() -> Store {
+    Store::default()
+}
+
+/// Grows the cache to hold at least `capacity` modules.
+pub(crate) fn reserve(capacity: usize) {
+    with_cache(|cache| cache.capacity = cache.capacity.max(capacity));
+}
+
+/// Returns the module for `code`, compiling it if it isn't cached yet.
+pub(crate) fn load(code: &Code) -> Result<Module, Error> {
+    let key = hash(code);
+    if let Some(module) = with_cache(|cache| cache.get(&key)) {
+        return Ok(module);
+    }
+    let module = compile(code)?;
+    with_cache(|cache| cache.insert(key, module.clone()));
+    Ok(module)
+}
+
+fn hash(code: &Code) -> Key {
+    let mut hasher = Sha256::new();
+    match code {
+        Code::Wasm(wasm) => {
+            hasher.update([0]);
+            hasher.update(wasm);
+        }
+        #[cfg(feature = "precompile")]
+        Code::Precompiled(bytes) => {
+            hasher.update([1]);
+            hasher.update(bytes);
+        }
+        _ => unreachable!(),
+    }
+    hasher.finalize().into()
+}
+
+fn compile(code: &Code) -> Result<Module, Error> {
+    #[cfg(test)]
+    COMPILES.with(|compiles| compiles.set(compiles.get() + 1));
+    let store = new_store();
+    match code {
+        Code::Wasm(wasm) => {
+            let wasm = wasm_submemory::rewrite(wasm, SUBMEMORY_SIZE)?;
+
+            // Add gas tracking and functions
+            let wasm = limiter::rewrite(&wasm)?;
+            let old_level = log::max_level();
+            log::set_max_level(log::LevelFilter::Info);
+            let res = Module::new(&store, wasm);
+            log::set_max_level(old_level);
+            translate_error(res)
+        }
+        #[cfg(feature = "precompile")]
+        Code::Precompiled(bytes) => {
+            translate_error(unsafe { Module::deserialize(&store, bytes.clone()) })
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::vm::builtin;
+    use test_log::test;
+
+    #[test]
+    fn test_cached() {
+        let code = builtin::load_compiled("testing/test").unwrap();
+        load(&code).unwrap();
+        let compiles = COMPILES.with(|compiles| compiles.get());
+        load(&code).unwrap();
+        assert_eq!(COMPILES.with(|compiles| compiles.get()), compiles);
+    }
+
+    #[test]
+    fn test_lru() {
+        let module = compile(&builtin::load_compiled("testing/test").unwrap()).unwrap();
+        let mut cache = ModuleCache::new(2);
+        cache.insert([0; 32], module.clone());
+        cache.insert([1; 32], module.clone());
+        assert!(cache.get(&[0; 32]).is_some());
+        cache.insert([2; 32], module);
+        assert!(cache.get(&[0; 32]).is_some());
+        assert!(cache.get(&[1; 32]).is_none());
+        assert!(cache.get(&[2; 32]).is_some());
+    }
+}
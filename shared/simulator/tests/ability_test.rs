@@ -1,7 +1,7 @@
 use nalgebra::vector;
 use oort_api::Ability;
 use oort_simulator::ship;
-use oort_simulator::ship::{cruiser, fighter, frigate, torpedo, ShipClass};
+use oort_simulator::ship::{cruiser, fighter, frigate, missile, torpedo, ShipClass};
 use oort_simulator::simulation::{self, Code, PHYSICS_TICK_LENGTH};
 use test_log::test;
 
@@ -127,3 +127,100 @@ fn test_shield() {
     assert_ne!(sim.ship(ship0).data().health, frigate(0).health);
     assert_eq!(sim.ship(ship1).data().health, cruiser(1).health);
 }
+
+#[test]
+fn test_chaff() {
+    let mut sim = simulation::Simulation::new("mechanics_test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let num_chaff = |sim: &simulation::Simulation| {
+        sim.ships
+            .iter()
+            .filter(|&&handle| sim.ship(handle).data().class == ShipClass::Chaff)
+            .count()
+    };
+
+    sim.step();
+    let contact = sim.ship(ship0).radar(0).unwrap().scan().unwrap();
+    assert_eq!(contact.class, ShipClass::Fighter);
+    let fighter_rssi = contact.rssi;
+
+    // The chaff outshines the fighter but reports the fighter's class.
+    sim.ship_mut(ship1).activate_ability(Ability::Chaff);
+    sim.step();
+    assert_eq!(num_chaff(&sim), 1);
+    let contact = sim.ship(ship0).radar(0).unwrap().scan().unwrap();
+    assert_eq!(contact.class, ShipClass::Fighter);
+    assert!(contact.rssi > fighter_rssi + 5.0);
+
+    // Limited supply.
+    for _ in 0..5 {
+        sim.ship_mut(ship1).activate_ability(Ability::Chaff);
+        for _ in 0..60 {
+            sim.step();
+        }
+    }
+    assert_eq!(num_chaff(&sim), 3);
+    assert_eq!(
+        sim.ship(ship1).data().countermeasure_launchers[0].count,
+        Some(0)
+    );
+
+    // Chaff disperses.
+    for _ in 0..300 {
+        sim.step();
+    }
+    assert_eq!(num_chaff(&sim), 0);
+}
+
+#[test]
+fn test_flare() {
+    let mut sim = simulation::Simulation::new("mechanics_test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let missile0 = ship::create(
+        &mut sim,
+        vector![0.0, 50.0],
+        vector![0.0, 0.0],
+        0.0,
+        missile(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+
+    sim.step();
+    let fighter_rssi = sim.ship(ship0).radar(0).unwrap().scan().unwrap().rssi;
+    let seeker_rssi = sim.ship(missile0).radar(0).unwrap().scan().unwrap().rssi;
+
+    // Only the seeker sees the flare, which reports the fighter's class.
+    sim.ship_mut(ship1).activate_ability(Ability::Flare);
+    sim.step();
+    let contact = sim.ship(ship0).radar(0).unwrap().scan().unwrap();
+    assert_eq!(contact.class, ShipClass::Fighter);
+    approx::assert_abs_diff_eq!(contact.rssi, fighter_rssi, epsilon = 1.0);
+    let contact = sim.ship(missile0).radar(0).unwrap().scan().unwrap();
+    assert_eq!(contact.class, ShipClass::Fighter);
+    assert!(contact.rssi > seeker_rssi + 5.0);
+}
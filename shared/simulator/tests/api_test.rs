@@ -1,4 +1,5 @@
 use nalgebra::vector;
+use oort_simulator::energy::Subsystem;
 use oort_simulator::ship::{self, fighter, ShipHandle};
 use oort_simulator::simulation::{self, Code};
 use std::collections::BTreeMap;
@@ -105,3 +106,119 @@ fn test_id() {
     check(ship_handles[1], 2);
     check(ship_handles[2], 1);
 }
+
+#[test]
+fn test_esm() {
+    let mut sim = simulation::Simulation::new(
+        "test",
+        0,
+        &[Code::Builtin("testing/test".to_string()), Code::None],
+    );
+    let mut env = BTreeMap::new();
+    env.insert("TESTCASE".to_string(), "esm".to_string());
+    sim.update_environment(0, env);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![1000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let ship2 = ship::create(
+        &mut sim,
+        vector![-2000.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    sim.ship_mut(ship2).radar_mut(0).unwrap().heading = std::f64::consts::PI;
+
+    let check = |sim: &simulation::Simulation, expected: &str| {
+        let output = sim
+            .events()
+            .debug_text
+            .get(&ship0.into())
+            .expect("Missing debug text");
+        assert!(output.contains(expected), "output: {:?}", output);
+    };
+
+    sim.ship_mut(ship1).radar_mut(0).unwrap().heading = 0.0;
+    sim.step();
+    check(&sim, "ESM: 0 None 0");
+
+    sim.ship_mut(ship1).radar_mut(0).unwrap().heading = std::f64::consts::PI;
+    sim.step();
+    check(&sim, "ESM: 1 Some(false) 1");
+
+    sim.ship_mut(ship2).radar_mut(0).unwrap().heading = 0.0;
+    sim.step();
+    check(&sim, "ESM: 2 Some(false) 2");
+}
+
+#[test]
+fn test_chaff() {
+    let mut sim = simulation::Simulation::new(
+        "mechanics_test",
+        0,
+        &[Code::Builtin("testing/test".to_string()), Code::None],
+    );
+    let mut env = BTreeMap::new();
+    env.insert("TESTCASE".to_string(), "chaff".to_string());
+    sim.update_environment(0, env);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+
+    // A single activation launches a single round.
+    for _ in 0..200 {
+        sim.step();
+    }
+    assert_eq!(
+        sim.ship(ship0).data().countermeasure_launchers[0].count,
+        Some(3)
+    );
+}
+
+#[test]
+fn test_energy() {
+    let mut sim = simulation::Simulation::new(
+        "mechanics_test",
+        0,
+        &[Code::Builtin("testing/test".to_string()), Code::None],
+    );
+    let mut env = BTreeMap::new();
+    env.insert("TESTCASE".to_string(), "energy".to_string());
+    sim.update_environment(0, env);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+
+    sim.step();
+    let output = sim
+        .events()
+        .debug_text
+        .get(&ship0.into())
+        .expect("Missing debug text");
+    assert!(output.contains("Energy: 10000000"), "output: {:?}", output);
+
+    let data = sim.ship(ship0).data();
+    let energy = data.energy.as_ref().unwrap();
+    assert_eq!(energy.allocation(Subsystem::Weapons), 0.0);
+    assert_eq!(energy.allocation(Subsystem::Sensors), 0.25);
+    assert_eq!(energy.allocation(Subsystem::Engines), 0.75);
+}
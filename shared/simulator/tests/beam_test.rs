@@ -0,0 +1,137 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter, frigate_point_defense, missile, ShipClass, ShipHandle};
+use oort_simulator::simulation::{self, Code};
+use test_log::test;
+
+const LASER: i64 = 1;
+
+fn setup(target_x: f64) -> (Box<simulation::Simulation>, ShipHandle, ShipHandle) {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        frigate_point_defense(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![target_x, 30.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    sim.ship_mut(ship0).aim(LASER, 0.0);
+    (sim, ship0, ship1)
+}
+
+#[test]
+fn test_hit() {
+    let (mut sim, ship0, ship1) = setup(500.0);
+    let initial_health = sim.ship(ship1).data().health;
+
+    sim.ship_mut(ship0).fire(LASER);
+    assert!(sim.bullets.is_empty());
+    assert_eq!(sim.events().beams.len(), 1);
+    assert!(sim.ship(ship1).data().health < initial_health);
+}
+
+#[test]
+fn test_miss() {
+    let (mut sim, ship0, ship1) = setup(500.0);
+    let initial_health = sim.ship(ship1).data().health;
+
+    sim.ship_mut(ship0).aim(LASER, 1.0);
+    sim.ship_mut(ship0).fire(LASER);
+    assert_eq!(sim.events().beams.len(), 1);
+    assert_eq!(sim.ship(ship1).data().health, initial_health);
+}
+
+#[test]
+fn test_falloff() {
+    let damage = |x: f64| {
+        let (mut sim, ship0, ship1) = setup(x);
+        let initial_health = sim.ship(ship1).data().health;
+        sim.ship_mut(ship0).fire(LASER);
+        initial_health - sim.ship(ship1).data().health
+    };
+
+    assert!(damage(200.0) > damage(1000.0));
+    assert!(damage(1000.0) > 0.0);
+    assert_eq!(damage(2000.0), 0.0);
+}
+
+#[test]
+fn test_friendly() {
+    let (mut sim, ship0, ship1) = setup(500.0);
+    let friendly = ship::create(
+        &mut sim,
+        vector![250.0, 30.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let initial_health = sim.ship(ship1).data().health;
+
+    sim.ship_mut(ship0).fire(LASER);
+    assert_eq!(sim.ship(friendly).data().health, fighter(0).health);
+    assert!(sim.ship(ship1).data().health < initial_health);
+}
+
+#[test]
+fn test_overheat() {
+    let (mut sim, ship0, _) = setup(500.0);
+    sim.ship_mut(ship0).aim(LASER, 1.0);
+
+    let mut shots = 0;
+    for _ in 0..200 {
+        sim.ship_mut(ship0).fire(LASER);
+        shots += sim.events().beams.len();
+        sim.step();
+    }
+    assert!(shots > 100);
+    assert!(shots < 200);
+}
+
+#[test]
+fn test_point_defense() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        frigate_point_defense(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![1000.0, 30.0],
+        vector![0.0, 0.0],
+        std::f64::consts::PI,
+        missile(1),
+    );
+    sim.ship_mut(ship0).aim(LASER, 0.0);
+
+    for _ in 0..100 {
+        sim.ship_mut(ship0).fire(LASER);
+        sim.step();
+        if !sim.ships.contains(ship1) {
+            break;
+        }
+    }
+
+    assert!(sim.ships.contains(ship0));
+    assert!(!sim.ships.contains(ship1));
+}
+
+#[test]
+fn test_point_defense_scenario() {
+    let sim = simulation::Simulation::new("frigate_point_defense", 0, &[Code::None, Code::None]);
+    let frigate = sim
+        .ships
+        .iter()
+        .find(|&&handle| sim.ship(handle).data().class == ShipClass::Frigate)
+        .cloned()
+        .expect("Missing frigate");
+    assert!(sim.ship(frigate).data().guns[LASER as usize].beam.is_some());
+}
@@ -1,5 +1,5 @@
 use nalgebra::vector;
-use oort_simulator::ship::{fighter, missile};
+use oort_simulator::ship::{asteroid, fighter, missile};
 use oort_simulator::simulation::{self, Code};
 use oort_simulator::{bullet, collision, ship};
 use rand::RngExt;
@@ -93,6 +93,8 @@ fn test_fighter_bullet_collision_same_team() {
             team: 0,
             color: BULLET_COLOR,
             ttl: 5.0,
+            shooter: None,
+            fragment: false,
         },
     );
 
@@ -124,6 +126,8 @@ fn test_fighter_bullet_collision_different_team() {
             team: 1,
             color: BULLET_COLOR,
             ttl: 5.0,
+            shooter: None,
+            fragment: false,
         },
     );
 
@@ -155,6 +159,8 @@ fn test_missile_bullet_collision_same_team() {
             team: 0,
             color: BULLET_COLOR,
             ttl: 5.0,
+            shooter: None,
+            fragment: false,
         },
     );
 
@@ -186,6 +192,8 @@ fn test_missile_bullet_collision_different_team() {
             team: 1,
             color: BULLET_COLOR,
             ttl: 5.0,
+            shooter: None,
+            fragment: false,
         },
     );
 
@@ -273,6 +281,8 @@ fn test_bullet_continuous_collision_detection() {
                 team: 1,
                 color: BULLET_COLOR,
                 ttl: 1.5,
+                shooter: None,
+                fragment: false,
             },
         );
 
@@ -304,3 +314,118 @@ fn test_ship_wall_collision() {
 
     assert!(!sim.ship(ship0).exists());
 }
+
+#[test]
+fn test_ramming() {
+    let mut sim = simulation::Simulation::new("mechanics_test", 0, &[Code::None, Code::None]);
+
+    let ship0 = ship::create(
+        &mut sim,
+        vector![-100.0, 0.0],
+        vector![100.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![100.0, 0.0],
+        vector![-100.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+
+    for _ in 0..100 {
+        sim.step();
+    }
+
+    assert!(!sim.ships.contains(ship0));
+    assert!(!sim.ships.contains(ship1));
+}
+
+#[test]
+fn test_bump() {
+    let mut sim = simulation::Simulation::new("mechanics_test", 0, &[Code::None, Code::None]);
+
+    let ship0 = ship::create(
+        &mut sim,
+        vector![-10.0, 0.0],
+        vector![0.5, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![10.0, 0.0],
+        vector![-0.5, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let initial_health = sim.ship(ship0).data().health;
+
+    for _ in 0..300 {
+        sim.step();
+    }
+
+    for ship in [ship0, ship1] {
+        let health = sim.ship(ship).data().health;
+        assert!(health < initial_health);
+        assert!(health > initial_health * 0.9);
+    }
+}
+
+#[test]
+fn test_collision_damage_disabled() {
+    let mut sim = simulation::Simulation::new("test", 0, &[Code::None, Code::None]);
+
+    let ship0 = ship::create(
+        &mut sim,
+        vector![-100.0, 0.0],
+        vector![100.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![100.0, 0.0],
+        vector![-100.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+    let initial_health = sim.ship(ship0).data().health;
+
+    for _ in 0..100 {
+        sim.step();
+    }
+
+    for ship in [ship0, ship1] {
+        assert_eq!(sim.ship(ship).data().health, initial_health);
+    }
+}
+
+#[test]
+fn test_asteroid_collision() {
+    let mut sim = simulation::Simulation::new("mechanics_test", 0, &[Code::None, Code::None]);
+
+    let ship0 = ship::create(
+        &mut sim,
+        vector![-200.0, 0.0],
+        vector![100.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    let ship1 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        asteroid(0),
+    );
+    let initial_health = sim.ship(ship1).data().health;
+
+    for _ in 0..200 {
+        sim.step();
+    }
+
+    assert!(!sim.ships.contains(ship0));
+    assert!(sim.ship(ship1).data().health < initial_health);
+}
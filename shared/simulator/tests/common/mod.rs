@@ -0,0 +1,86 @@
+//! Golden per-tick hashes for the deterministic physics mode.
+//!
+//! The same cases are run natively and on wasm32, and both must reproduce
+//! `tests/data/deterministic_hashes.txt` exactly. Regenerate the file with
+//! `OORT_UPDATE_GOLDEN=1 cargo test -p oort_simulator --test determinism_test`.
+#![allow(dead_code)]
+
+use oort_simulator::scenario;
+use oort_simulator::simulation;
+use std::collections::BTreeMap;
+
+pub const CASES: &[(&str, u32)] = &[
+    ("deterministic_gunnery", 0),
+    ("deterministic_fighter_duel", 0),
+    ("deterministic_asteroid_duel", 0),
+    ("deterministic_fleet", 0),
+];
+pub const TICKS: u32 = 300;
+pub const GOLDEN_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/data/deterministic_hashes.txt"
+);
+const GOLDEN: &str = include_str!("../data/deterministic_hashes.txt");
+
+pub type Results = Vec<(&'static str, u32, Vec<u64>)>;
+
+/// Hashes of the simulation after each tick.
+pub fn run(scenario_name: &str, seed: u32) -> Vec<u64> {
+    let scenario = scenario::load(scenario_name);
+    let codes = scenario.solution_codes();
+    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
+    let mut hashes = vec![];
+    while sim.status() == scenario::Status::Running && sim.tick() < TICKS {
+        sim.step();
+        hashes.push(sim.hash());
+    }
+    hashes
+}
+
+/// Formats results as lines of `scenario seed tick hash`.
+pub fn format(results: &Results) -> String {
+    let mut s = "# scenario seed tick hash\n".to_string();
+    for (scenario_name, seed, hashes) in results {
+        for (tick, hash) in hashes.iter().enumerate() {
+            s += &format!("{scenario_name} {seed} {} {hash}\n", tick + 1);
+        }
+    }
+    s
+}
+
+fn golden() -> BTreeMap<(String, u32), Vec<u64>> {
+    let mut golden: BTreeMap<(String, u32), Vec<u64>> = BTreeMap::new();
+    for line in GOLDEN.lines().filter(|line| !line.starts_with('#')) {
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let [scenario_name, seed, _tick, hash] = fields[..] else {
+            panic!("Malformed golden line {line:?}");
+        };
+        golden
+            .entry((scenario_name.to_string(), seed.parse().unwrap()))
+            .or_default()
+            .push(hash.parse().unwrap());
+    }
+    golden
+}
+
+/// Panics at the first tick that differs from the golden hashes.
+pub fn check(results: &Results) {
+    let golden = golden();
+    for (scenario_name, seed, hashes) in results {
+        let expected = golden
+            .get(&(scenario_name.to_string(), *seed))
+            .unwrap_or_else(|| {
+                panic!("No golden hashes for {scenario_name} seed {seed}, regenerate them with OORT_UPDATE_GOLDEN=1")
+            });
+        if let Some(tick) =
+            (0..hashes.len().max(expected.len())).find(|&i| hashes.get(i) != expected.get(i))
+        {
+            panic!(
+                "{scenario_name} seed {seed} diverged at tick {}: got {:?}, expected {:?}",
+                tick + 1,
+                hashes.get(tick),
+                expected.get(tick)
+            );
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use nalgebra::vector;
 use oort_simulator::ship;
-use oort_simulator::ship::{cruiser, fighter, frigate, target};
+use oort_simulator::ship::{big_asteroid, cruiser, fighter, frigate, target, ShipClass};
 use oort_simulator::simulation::{self, Code};
 use test_log::test;
 
@@ -63,3 +63,46 @@ fn test_dps() {
     approx::assert_abs_diff_eq!(find_gun_dps(frigate(0), 2), 73.5, epsilon = 1.0);
     approx::assert_abs_diff_eq!(find_gun_dps(cruiser(0), 0), 673.0, epsilon = 1.0);
 }
+
+#[test]
+fn test_asteroid_fragmentation() {
+    let fragments = |seed: u32| {
+        let mut sim = simulation::Simulation::new("test", seed, &[Code::None, Code::None]);
+        let parent = ship::create(
+            &mut sim,
+            vector![0.0, 0.0],
+            vector![50.0, 0.0],
+            0.0,
+            big_asteroid(0),
+        );
+        sim.ship_mut(parent).data_mut().destroyed = true;
+        sim.step();
+        assert!(!sim.ships.contains(parent));
+        let mut fragments: Vec<_> = sim.ships.iter().cloned().collect();
+        fragments.sort();
+        (sim, fragments)
+    };
+
+    let (mut sim, handles) = fragments(0);
+    assert_eq!(handles.len(), ship::ASTEROID_FRAGMENTS);
+    for &handle in handles.iter() {
+        let ship = sim.ship(handle);
+        assert!(matches!(ship.data().class, ShipClass::Asteroid { .. }));
+        assert_eq!(ship.data().team, 9);
+        let spread = (ship.velocity() - vector![50.0, 0.0]).norm();
+        assert!(spread > 10.0 && spread < 110.0, "spread={spread}");
+    }
+
+    // The break-up is deterministic.
+    let (other, other_handles) = fragments(0);
+    for (&a, &b) in handles.iter().zip(other_handles.iter()) {
+        assert_eq!(sim.ship(a).position(), other.ship(b).position());
+        assert_eq!(sim.ship(a).velocity(), other.ship(b).velocity());
+    }
+
+    // Small asteroids leave debris.
+    sim.ship_mut(handles[0]).data_mut().destroyed = true;
+    sim.step();
+    assert_eq!(sim.ships.len(), ship::ASTEROID_FRAGMENTS - 1);
+    assert!(!sim.events().particles.is_empty());
+}
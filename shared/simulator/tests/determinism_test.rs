@@ -0,0 +1,50 @@
+mod common;
+
+use oort_simulator::scenario;
+use oort_simulator::simulation;
+use rayon::prelude::*;
+use test_log::test;
+
+fn run(scenario_name: &str, seed: u32) -> Vec<u64> {
+    let scenario = scenario::load(scenario_name);
+    let codes = scenario.solution_codes();
+    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
+    let mut hashes = vec![];
+    while sim.status() == scenario::Status::Running && sim.tick() < 1000 {
+        sim.step();
+        hashes.push(sim.hash());
+    }
+    hashes
+}
+
+#[test]
+fn test_deterministic_mode() {
+    let scenario_names = [
+        "deterministic_gunnery",
+        "deterministic_fighter_duel",
+        "deterministic_asteroid_duel",
+        "deterministic_fleet",
+    ];
+    let cases: Vec<_> = scenario_names
+        .iter()
+        .flat_map(|name| (0..4u32).map(move |seed| (*name, seed)))
+        .collect();
+    cases.par_iter().for_each(|(scenario_name, seed)| {
+        let a = run(scenario_name, *seed);
+        let b = run(scenario_name, *seed);
+        assert_eq!(a, b, "scenario {scenario_name} seed {seed} diverged");
+    });
+}
+
+#[test]
+fn test_golden_hashes() {
+    let results: common::Results = common::CASES
+        .par_iter()
+        .map(|&(scenario_name, seed)| (scenario_name, seed, common::run(scenario_name, seed)))
+        .collect();
+    if std::env::var("OORT_UPDATE_GOLDEN").is_ok() {
+        std::fs::write(common::GOLDEN_PATH, common::format(&results)).unwrap();
+        return;
+    }
+    common::check(&results);
+}
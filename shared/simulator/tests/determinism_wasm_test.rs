@@ -0,0 +1,18 @@
+//! Runs the deterministic physics golden hashes on wasm32.
+//!
+//! `CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner cargo test
+//! -p oort_simulator --target wasm32-unknown-unknown --test determinism_wasm_test`
+#![cfg(target_arch = "wasm32")]
+
+mod common;
+
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn test_golden_hashes() {
+    let results: common::Results = common::CASES
+        .iter()
+        .map(|&(scenario_name, seed)| (scenario_name, seed, common::run(scenario_name, seed)))
+        .collect();
+    common::check(&results);
+}
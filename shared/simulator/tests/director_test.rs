@@ -0,0 +1,98 @@
+use oort_simulator::scenario::{self, Status};
+use oort_simulator::ship::ShipClass;
+use oort_simulator::simulation::{Code, Simulation};
+use test_log::test;
+
+fn wat2wasm(wat: &str) -> Vec<u8> {
+    wabt::Wat2Wasm::new()
+        .convert(wat)
+        .unwrap()
+        .as_ref()
+        .to_vec()
+}
+
+const DIRECTOR: &str = r#"
+(module
+    (import "oort_director" "current_tick" (func $current_tick (result i32)))
+    (import "oort_director" "ship_count" (func $ship_count (result i32)))
+    (import "oort_director" "ship_team" (func $ship_team (param i32) (result i32)))
+    (import "oort_director" "spawn_ship" (func $spawn_ship (param i32 i32 f64 f64 f64 f64 f64)))
+    (import "oort_director" "draw_line" (func $draw_line (param f64 f64 f64 f64 i32)))
+    (import "oort_director" "set_status" (func $set_status (param i32 i32)))
+    (func (export "director_init") (param $seed i32)
+        (call $spawn_ship (i32.const 0) (i32.const 0)
+            (f64.const 0) (f64.const 0) (f64.const 0) (f64.const 0) (f64.const 0))
+        (call $spawn_ship (i32.const 4) (i32.const 1)
+            (f64.const 1000) (f64.const 0) (f64.const 0) (f64.const 0) (f64.const 0))
+        (call $draw_line (f64.const 0) (f64.const 0) (f64.const 1000) (f64.const 0)
+            (i32.const 0xff0000)))
+    (func (export "director_tick")
+        (if (i32.ge_u (call $current_tick) (i32.const 10))
+            (then
+                (if (i32.eq (call $ship_count) (i32.const 2))
+                    (then
+                        (call $set_status (i32.const 1) (call $ship_team (i32.const 0)))))))))
+"#;
+
+#[test]
+fn test_director() {
+    let director = Code::Wasm(wat2wasm(DIRECTOR));
+    let mut sim =
+        Simulation::new_with_director("director_test", &director, 0, &[Code::None, Code::None])
+            .unwrap();
+
+    let classes: Vec<ShipClass> = sim
+        .ships
+        .iter()
+        .map(|&handle| sim.ship(handle).data().class)
+        .collect();
+    assert_eq!(classes, vec![ShipClass::Fighter, ShipClass::Target]);
+    assert_eq!(sim.snapshot(0).scenario_lines.len(), 1);
+
+    let mut i = 0;
+    while sim.status() == Status::Running && i < 100 {
+        sim.step();
+        i += 1;
+    }
+    assert_eq!(sim.status(), Status::Victory { team: 0 });
+    assert_eq!(sim.tick(), 11);
+}
+
+#[test]
+fn test_director_infinite_loop() {
+    let wasm = wat2wasm(
+        r#"
+(module
+    (func (export "director_init") (param i32))
+    (func (export "director_tick")
+        (loop $loop (br $loop))))
+"#,
+    );
+    let mut sim = Simulation::new_with_director(
+        "director_infinite_loop",
+        &Code::Wasm(wasm),
+        0,
+        &[Code::None],
+    )
+    .unwrap();
+    sim.step();
+    assert_eq!(sim.status(), Status::Failed);
+}
+
+#[test]
+fn test_director_missing_exports() {
+    let wasm = wat2wasm("(module)");
+    assert!(scenario::load_director("director_missing_exports", &Code::Wasm(wasm)).is_err());
+}
+
+#[test]
+fn test_director_builtin_name() {
+    let wasm = wat2wasm(
+        r#"
+(module
+    (func (export "director_init") (param i32))
+    (func (export "director_tick")))
+"#,
+    );
+    assert!(scenario::load_director("fighter_duel", &Code::Wasm(wasm)).is_err());
+}
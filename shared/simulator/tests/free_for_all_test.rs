@@ -0,0 +1,47 @@
+use oort_simulator::scenario::{self, Status};
+use oort_simulator::simulation::{Code, Simulation};
+use test_log::test;
+
+fn destroy_team(sim: &mut Simulation, team: i32) {
+    let handles: Vec<_> = sim.ships.iter().cloned().collect();
+    for handle in handles {
+        if sim.ship(handle).data().team == team {
+            sim.ship_mut(handle).data_mut().destroyed = true;
+        }
+    }
+    sim.step();
+}
+
+#[test]
+fn test_rank_by_score() {
+    assert_eq!(
+        scenario::rank_by_score(&[3.0, 1.0, 3.0, 2.0]),
+        vec![0, 3, 0, 2]
+    );
+}
+
+#[test]
+fn test_last_team_standing() {
+    let mut sim = Simulation::new(
+        "free_for_all",
+        0,
+        &[Code::None, Code::None, Code::None, Code::None],
+    );
+    let mut teams: Vec<i32> = sim
+        .ships
+        .iter()
+        .map(|&handle| sim.ship(handle).data().team)
+        .collect();
+    teams.sort();
+    teams.dedup();
+    assert_eq!(teams, vec![0, 1, 2, 3]);
+    assert_eq!(sim.status(), Status::Running);
+
+    destroy_team(&mut sim, 2);
+    destroy_team(&mut sim, 0);
+    assert_eq!(sim.status(), Status::Running);
+    destroy_team(&mut sim, 3);
+
+    assert_eq!(sim.status(), Status::Victory { team: 1 });
+    assert_eq!(sim.ranking(), vec![2, 0, 3, 1]);
+}
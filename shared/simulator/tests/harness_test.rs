@@ -0,0 +1,46 @@
+use oort_simulator::harness;
+use oort_simulator::simulation::Code;
+use test_log::test;
+
+fn code() -> Code {
+    Code::Builtin("testing/harness".to_string())
+}
+
+#[test]
+fn test_list() {
+    let mut names = harness::list(&code()).unwrap();
+    names.sort();
+    assert_eq!(
+        names,
+        vec!["destroys_target", "expected_failure", "moves_forward"]
+    );
+}
+
+#[test]
+fn test_run() {
+    let result = harness::run(&code(), "moves_forward").unwrap();
+    assert!(result.passed, "{:?}", result.message);
+    assert_eq!(result.ticks, 60);
+
+    let result = harness::run(&code(), "destroys_target").unwrap();
+    assert!(result.passed, "{:?}", result.message);
+}
+
+#[test]
+fn test_failure() {
+    let result = harness::run(&code(), "expected_failure").unwrap();
+    assert!(!result.passed);
+    assert_eq!(
+        result.message.as_deref(),
+        Some("Expected ship 0 to be destroyed at tick 10")
+    );
+}
+
+#[test]
+fn test_unknown() {
+    let result = harness::run(&code(), "missing").unwrap();
+    assert!(!result.passed);
+    assert!(harness::list(&Code::Builtin("testing/test".to_string()))
+        .unwrap()
+        .is_empty());
+}
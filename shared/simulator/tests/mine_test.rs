@@ -0,0 +1,103 @@
+use nalgebra::vector;
+use oort_simulator::ship::{self, fighter, frigate_minelayer, ShipClass};
+use oort_simulator::simulation::{Code, Simulation};
+use test_log::test;
+
+fn mines(sim: &Simulation) -> Vec<ship::ShipHandle> {
+    sim.ships
+        .iter()
+        .filter(|&&handle| sim.ship(handle).data().class == ShipClass::Mine)
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_mine_launch() {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let ship0 = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![100.0, 0.0],
+        0.0,
+        frigate_minelayer(0),
+    );
+
+    // Weapon 4 is the mine launcher.
+    sim.ship_mut(ship0).fire(4);
+    sim.step();
+    let mine = mines(&sim)[0];
+    assert_eq!(sim.ship(mine).data().team, 0);
+
+    // The mine comes to a halt behind the frigate.
+    for _ in 0..300 {
+        sim.step();
+    }
+    assert!(sim.ship(mine).velocity().norm() < 1.0);
+    assert!(sim.ship(mine).position().x < sim.ship(ship0).position().x);
+
+    // Mines are limited.
+    for _ in 0..1000 {
+        sim.ship_mut(ship0).fire(4);
+        sim.step();
+    }
+    assert_eq!(mines(&sim).len(), 6);
+}
+
+#[test]
+fn test_mine_fuse() {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mine = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ship::mine(0),
+    );
+    let enemy = ship::create(
+        &mut sim,
+        vector![50.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        fighter(1),
+    );
+
+    // Not armed yet.
+    for _ in 0..ship::MINE_ARMING_TICKS - 1 {
+        sim.step();
+    }
+    assert!(sim.ships.contains(mine));
+
+    for _ in 0..10 {
+        sim.step();
+    }
+    assert!(!sim.ships.contains(mine));
+    assert!(sim.ship(enemy).data().health < 100.0);
+}
+
+#[test]
+fn test_mine_ignores_friendly_contact() {
+    let mut sim = Simulation::new("test", 0, &[Code::None, Code::None]);
+    let mine = ship::create(
+        &mut sim,
+        vector![0.0, 0.0],
+        vector![0.0, 0.0],
+        0.0,
+        ship::mine(0),
+    );
+    for _ in 0..ship::MINE_ARMING_TICKS {
+        sim.step();
+    }
+
+    // A friendly fighter runs straight into the armed mine.
+    ship::create(
+        &mut sim,
+        vector![-200.0, 0.0],
+        vector![200.0, 0.0],
+        0.0,
+        fighter(0),
+    );
+    for _ in 0..120 {
+        sim.step();
+    }
+    assert!(sim.ships.contains(mine));
+}
@@ -1,10 +1,12 @@
 use clap::Parser;
 use oort_simulator::simulation::Code;
+use oort_simulator::stats::CombatStats;
 use oort_simulator::{scenario, simulation};
 use oort_tools::AI;
 use rayon::prelude::*;
 use serde::Serialize;
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::default::Default;
 use std::path::PathBuf;
 
@@ -28,6 +30,9 @@ struct Arguments {
 
     #[clap(short, long, help = "Output results in JSON format")]
     json: bool,
+
+    #[clap(long, help = "Wasm director module scripting the scenario")]
+    director: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -36,7 +41,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         .init();
 
     let args = Arguments::parse();
-    scenario::load_safe(&args.scenario).expect("Unknown scenario");
+    let director = match args.director.as_ref() {
+        Some(path) => {
+            let director = Code::Wasm(std::fs::read(path)?);
+            scenario::load_director(&args.scenario, &director)
+                .map_err(|e| format!("Failed to load director: {}", e.msg))?;
+            Some(director)
+        }
+        None => {
+            scenario::load_safe(&args.scenario).expect("Unknown scenario");
+            None
+        }
+    };
     if args.shortcodes.len() < 2 {
         panic!("Expected at least two shortcodes");
     }
@@ -73,7 +89,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         .par_iter()
         .map(|player1| {
             let codes = vec![player0.compiled_code.clone(), player1.compiled_code.clone()];
-            let results = run_simulations(&args.scenario, codes, args.rounds);
+            let results = run_simulations(&args.scenario, director.as_ref(), codes, args.rounds);
             (player1, results)
         })
         .collect::<Vec<_>>();
@@ -89,6 +105,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
                     "draws": r.draws,
                     "times": r.times,
                     "average_time": r.times.iter().sum::<f64>() / r.times.len() as f64,
+                    "stats": r.team_stats,
                 })
             })
             .collect::<Vec<_>>();
@@ -125,6 +142,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             "  Average time: {:.3}",
             results.times.iter().sum::<f64>() / results.times.len() as f64
         );
+        let rounds = results.times.len() as f64;
+        for (team, stats) in results.team_stats.iter() {
+            println!(
+                "  Team {team} per round: shots {:.1}, hits {:.1}, warhead hits {:.1}, damage dealt {:.0}, damage taken {:.0}, kills {:.2}, missiles {:.1}, intercepts {:.2}, distance {:.0}m",
+                stats.shots_fired as f64 / rounds,
+                stats.hits as f64 / rounds,
+                stats.warhead_hits as f64 / rounds,
+                stats.damage_dealt / rounds,
+                stats.damage_taken / rounds,
+                stats.kills as f64 / rounds,
+                stats.missiles_launched as f64 / rounds,
+                stats.missiles_intercepted as f64 / rounds,
+                stats.distance_travelled / rounds,
+            );
+        }
     }
 
     Ok(())
@@ -136,15 +168,27 @@ struct Results {
     team1_wins: Vec<u32>,
     draws: Vec<u32>,
     times: Vec<f64>,
+    /// Summed over all rounds, indexed by team ID.
+    team_stats: BTreeMap<i32, CombatStats>,
 }
 
-fn run_simulations(scenario_name: &str, codes: Vec<Code>, rounds: u32) -> Results {
-    let seed_statuses: Vec<(u32, (scenario::Status, f64))> = (0..rounds)
+fn run_simulations(
+    scenario_name: &str,
+    director: Option<&Code>,
+    codes: Vec<Code>,
+    rounds: u32,
+) -> Results {
+    let seed_statuses: Vec<(u32, SimulationResult)> = (0..rounds)
         .into_par_iter()
-        .map(|seed| (seed, run_simulation(scenario_name, seed, codes.clone())))
+        .map(|seed| {
+            (
+                seed,
+                run_simulation(scenario_name, director, seed, codes.clone()),
+            )
+        })
         .collect();
     let mut results: Results = Default::default();
-    for (seed, (status, time)) in seed_statuses {
+    for (seed, (status, time, team_stats)) in seed_statuses {
         match status {
             scenario::Status::Victory { team: 0 } => results.team0_wins.push(seed),
             scenario::Status::Victory { team: 1 } => results.team1_wins.push(seed),
@@ -153,14 +197,30 @@ fn run_simulations(scenario_name: &str, codes: Vec<Code>, rounds: u32) -> Result
             _ => unreachable!(),
         }
         results.times.push(time);
+        for (team, stats) in team_stats {
+            results.team_stats.entry(team).or_default().add(&stats);
+        }
     }
     results
 }
 
-fn run_simulation(scenario_name: &str, seed: u32, codes: Vec<Code>) -> (scenario::Status, f64) {
-    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
+type SimulationResult = (scenario::Status, f64, BTreeMap<i32, CombatStats>);
+
+fn run_simulation(
+    scenario_name: &str,
+    director: Option<&Code>,
+    seed: u32,
+    codes: Vec<Code>,
+) -> SimulationResult {
+    let mut sim = match director {
+        Some(director) => {
+            simulation::Simulation::new_with_director(scenario_name, director, seed, &codes)
+                .unwrap()
+        }
+        None => simulation::Simulation::new(scenario_name, seed, &codes),
+    };
     while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
         sim.step();
     }
-    (sim.status(), sim.score_time())
+    (sim.status(), sim.score_time(), sim.stats().teams())
 }
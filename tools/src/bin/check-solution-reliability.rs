@@ -4,7 +4,14 @@ use comfy_table::Table;
 use oort_simulator::simulation::Code;
 use oort_simulator::{scenario, simulation};
 use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeSet;
 use std::default::Default;
+use std::path::PathBuf;
+
+const PERCENTILES: &[u32] = &[5, 25, 50, 75, 95];
+const HISTOGRAM_BINS: usize = 20;
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
 
 #[derive(Parser, Debug)]
 #[clap()]
@@ -17,6 +24,87 @@ struct Arguments {
     /// Base seed
     #[clap(short, long, default_value = "0")]
     seed: u32,
+
+    /// Shortcode or path of the AI to test instead of the scenario's reference solution
+    #[clap(long)]
+    solution: Option<String>,
+
+    /// Shortcode or path of a baseline AI to compare the solution against
+    #[clap(long)]
+    compare: Option<String>,
+
+    #[clap(short, long)]
+    dev: bool,
+
+    #[clap(long, default_value = "/tmp/oort-wasm-cache")]
+    wasm_cache: Option<PathBuf>,
+
+    /// Write the report as JSON to this path
+    #[clap(long)]
+    json: Option<PathBuf>,
+
+    /// Write the report as HTML to this path
+    #[clap(long)]
+    html: Option<PathBuf>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct RunResult {
+    seed: u32,
+    status: scenario::Status,
+    time: Option<f64>,
+    errors: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct HistogramBin {
+    start: f64,
+    end: f64,
+    count: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct TimeStats {
+    mean: f64,
+    stddev: f64,
+    min: f64,
+    max: f64,
+    percentiles: Vec<(u32, f64)>,
+    histogram: Vec<HistogramBin>,
+}
+
+#[derive(Serialize, Debug)]
+struct SolutionReport {
+    name: String,
+    runs: usize,
+    victories: usize,
+    time_stats: Option<TimeStats>,
+    failures: Vec<RunResult>,
+    #[serde(skip)]
+    results: Vec<RunResult>,
+}
+
+#[derive(Serialize, Debug)]
+struct Comparison {
+    baseline: String,
+    candidate: String,
+    /// Seeds won by both AIs, used for the paired time comparison.
+    paired_seeds: usize,
+    /// Mean of candidate time minus baseline time; negative is an improvement.
+    mean_time_difference: Option<f64>,
+    time_p_value: Option<f64>,
+    success_rate_difference: f64,
+    success_rate_p_value: Option<f64>,
+    significant: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct ScenarioReport {
+    scenario_name: String,
+    base_seed: u32,
+    rounds: u32,
+    solutions: Vec<SolutionReport>,
+    comparison: Option<Comparison>,
 }
 
 #[tokio::main]
@@ -28,9 +116,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     let args = Arguments::parse();
 
-    let scenarios = if let Some(scenario_name) = args.scenario {
+    let scenarios = if let Some(scenario_name) = args.scenario.as_ref() {
         vec![scenario_name.clone()]
     } else {
+        if args.solution.is_some() || args.compare.is_some() {
+            panic!("A scenario is required when testing a specific solution");
+        }
         let categories = scenario::list();
         categories
             .iter()
@@ -40,56 +131,477 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             .clone()
     };
 
-    let progress = indicatif::ProgressBar::new(args.rounds as u64 * scenarios.len() as u64);
+    if args.compare.is_some() && args.solution.is_none() {
+        panic!("--compare requires --solution");
+    }
+
+    // (name, player code), None means the scenario's reference solution.
+    let mut solutions: Vec<(String, Option<Code>)> = vec![];
+    let shortcodes: Vec<String> = args
+        .compare
+        .iter()
+        .chain(args.solution.iter())
+        .cloned()
+        .collect();
+    if shortcodes.is_empty() {
+        solutions.push(("reference".to_string(), None));
+    } else {
+        log::info!("Compiling AIs");
+        let http = reqwest::Client::new();
+        let ais = oort_tools::fetch_and_compile_multiple(
+            &http,
+            &shortcodes,
+            args.dev,
+            args.wasm_cache.as_deref(),
+        )
+        .await?;
+        for ai in ais {
+            solutions.push((ai.name, Some(ai.compiled_code)));
+        }
+    }
+
+    let progress = indicatif::ProgressBar::new(
+        args.rounds as u64 * scenarios.len() as u64 * solutions.len() as u64,
+    );
 
-    let failures: Vec<(String, Vec<u32>)> = scenarios
+    let reports: Vec<ScenarioReport> = scenarios
         .par_iter()
         .map(|scenario_name| {
             let scenario =
                 scenario::load_safe(scenario_name).expect("Unknown scenario {scenario_name}");
-            let codes = scenario.solution_codes();
-            let failed_seeds: Vec<u32> = (args.seed..(args.seed + args.rounds))
-                .into_par_iter()
-                .filter_map(|seed| {
-                    let status = run_simulation(scenario_name, seed, codes.clone());
-                    progress.inc(1);
-                    match status {
-                        scenario::Status::Victory { team: 0 } => None,
-                        _ => Some(seed),
+            let solution_reports: Vec<SolutionReport> = solutions
+                .iter()
+                .map(|(name, code)| {
+                    let mut codes = scenario.solution_codes();
+                    if let Some(code) = code {
+                        codes[0] = code.clone();
                     }
+                    let results: Vec<RunResult> = (args.seed..(args.seed + args.rounds))
+                        .into_par_iter()
+                        .map(|seed| {
+                            let result = run_simulation(scenario_name, seed, codes.clone());
+                            progress.inc(1);
+                            result
+                        })
+                        .collect();
+                    summarize(name, results)
                 })
                 .collect();
-            (scenario_name.clone(), failed_seeds)
+            let comparison = if solution_reports.len() == 2 {
+                Some(compare(&solution_reports[0], &solution_reports[1]))
+            } else {
+                None
+            };
+            ScenarioReport {
+                scenario_name: scenario_name.clone(),
+                base_seed: args.seed,
+                rounds: args.rounds,
+                solutions: solution_reports,
+                comparison,
+            }
         })
         .collect();
     progress.finish_and_clear();
 
+    print_tables(&reports);
+
+    if let Some(path) = args.json.as_ref() {
+        std::fs::write(path, serde_json::to_string_pretty(&reports)?)?;
+        log::info!("Wrote JSON report to {}", path.display());
+    }
+
+    if let Some(path) = args.html.as_ref() {
+        std::fs::write(path, render_html(&reports))?;
+        log::info!("Wrote HTML report to {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn run_simulation(scenario_name: &str, seed: u32, codes: Vec<Code>) -> RunResult {
+    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
+    let mut errors = BTreeSet::new();
+    let mut collect_errors = |sim: &simulation::Simulation| {
+        for error in sim.events().errors.iter() {
+            errors.insert(error.msg.clone());
+        }
+        for &handle in sim.ships.iter() {
+            let ship = sim.ship(handle);
+            let data = ship.data();
+            if data.team == 0 {
+                if let Some(msg) = data.crash_message.as_ref() {
+                    errors.insert(msg.clone());
+                }
+            }
+        }
+    };
+    collect_errors(&sim);
+    while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
+        sim.step();
+        collect_errors(&sim);
+    }
+    let status = sim.status();
+    RunResult {
+        seed,
+        status,
+        time: matches!(status, scenario::Status::Victory { team: 0 }).then(|| sim.score_time()),
+        errors: errors.into_iter().collect(),
+    }
+}
+
+fn summarize(name: &str, mut results: Vec<RunResult>) -> SolutionReport {
+    results.sort_by_key(|r| r.seed);
+    let mut times: Vec<f64> = results.iter().filter_map(|r| r.time).collect();
+    times.sort_by(|a, b| a.total_cmp(b));
+    let failures: Vec<RunResult> = results
+        .iter()
+        .filter(|r| r.time.is_none() || !r.errors.is_empty())
+        .cloned()
+        .collect();
+    SolutionReport {
+        name: name.to_string(),
+        runs: results.len(),
+        victories: times.len(),
+        time_stats: time_stats(&times),
+        failures,
+        results,
+    }
+}
+
+/// Computes statistics over completion times, which must be sorted.
+fn time_stats(times: &[f64]) -> Option<TimeStats> {
+    if times.is_empty() {
+        return None;
+    }
+    let (mean, stddev) = mean_and_stddev(times);
+    let min = times[0];
+    let max = times[times.len() - 1];
+    let percentiles = PERCENTILES
+        .iter()
+        .map(|&p| (p, percentile(times, p as f64 / 100.0)))
+        .collect();
+
+    let width = ((max - min) / HISTOGRAM_BINS as f64).max(1e-9);
+    let mut histogram: Vec<HistogramBin> = (0..HISTOGRAM_BINS)
+        .map(|i| HistogramBin {
+            start: min + width * i as f64,
+            end: min + width * (i + 1) as f64,
+            count: 0,
+        })
+        .collect();
+    for &t in times {
+        let i = (((t - min) / width) as usize).min(HISTOGRAM_BINS - 1);
+        histogram[i].count += 1;
+    }
+
+    Some(TimeStats {
+        mean,
+        stddev,
+        min,
+        max,
+        percentiles,
+        histogram,
+    })
+}
+
+/// Linear interpolation between closest ranks.
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    let rank = q * (sorted.len() - 1) as f64;
+    let lo = rank.floor() as usize;
+    let hi = rank.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+fn mean_and_stddev(xs: &[f64]) -> (f64, f64) {
+    let n = xs.len() as f64;
+    let mean = xs.iter().sum::<f64>() / n;
+    if xs.len() < 2 {
+        return (mean, 0.0);
+    }
+    let variance = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (mean, variance.sqrt())
+}
+
+/// Compares two solutions run on the same seeds.
+///
+/// Times are compared with a paired t-test over seeds both solutions won, and
+/// success rates with a two-proportion z-test. Both use the normal
+/// approximation, which is reasonable for the usual 100+ rounds.
+fn compare(baseline: &SolutionReport, candidate: &SolutionReport) -> Comparison {
+    let differences: Vec<f64> = baseline
+        .results
+        .iter()
+        .zip(candidate.results.iter())
+        .filter_map(|(a, b)| {
+            assert_eq!(a.seed, b.seed);
+            Some(b.time? - a.time?)
+        })
+        .collect();
+
+    let (mean_time_difference, time_p_value) = if differences.len() >= 2 {
+        let (mean, stddev) = mean_and_stddev(&differences);
+        let p = if stddev == 0.0 {
+            if mean == 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        } else {
+            let t = mean / (stddev / (differences.len() as f64).sqrt());
+            two_sided_p_value(t)
+        };
+        (Some(mean), Some(p))
+    } else {
+        (None, None)
+    };
+
+    let n1 = baseline.runs as f64;
+    let n2 = candidate.runs as f64;
+    let p1 = baseline.victories as f64 / n1;
+    let p2 = candidate.victories as f64 / n2;
+    let pooled = (baseline.victories + candidate.victories) as f64 / (n1 + n2);
+    let se = (pooled * (1.0 - pooled) * (1.0 / n1 + 1.0 / n2)).sqrt();
+    let success_rate_p_value = (se > 0.0).then(|| two_sided_p_value((p2 - p1) / se));
+
+    let significant = time_p_value
+        .map(|p| p < SIGNIFICANCE_LEVEL)
+        .unwrap_or(false)
+        || success_rate_p_value
+            .map(|p| p < SIGNIFICANCE_LEVEL)
+            .unwrap_or(false);
+
+    Comparison {
+        baseline: baseline.name.clone(),
+        candidate: candidate.name.clone(),
+        paired_seeds: differences.len(),
+        mean_time_difference,
+        time_p_value,
+        success_rate_difference: p2 - p1,
+        success_rate_p_value,
+        significant,
+    }
+}
+
+fn two_sided_p_value(z: f64) -> f64 {
+    (2.0 * (1.0 - normal_cdf(z.abs()))).clamp(0.0, 1.0)
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz and Stegun formula 7.1.26, max error 1.5e-7.
+fn erf(x: f64) -> f64 {
+    let sign = x.signum();
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let y = 1.0
+        - (((((1.061405429 * t - 1.453152027) * t) + 1.421413741) * t - 0.284496736) * t
+            + 0.254829592)
+            * t
+            * (-x * x).exp();
+    sign * y
+}
+
+fn format_time(t: Option<f64>) -> String {
+    t.map(|t| format!("{t:.3}s"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn print_tables(reports: &[ScenarioReport]) {
     let mut table = Table::new();
     table.load_preset(UTF8_FULL);
-    table.set_header(vec!["Scenario", "Failure Count", "Sample Seeds"]);
-    for (scenario_name, failed_seeds) in failures {
-        if !failed_seeds.is_empty() {
-            table.add_row(vec![
-                scenario_name.clone(),
-                failed_seeds.len().to_string(),
-                failed_seeds
+    let mut header = vec![
+        "Scenario".to_string(),
+        "Solution".to_string(),
+        "Victories".to_string(),
+        "Mean".to_string(),
+    ];
+    header.extend(PERCENTILES.iter().map(|p| format!("p{p}")));
+    header.push("Failed Seeds".to_string());
+    table.set_header(header);
+    for report in reports {
+        for solution in report.solutions.iter() {
+            let stats = solution.time_stats.as_ref();
+            let mut row = vec![
+                report.scenario_name.clone(),
+                solution.name.clone(),
+                format!("{}/{}", solution.victories, solution.runs),
+                format_time(stats.map(|s| s.mean)),
+            ];
+            row.extend(
+                PERCENTILES
+                    .iter()
+                    .enumerate()
+                    .map(|(i, _)| format_time(stats.map(|s| s.percentiles[i].1))),
+            );
+            row.push(
+                solution
+                    .failures
                     .iter()
+                    .filter(|r| r.time.is_none())
                     .take(10)
-                    .map(|seed| seed.to_string())
+                    .map(|r| r.seed.to_string())
                     .collect::<Vec<_>>()
                     .join(", "),
-            ]);
+            );
+            table.add_row(row);
         }
     }
     println!("{table}");
 
-    Ok(())
+    for report in reports {
+        for solution in report.solutions.iter() {
+            if let Some(stats) = solution.time_stats.as_ref() {
+                println!(
+                    "{} / {} completion times:",
+                    report.scenario_name, solution.name
+                );
+                let max_count = stats.histogram.iter().map(|b| b.count).max().unwrap_or(1);
+                for bin in stats.histogram.iter() {
+                    let bar = "#".repeat((bin.count * 40).div_ceil(max_count.max(1)));
+                    println!(
+                        "  {:>9.3}s - {:>9.3}s {:>5} {}",
+                        bin.start, bin.end, bin.count, bar
+                    );
+                }
+            }
+            for failure in solution.failures.iter().filter(|r| !r.errors.is_empty()) {
+                println!(
+                    "{} / {} seed {} ({}): {}",
+                    report.scenario_name,
+                    solution.name,
+                    failure.seed,
+                    failure.status,
+                    failure.errors.join("; ")
+                );
+            }
+        }
+
+        if let Some(comparison) = report.comparison.as_ref() {
+            println!(
+                "{}: {} vs {}: time difference {} (p={}) over {} seeds, success rate difference {:+.1}% (p={}){}",
+                report.scenario_name,
+                comparison.candidate,
+                comparison.baseline,
+                comparison
+                    .mean_time_difference
+                    .map(|d| format!("{d:+.3}s"))
+                    .unwrap_or_else(|| "-".to_string()),
+                format_p_value(comparison.time_p_value),
+                comparison.paired_seeds,
+                comparison.success_rate_difference * 100.0,
+                format_p_value(comparison.success_rate_p_value),
+                if comparison.significant {
+                    ", significant"
+                } else {
+                    ", not significant"
+                },
+            );
+        }
+    }
 }
 
-fn run_simulation(scenario_name: &str, seed: u32, codes: Vec<Code>) -> scenario::Status {
-    let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
-    while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
-        sim.step();
+fn format_p_value(p: Option<f64>) -> String {
+    p.map(|p| format!("{p:.4}"))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_html(reports: &[ScenarioReport]) -> String {
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Solution reliability report</title>\n");
+    out.push_str("<style>body { font-family: sans-serif; } table { border-collapse: collapse; margin-bottom: 1em; } td, th { border: 1px solid #999; padding: 2px 8px; } .bar { background: #4a7; height: 1em; }</style>\n");
+    out.push_str("</head><body>\n");
+    for report in reports {
+        out.push_str(&format!(
+            "<h1>{}</h1>\n<p>Seeds {}..{}</p>\n",
+            escape_html(&report.scenario_name),
+            report.base_seed,
+            report.base_seed + report.rounds
+        ));
+        for solution in report.solutions.iter() {
+            out.push_str(&format!(
+                "<h2>{}</h2>\n<p>Victories: {}/{}</p>\n",
+                escape_html(&solution.name),
+                solution.victories,
+                solution.runs
+            ));
+            if let Some(stats) = solution.time_stats.as_ref() {
+                out.push_str("<table><tr><th>Mean</th><th>Stddev</th><th>Min</th>");
+                for p in PERCENTILES {
+                    out.push_str(&format!("<th>p{p}</th>"));
+                }
+                out.push_str("<th>Max</th></tr>\n<tr>");
+                for v in [stats.mean, stats.stddev, stats.min]
+                    .into_iter()
+                    .chain(stats.percentiles.iter().map(|(_, v)| *v))
+                    .chain(std::iter::once(stats.max))
+                {
+                    out.push_str(&format!("<td>{v:.3}s</td>"));
+                }
+                out.push_str("</tr></table>\n");
+
+                let max_count = stats.histogram.iter().map(|b| b.count).max().unwrap_or(1);
+                out.push_str("<table><tr><th>Time</th><th>Count</th><th></th></tr>\n");
+                for bin in stats.histogram.iter() {
+                    out.push_str(&format!(
+                        "<tr><td>{:.3}s - {:.3}s</td><td>{}</td><td style=\"width: 300px\"><div class=\"bar\" style=\"width: {:.1}%\"></div></td></tr>\n",
+                        bin.start,
+                        bin.end,
+                        bin.count,
+                        bin.count as f64 * 100.0 / max_count.max(1) as f64
+                    ));
+                }
+                out.push_str("</table>\n");
+            }
+            if !solution.failures.is_empty() {
+                out.push_str("<table><tr><th>Seed</th><th>Status</th><th>Errors</th></tr>\n");
+                for failure in solution.failures.iter() {
+                    out.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                        failure.seed,
+                        failure.status,
+                        failure
+                            .errors
+                            .iter()
+                            .map(|e| escape_html(e))
+                            .collect::<Vec<_>>()
+                            .join("<br>")
+                    ));
+                }
+                out.push_str("</table>\n");
+            }
+        }
+        if let Some(comparison) = report.comparison.as_ref() {
+            out.push_str(&format!(
+                "<h2>{} vs {}</h2>\n<table>\n\
+                 <tr><td>Paired seeds</td><td>{}</td></tr>\n\
+                 <tr><td>Mean time difference</td><td>{}</td></tr>\n\
+                 <tr><td>Time p-value</td><td>{}</td></tr>\n\
+                 <tr><td>Success rate difference</td><td>{:+.1}%</td></tr>\n\
+                 <tr><td>Success rate p-value</td><td>{}</td></tr>\n\
+                 <tr><td>Significant</td><td>{}</td></tr>\n</table>\n",
+                escape_html(&comparison.candidate),
+                escape_html(&comparison.baseline),
+                comparison.paired_seeds,
+                comparison
+                    .mean_time_difference
+                    .map(|d| format!("{d:+.3}s"))
+                    .unwrap_or_else(|| "-".to_string()),
+                format_p_value(comparison.time_p_value),
+                comparison.success_rate_difference * 100.0,
+                format_p_value(comparison.success_rate_p_value),
+                comparison.significant,
+            ));
+        }
     }
-    sim.status()
+    out.push_str("</body></html>\n");
+    out
 }
@@ -16,6 +16,10 @@ struct Arguments {
     #[clap(short, long)]
     dev: bool,
 
+    /// Use the fixed-point physics integrator.
+    #[clap(long)]
+    deterministic: bool,
+
     #[clap(long, default_value = "/tmp/oort-wasm-cache")]
     wasm_cache: Option<PathBuf>,
 }
@@ -25,7 +29,12 @@ async fn main() -> anyhow::Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     let args = Arguments::parse();
-    scenario::load_safe(&args.scenario).expect("Unknown scenario");
+    let scenario_name = if args.deterministic {
+        format!("deterministic_{}", args.scenario)
+    } else {
+        args.scenario.clone()
+    };
+    scenario::load_safe(&scenario_name).expect("Unknown scenario");
     if args.shortcodes.len() != 2 {
         panic!("Expected two shortcodes");
     }
@@ -43,7 +52,7 @@ async fn main() -> anyhow::Result<()> {
 
     let mut sims = [0, 1]
         .iter()
-        .map(|_| simulation::Simulation::new(&args.scenario, args.seed, &codes))
+        .map(|_| simulation::Simulation::new(&scenario_name, args.seed, &codes))
         .collect::<Vec<_>>();
     while sims[0].status() == scenario::Status::Running && sims[0].tick() < scenario::MAX_TICKS {
         let hashes = sims.iter().map(|sim| sim.hash()).collect::<Vec<_>>();
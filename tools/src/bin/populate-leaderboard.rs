@@ -99,6 +99,7 @@ async fn run(
             code,
             scenario_name,
             code_size,
+            gas,
             ..
         } = &msg.payload
         {
@@ -111,6 +112,7 @@ async fn run(
                 code_size: *code_size,
                 time: time.unwrap(),
                 rescored_version: None,
+                gas: *gas,
             });
         }
     }
@@ -0,0 +1,185 @@
+use anyhow::Context;
+use clap::Parser;
+use oort_headless_renderer::encode::{self, ApngWriter, GifWriter};
+use oort_headless_renderer::{Camera, RenderOptions, Renderer};
+use oort_simulator::simulation::{Code, Simulation};
+use oort_simulator::{scenario, snapshot::Snapshot};
+use std::path::{Path, PathBuf};
+
+/// Renders a match to PNG frames, an animated GIF, or an APNG.
+#[derive(Parser, Debug)]
+#[clap()]
+struct Arguments {
+    scenario: String,
+    shortcodes: Vec<String>,
+
+    /// Output file. A ".gif" or ".png" extension writes an animation, anything
+    /// else is treated as a directory of numbered PNG frames.
+    #[clap(short, long)]
+    output: PathBuf,
+
+    #[clap(short, long, default_value = "0")]
+    seed: u32,
+
+    #[clap(long, default_value = "640")]
+    width: u32,
+
+    #[clap(long, default_value = "360")]
+    height: u32,
+
+    #[clap(long, default_value = "30")]
+    fps: u32,
+
+    /// Skip this many seconds at the start of the match.
+    #[clap(long, default_value = "0")]
+    start: f64,
+
+    /// Maximum length of the clip in seconds.
+    #[clap(long)]
+    duration: Option<f64>,
+
+    /// Show the whole world instead of following the ships.
+    #[clap(long)]
+    world_camera: bool,
+
+    /// Draw debug lines and text from all ships.
+    #[clap(long)]
+    debug: bool,
+
+    #[clap(short, long)]
+    dev: bool,
+
+    #[clap(long, default_value = "/tmp/oort-wasm-cache")]
+    wasm_cache: Option<PathBuf>,
+}
+
+enum Output {
+    Frames(PathBuf),
+    Gif(GifWriter<std::io::BufWriter<std::fs::File>>),
+    Apng(ApngWriter<std::io::BufWriter<std::fs::File>>),
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("render=info"))
+        .init();
+
+    let args = Arguments::parse();
+    scenario::load_safe(&args.scenario).context("Unknown scenario")?;
+    anyhow::ensure!(
+        args.fps > 0 && args.fps <= 60,
+        "fps must be between 1 and 60"
+    );
+
+    log::info!("Compiling AIs");
+    let http = reqwest::Client::new();
+    let ais = oort_tools::fetch_and_compile_multiple(
+        &http,
+        &args.shortcodes,
+        args.dev,
+        args.wasm_cache.as_deref(),
+    )
+    .await?;
+    let codes: Vec<Code> = ais.iter().map(|ai| ai.compiled_code.clone()).collect();
+
+    let ticks_per_frame = (60 / args.fps).max(1);
+    let start_tick = (args.start * 60.0) as u32;
+    let end_tick = args
+        .duration
+        .map(|d| start_tick + (d * 60.0) as u32)
+        .unwrap_or(scenario::MAX_TICKS)
+        .min(scenario::MAX_TICKS);
+    let is_frame = |tick: u32| tick >= start_tick && (tick - start_tick) % ticks_per_frame == 0;
+
+    let mut output = match args.output.extension().and_then(|x| x.to_str()) {
+        Some("gif") => Output::Gif(GifWriter::new(create(&args.output)?, args.fps)?),
+        Some("png") => {
+            // APNG needs the frame count up front, so run the match once to find it.
+            log::info!("Counting frames");
+            let mut num_frames = 0;
+            run(&args.scenario, args.seed, &codes, end_tick, |snapshot| {
+                if is_frame(snapshot.tick) {
+                    num_frames += 1;
+                }
+            });
+            Output::Apng(ApngWriter::new(
+                create(&args.output)?,
+                args.width,
+                args.height,
+                num_frames.max(1),
+                args.fps,
+            )?)
+        }
+        _ => {
+            std::fs::create_dir_all(&args.output)?;
+            Output::Frames(args.output.clone())
+        }
+    };
+
+    log::info!("Rendering");
+    let mut renderer = Renderer::new(args.width, args.height);
+    renderer.set_options(RenderOptions {
+        debug: args.debug,
+        ..Default::default()
+    });
+    let mut camera: Option<Camera> = None;
+    let mut frame_index = 0;
+    let mut result = Ok(());
+    run(&args.scenario, args.seed, &codes, end_tick, |snapshot| {
+        renderer.update(snapshot);
+        if result.is_err() || !is_frame(snapshot.tick) {
+            return;
+        }
+        let target = if args.world_camera {
+            Camera::world(snapshot)
+        } else {
+            Camera::fit_ships(snapshot, renderer.aspect_ratio(), 1000.0)
+        };
+        let current = match camera {
+            Some(camera) => camera.approach(&target, 0.1),
+            None => target,
+        };
+        camera = Some(current);
+        let image = renderer.render(&current, snapshot);
+        result = match &mut output {
+            Output::Frames(dir) => {
+                encode::write_png(&dir.join(format!("frame{frame_index:05}.png")), &image)
+            }
+            Output::Gif(writer) => writer.add_frame(&image),
+            Output::Apng(writer) => writer.add_frame(&image),
+        };
+        frame_index += 1;
+    });
+    result?;
+
+    match output {
+        Output::Frames(_) => {}
+        Output::Gif(writer) => writer.finish()?,
+        Output::Apng(writer) => writer.finish()?,
+    }
+    log::info!("Wrote {} frames to {}", frame_index, args.output.display());
+
+    Ok(())
+}
+
+fn create(path: &Path) -> anyhow::Result<std::io::BufWriter<std::fs::File>> {
+    let file =
+        std::fs::File::create(path).with_context(|| format!("creating {}", path.display()))?;
+    Ok(std::io::BufWriter::new(file))
+}
+
+/// Runs the match, calling `f` with the snapshot after every tick.
+fn run(
+    scenario_name: &str,
+    seed: u32,
+    codes: &[Code],
+    end_tick: u32,
+    mut f: impl FnMut(&Snapshot),
+) {
+    let mut sim = Simulation::new(scenario_name, seed, codes);
+    f(&sim.snapshot(0));
+    while sim.status() == scenario::Status::Running && sim.tick() < end_tick {
+        sim.step();
+        f(&sim.snapshot(0));
+    }
+}
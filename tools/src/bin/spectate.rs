@@ -0,0 +1,438 @@
+use anyhow::Context;
+use clap::Parser;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use oort_simulator::scenario;
+use oort_simulator::ship::ShipClass;
+use oort_simulator::simulation::{Code, Simulation};
+use oort_simulator::snapshot::{ShipSnapshot, Snapshot};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::symbols::Marker;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::canvas::{Canvas, Points, Rectangle};
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table, TableState, Wrap};
+use ratatui::{Frame, Terminal};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Watches a match live in the terminal.
+///
+/// Keys: q quit, space pause, n/. step, +/- speed, z/x zoom, arrows pan,
+/// tab/j/k select ship, f follow selected ship, 0 reset view.
+#[derive(Parser, Debug)]
+#[clap()]
+struct Arguments {
+    scenario: String,
+    shortcodes: Vec<String>,
+
+    #[clap(short, long, default_value = "0")]
+    seed: u32,
+
+    #[clap(short, long)]
+    dev: bool,
+
+    #[clap(long, default_value = "/tmp/oort-wasm-cache")]
+    wasm_cache: Option<PathBuf>,
+}
+
+const FRAME_TIME: Duration = Duration::from_millis(33);
+const SPEEDS: &[f64] = &[0.125, 0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0];
+const DEFAULT_SPEED_INDEX: usize = 3;
+const FOLLOW_VIEW_WIDTH: f64 = 4000.0;
+
+struct App {
+    scenario_name: String,
+    sim: Box<Simulation>,
+    snapshot: Snapshot,
+    paused: bool,
+    speed_index: usize,
+    pending_ticks: f64,
+    center: (f64, f64),
+    view_width: f64,
+    selected: Option<u64>,
+    follow: bool,
+    table_state: TableState,
+}
+
+impl App {
+    fn new(scenario_name: &str, sim: Box<Simulation>) -> Self {
+        let snapshot = sim.snapshot(0);
+        let view_width = snapshot.world_size;
+        let mut app = Self {
+            scenario_name: scenario_name.to_string(),
+            sim,
+            snapshot,
+            paused: false,
+            speed_index: DEFAULT_SPEED_INDEX,
+            pending_ticks: 0.0,
+            center: (0.0, 0.0),
+            view_width,
+            selected: None,
+            follow: false,
+            table_state: TableState::default(),
+        };
+        app.select_offset(0);
+        app
+    }
+
+    fn running(&self) -> bool {
+        self.sim.status() == scenario::Status::Running && self.sim.tick() < scenario::MAX_TICKS
+    }
+
+    fn step(&mut self) {
+        if self.running() {
+            self.sim.step();
+            self.snapshot = self.sim.snapshot(0);
+        }
+    }
+
+    /// Advances the simulation by one frame's worth of ticks at the current speed.
+    fn update(&mut self) {
+        if !self.paused {
+            self.pending_ticks += SPEEDS[self.speed_index];
+            while self.pending_ticks >= 1.0 {
+                self.pending_ticks -= 1.0;
+                self.step();
+            }
+        }
+        if !self
+            .snapshot
+            .ships
+            .iter()
+            .any(|s| Some(s.id) == self.selected)
+        {
+            self.select_offset(0);
+        }
+        if self.follow {
+            if let Some(ship) = self.selected_ship() {
+                self.center = (ship.position.x, ship.position.y);
+            }
+        }
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        self.snapshot
+            .ships
+            .iter()
+            .position(|s| Some(s.id) == self.selected)
+    }
+
+    fn selected_ship(&self) -> Option<&ShipSnapshot> {
+        self.selected_index().map(|i| &self.snapshot.ships[i])
+    }
+
+    fn select_offset(&mut self, offset: isize) {
+        let n = self.snapshot.ships.len() as isize;
+        if n == 0 {
+            self.selected = None;
+            self.table_state.select(None);
+            return;
+        }
+        let current = self.selected_index().map(|i| i as isize).unwrap_or(0);
+        let index = (current + offset).rem_euclid(n) as usize;
+        self.selected = Some(self.snapshot.ships[index].id);
+        self.table_state.select(Some(index));
+    }
+
+    fn pan(&mut self, dx: f64, dy: f64) {
+        self.follow = false;
+        self.center.0 += dx * self.view_width * 0.1;
+        self.center.1 += dy * self.view_width * 0.1;
+    }
+
+    fn reset_view(&mut self) {
+        self.follow = false;
+        self.center = (0.0, 0.0);
+        self.view_width = self.snapshot.world_size;
+    }
+
+    /// Returns true if the app should quit.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Char(' ') => self.paused = !self.paused,
+            KeyCode::Char('n') | KeyCode::Char('.') => {
+                self.paused = true;
+                self.step();
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.speed_index = (self.speed_index + 1).min(SPEEDS.len() - 1)
+            }
+            KeyCode::Char('-') => self.speed_index = self.speed_index.saturating_sub(1),
+            KeyCode::Char('z') => self.view_width = (self.view_width * 0.5).max(100.0),
+            KeyCode::Char('x') => {
+                self.view_width = (self.view_width * 2.0).min(self.snapshot.world_size * 2.0)
+            }
+            KeyCode::Tab | KeyCode::Char('j') | KeyCode::Down => self.select_offset(1),
+            KeyCode::BackTab | KeyCode::Char('k') | KeyCode::Up => self.select_offset(-1),
+            KeyCode::Char('f') => {
+                self.follow = !self.follow;
+                if self.follow {
+                    self.view_width = self.view_width.min(FOLLOW_VIEW_WIDTH);
+                }
+            }
+            KeyCode::Left => self.pan(-1.0, 0.0),
+            KeyCode::Right => self.pan(1.0, 0.0),
+            KeyCode::PageUp => self.pan(0.0, 1.0),
+            KeyCode::PageDown => self.pan(0.0, -1.0),
+            KeyCode::Char('0') => self.reset_view(),
+            _ => {}
+        }
+        false
+    }
+}
+
+/// Restores the terminal even if drawing panics.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> anyhow::Result<Self> {
+        enable_raw_mode()?;
+        execute!(std::io::stdout(), EnterAlternateScreen)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(std::io::stdout(), LeaveAlternateScreen);
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("spectate=info"))
+        .init();
+
+    let args = Arguments::parse();
+    scenario::load_safe(&args.scenario).context("Unknown scenario")?;
+
+    log::info!("Compiling AIs");
+    let http = reqwest::Client::new();
+    let ais = oort_tools::fetch_and_compile_multiple(
+        &http,
+        &args.shortcodes,
+        args.dev,
+        args.wasm_cache.as_deref(),
+    )
+    .await?;
+    let codes: Vec<Code> = ais.iter().map(|ai| ai.compiled_code.clone()).collect();
+
+    let mut app = App::new(
+        &args.scenario,
+        Simulation::new(&args.scenario, args.seed, &codes),
+    );
+
+    let _guard = TerminalGuard::new()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(std::io::stdout()))?;
+    terminal.clear()?;
+
+    loop {
+        let frame_start = Instant::now();
+        terminal.draw(|f| draw(f, &mut app))?;
+        while let Some(timeout) = FRAME_TIME.checked_sub(frame_start.elapsed()) {
+            if !event::poll(timeout)? {
+                break;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && app.handle_key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+        app.update();
+    }
+}
+
+fn draw(f: &mut Frame, app: &mut App) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(10),
+            Constraint::Length(8),
+            Constraint::Length(1),
+        ])
+        .split(f.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(20), Constraint::Length(58)])
+        .split(rows[0]);
+
+    draw_world(f, app, columns[0]);
+    draw_ship_table(f, app, columns[1]);
+    draw_debug_text(f, app, rows[1]);
+    draw_status(f, app, rows[2]);
+}
+
+fn draw_world(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    // Terminal cells are roughly twice as tall as they are wide.
+    let cols = area.width.saturating_sub(2).max(1) as f64;
+    let rows = area.height.saturating_sub(2).max(1) as f64;
+    let view_height = app.view_width * rows * 2.0 / cols;
+    let (cx, cy) = app.center;
+    let world_size = app.snapshot.world_size;
+    let bullets: Vec<(f64, f64)> = app
+        .snapshot
+        .bullets
+        .iter()
+        .map(|b| (b.position.x, b.position.y))
+        .collect();
+
+    let canvas = Canvas::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {} — {:.0} m ", app.scenario_name, app.view_width)),
+        )
+        .marker(Marker::Braille)
+        .x_bounds([cx - app.view_width / 2.0, cx + app.view_width / 2.0])
+        .y_bounds([cy - view_height / 2.0, cy + view_height / 2.0])
+        .paint(|ctx| {
+            ctx.draw(&Rectangle {
+                x: -world_size / 2.0,
+                y: -world_size / 2.0,
+                width: world_size,
+                height: world_size,
+                color: Color::DarkGray,
+            });
+            ctx.draw(&Points {
+                coords: &bullets,
+                color: Color::Gray,
+            });
+            ctx.layer();
+            for ship in app.snapshot.ships.iter() {
+                let mut style = Style::default().fg(team_color(ship.team));
+                if Some(ship.id) == app.selected {
+                    style = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+                }
+                ctx.print(
+                    ship.position.x,
+                    ship.position.y,
+                    Span::styled(glyph(ship.class), style),
+                );
+            }
+        });
+    f.render_widget(canvas, area);
+}
+
+fn draw_ship_table(f: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    let rows: Vec<Row> = app
+        .snapshot
+        .ships
+        .iter()
+        .map(|ship| {
+            Row::new(vec![
+                format!("{}", ship.id),
+                ship.class.name().to_string(),
+                format!("{}", ship.team),
+                format!("{:.0}", ship.health),
+                format!("{:.0},{:.0}", ship.position.x, ship.position.y),
+            ])
+            .style(Style::default().fg(team_color(ship.team)))
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Length(4),
+            Constraint::Length(7),
+            Constraint::Min(14),
+        ],
+    )
+    .header(
+        Row::new(vec!["id", "class", "team", "health", "position"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Ships ({}) ", app.snapshot.ships.len())),
+    )
+    .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    f.render_stateful_widget(table, area, &mut app.table_state);
+}
+
+fn draw_debug_text(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let (title, text) = match app.selected_ship() {
+        Some(ship) => (
+            format!(
+                " Ship {} ({}) v=({:.0}, {:.0}) h={:.2} ",
+                ship.id,
+                ship.class.name(),
+                ship.velocity.x,
+                ship.velocity.y,
+                ship.heading
+            ),
+            app.snapshot
+                .debug_text
+                .get(&ship.id)
+                .cloned()
+                .unwrap_or_default(),
+        ),
+        None => (" No ship selected ".to_string(), String::new()),
+    };
+    let paragraph = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+fn draw_status(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let mut spans = vec![Span::raw(format!(
+        "tick {} ({:.2}s)  {}  speed {}x",
+        app.snapshot.tick, app.snapshot.time, app.snapshot.status, SPEEDS[app.speed_index]
+    ))];
+    if app.paused {
+        spans.push(Span::styled(
+            "  PAUSED",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    if app.follow {
+        spans.push(Span::raw("  following"));
+    }
+    if let Some(e) = app.snapshot.errors.first() {
+        spans.push(Span::styled(
+            format!("  error: {}", e.msg),
+            Style::default().fg(Color::Red),
+        ));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn team_color(team: i32) -> Color {
+    match team {
+        0 => Color::Yellow,
+        1 => Color::Magenta,
+        2 => Color::Blue,
+        9 => Color::DarkGray,
+        _ => Color::White,
+    }
+}
+
+fn glyph(class: ShipClass) -> &'static str {
+    match class {
+        ShipClass::Fighter => "f",
+        ShipClass::Frigate => "F",
+        ShipClass::Cruiser => "C",
+        ShipClass::Asteroid { .. } | ShipClass::BigAsteroid { .. } => "*",
+        ShipClass::Target => "x",
+        ShipClass::Missile => "m",
+        ShipClass::Torpedo => "t",
+        ShipClass::Planet => "O",
+        ShipClass::Beacon => "b",
+        ShipClass::Chaff => ":",
+        ShipClass::Flare => "'",
+        ShipClass::Mine => "+",
+    }
+}
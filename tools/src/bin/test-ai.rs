@@ -0,0 +1,67 @@
+use clap::Parser;
+use oort_simulator::harness;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[clap()]
+struct Arguments {
+    /// Shortcode or path to the AI source code.
+    shortcode: String,
+
+    /// Only run tests whose names contain this string.
+    filter: Option<String>,
+
+    #[clap(short, long)]
+    dev: bool,
+
+    #[clap(long, default_value = "/tmp/oort-wasm-cache")]
+    wasm_cache: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
+
+    let args = Arguments::parse();
+
+    let http = reqwest::Client::new();
+    let ai =
+        oort_tools::fetch_and_compile(&http, &args.shortcode, args.dev, args.wasm_cache.as_deref())
+            .await?;
+
+    let names: Vec<String> = harness::list(&ai.compiled_code)
+        .map_err(|e| anyhow::anyhow!(e.msg))?
+        .into_iter()
+        .filter(|name| {
+            args.filter
+                .as_ref()
+                .map(|filter| name.contains(filter))
+                .unwrap_or(true)
+        })
+        .collect();
+    if names.is_empty() {
+        println!("No tests found");
+        return Ok(());
+    }
+
+    let mut failures = 0;
+    for name in names.iter() {
+        let result = harness::run(&ai.compiled_code, name).map_err(|e| anyhow::anyhow!(e.msg))?;
+        if result.passed {
+            println!("PASS {} ({} ticks)", result.name, result.ticks);
+        } else {
+            failures += 1;
+            println!(
+                "FAIL {}: {}",
+                result.name,
+                result.message.as_deref().unwrap_or("unknown error")
+            );
+        }
+    }
+    println!("{}/{} tests passed", names.len() - failures, names.len());
+
+    if failures > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
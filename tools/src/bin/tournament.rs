@@ -15,7 +15,7 @@ use oort_tools::process_pool::ProcessPool;
 use rand::RngExt;
 
 use skillratings::{
-    glicko2::{glicko2, Glicko2Config, Glicko2Rating},
+    glicko2::{glicko2, glicko2_rating_period, Glicko2Config, Glicko2Rating},
     Outcomes,
 };
 use std::default::Default;
@@ -38,7 +38,7 @@ enum WorkerTask {
 #[derive(Serialize, Deserialize, Debug)]
 enum WorkerResponse {
     Registered,
-    SimulationResult(Result<Outcomes, String>),
+    SimulationResult(Result<Vec<usize>, String>),
     Error(String),
 }
 
@@ -63,7 +63,8 @@ struct SimulationResult {
     round: i32,
     ai_indices: Vec<usize>,
     seed: u32,
-    outcome: Result<Outcomes, String>,
+    /// Finishing position of each AI in `ai_indices`.
+    ranking: Result<Vec<usize>, String>,
 }
 
 fn get_code_hash(source_code: &str) -> String {
@@ -79,6 +80,27 @@ fn get_start_seed(hash0: &str, hash1: &str, secret_hash: &str) -> u32 {
     u32::from_be_bytes(result[0..4].try_into().unwrap())
 }
 
+/// Outcome of a two-player match from the first player's point of view.
+fn ranking_outcome(ranking: &[usize]) -> Outcomes {
+    match ranking[0].cmp(&ranking[1]) {
+        std::cmp::Ordering::Less => Outcomes::WIN,
+        std::cmp::Ordering::Greater => Outcomes::LOSS,
+        std::cmp::Ordering::Equal => Outcomes::DRAW,
+    }
+}
+
+fn outcome_ranking(outcome: Outcomes) -> Vec<usize> {
+    match outcome {
+        Outcomes::WIN => vec![0, 1],
+        Outcomes::LOSS => vec![1, 0],
+        Outcomes::DRAW => vec![0, 0],
+    }
+}
+
+fn num_teams(scenario_name: &str) -> usize {
+    scenario::load(scenario_name).initial_code().len()
+}
+
 fn hash_seed(seed: u32) -> u64 {
     let mut hasher = Sha256::new();
     hasher.update(seed.to_be_bytes());
@@ -137,7 +159,7 @@ fn get_pair_outcomes(
                 round,
                 ai_indices: vec![i, j],
                 seed: start_seed.wrapping_add(round as u32),
-                outcome: Ok(outcome),
+                ranking: Ok(outcome_ranking(outcome)),
             });
         }
     }
@@ -161,8 +183,8 @@ fn update_cache_from_simulations(
     for (idx, outcome) in sim_outcomes.iter().enumerate() {
         let (i, j, target_rounds) = sim_matchups_info[idx];
         let counts = pair_results.entry((i, j, target_rounds)).or_insert((0, 0, 0));
-        if let Ok(outcome_val) = &outcome.outcome {
-            match outcome_val {
+        if let Ok(ranking) = &outcome.ranking {
+            match ranking_outcome(ranking) {
                 Outcomes::WIN => counts.0 += 1,
                 Outcomes::LOSS => counts.1 += 1,
                 Outcomes::DRAW => counts.2 += 1,
@@ -237,7 +259,7 @@ fn run_simulations_parallel(
     let mut results = Vec::with_capacity(matchups.len());
     for (idx, response) in responses.into_iter().enumerate() {
         let (round, ai_indices, seed) = &matchups[idx];
-        let outcome_res = match response {
+        let ranking = match response {
             Ok(WorkerResponse::SimulationResult(res)) => res,
             Ok(WorkerResponse::Registered) => Err("Received unexpected Registered response".to_string()),
             Ok(WorkerResponse::Error(err)) => Err(err),
@@ -247,7 +269,7 @@ fn run_simulations_parallel(
             round: *round,
             ai_indices: ai_indices.clone(),
             seed: *seed,
-            outcome: outcome_res,
+            ranking,
         });
     }
     results
@@ -345,6 +367,7 @@ fn main() -> anyhow::Result<()> {
         Some(ProcessPool::new(move |req: WorkerTask| -> WorkerResponse {
             match req {
                 WorkerTask::RegisterAIs { ais } => {
+                    oort_simulator::vm::reserve_module_cache(ais.len());
                     registered_ais = ais;
                     WorkerResponse::Registered
                 }
@@ -551,11 +574,21 @@ async fn cmd_run(
     let secret_hash = get_code_hash(secret);
     log::info!("Running tournament");
     let original_cache = cache.clone();
-    let results = run_tournament(pool, scenario_name, &ais, rounds, Some(&mut cache), &ai_hashes, &secret_hash);
+    // The incremental cache only holds results of two-player matches.
+    let free_for_all = num_teams(scenario_name) > 2;
+    let results = run_tournament(
+        pool,
+        scenario_name,
+        &ais,
+        rounds,
+        if free_for_all { None } else { Some(&mut cache) },
+        &ai_hashes,
+        &secret_hash,
+    );
 
     display_results(&results);
 
-    let cache_changed = {
+    let cache_changed = free_for_all || {
         let mut original_entries = original_cache.entries.clone();
         original_entries.sort_by(|a, b| (&a.player0_hash, &a.player1_hash).cmp(&(&b.player0_hash, &b.player1_hash)));
         let mut new_entries = cache.entries.clone();
@@ -567,9 +600,11 @@ async fn cmd_run(
         if cache_changed {
             let url = upload_results(&db, project_id, &entrants, &results).await?;
             
-            log::info!("Writing updated incremental cache to Firestore");
-            db.update_obj::<_, (), _>("tournament_incremental_cache", cache_doc_id, &cache, None, None, None)
-                .await?;
+            if !free_for_all {
+                log::info!("Writing updated incremental cache to Firestore");
+                db.update_obj::<_, (), _>("tournament_incremental_cache", cache_doc_id, &cache, None, None, None)
+                    .await?;
+            }
 
             if let Some(webhook_url) = discord_webhook.filter(|url| !url.is_empty()) {
                 log::info!("Posting results link to Discord webhook");
@@ -597,6 +632,11 @@ fn run_tournament(
     ai_hashes: &[String],
     secret_hash: &str,
 ) -> TournamentResults {
+    let num_teams = num_teams(scenario_name);
+    if num_teams > 2 {
+        return run_free_for_all_tournament(pool, scenario_name, ais, rounds, num_teams, ai_hashes, secret_hash);
+    }
+
     let config = Glicko2Config::new();
     let mut pairings: HashMap<(String, String), f64> = HashMap::new();
     let mut ratings: Vec<Glicko2Rating> = Vec::new();
@@ -653,8 +693,8 @@ fn run_tournament(
 
     let mut crashes = Vec::new();
     for outcome in outcomes {
-        let outcome_val = match outcome.outcome {
-            Ok(outcome_val) => outcome_val,
+        let outcome_val = match outcome.ranking {
+            Ok(ranking) => ranking_outcome(&ranking),
             Err(_) => {
                 crashes.push(oort_proto::TournamentCrash {
                     seed: outcome.seed,
@@ -688,29 +728,160 @@ fn run_tournament(
         }
     }
 
-    let mut competitors: Vec<_> = ais
+    tournament_results(
+        scenario_name,
+        ais,
+        &ratings,
+        |i, j| {
+            pairings
+                .get(&(ais[i].name.clone(), ais[j].name.clone()))
+                .copied()
+                .unwrap_or_default()
+        },
+        crashes,
+    )
+}
+
+/// Runs a tournament for a scenario with more than two teams. Each round the
+/// entrants are shuffled into groups of `num_teams`. Every match counts as a
+/// Glicko-2 rating period in which each player is compared against the others
+/// by finishing position.
+fn run_free_for_all_tournament(
+    pool: &ProcessPool<WorkerTask, WorkerResponse>,
+    scenario_name: &str,
+    ais: &[AI],
+    rounds: i32,
+    num_teams: usize,
+    ai_hashes: &[String],
+    secret_hash: &str,
+) -> TournamentResults {
+    assert!(
+        ais.len() >= num_teams,
+        "{scenario_name} needs at least {num_teams} entrants"
+    );
+    let config = Glicko2Config::new();
+    let mut ratings: Vec<Glicko2Rating> = Vec::new();
+    ratings.resize_with(ais.len(), Default::default);
+
+    let start_seed = get_start_seed(&ai_hashes.join(":"), scenario_name, secret_hash);
+    let mut matchups = Vec::new();
+    for round in 0..rounds {
+        let round_seed = start_seed.wrapping_add(round as u32).to_string();
+        let mut order: Vec<usize> = (0..ais.len()).collect();
+        order.sort_by_key(|&i| get_start_seed(&ai_hashes[i], &round_seed, secret_hash));
+        for group in order.chunks(num_teams) {
+            // Entrants from earlier groups fill out the last one.
+            let mut group = group.to_vec();
+            let fill: Vec<usize> = order
+                .iter()
+                .copied()
+                .filter(|i| !group.contains(i))
+                .take(num_teams - group.len())
+                .collect();
+            group.extend(fill);
+            let seed = start_seed.wrapping_add(matchups.len() as u32);
+            matchups.push((round, group, seed));
+        }
+    }
+
+    let progress = indicatif::ProgressBar::new(matchups.len() as u64);
+    progress.set_style(
+        indicatif::ProgressStyle::default_bar()
+            .template("{wide_bar} {pos}/{len} Elapsed: {elapsed_precise} ETA: {eta_precise}")
+            .unwrap(),
+    );
+    let outcomes = run_simulations_parallel(pool, scenario_name, ais, matchups, &progress);
+    progress.finish_and_clear();
+
+    let mut wins: HashMap<(usize, usize), f64> = HashMap::new();
+    let mut games: HashMap<(usize, usize), f64> = HashMap::new();
+    let mut crashes = Vec::new();
+    for outcome in outcomes {
+        let players = &outcome.ai_indices;
+        let ranking = match outcome.ranking {
+            Ok(ranking) => ranking,
+            Err(_) => {
+                crashes.push(oort_proto::TournamentCrash {
+                    seed: outcome.seed,
+                    ais: players.iter().map(|&idx| ais[idx].name.clone()).collect(),
+                });
+                vec![0; players.len()]
+            }
+        };
+        log::debug!(
+            "{:?} seed {}: {:?}",
+            players.iter().map(|&idx| &ais[idx].name).collect::<Vec<_>>(),
+            outcome.seed,
+            ranking
+        );
+
+        let new_ratings: Vec<Glicko2Rating> = players
+            .iter()
+            .enumerate()
+            .map(|(a, &i)| {
+                let results: Vec<(Glicko2Rating, Outcomes)> = players
+                    .iter()
+                    .enumerate()
+                    .filter(|&(b, _)| b != a)
+                    .map(|(b, &j)| (ratings[j], ranking_outcome(&[ranking[a], ranking[b]])))
+                    .collect();
+                glicko2_rating_period(&ratings[i], &results, &config)
+            })
+            .collect();
+        for (&i, rating) in players.iter().zip(new_ratings) {
+            ratings[i] = rating;
+        }
+
+        for (a, &i) in players.iter().enumerate() {
+            for (b, &j) in players.iter().enumerate() {
+                if a == b {
+                    continue;
+                }
+                *games.entry((i, j)).or_default() += 1.0;
+                if ranking[a] < ranking[b] {
+                    *wins.entry((i, j)).or_default() += 1.0;
+                }
+            }
+        }
+    }
+
+    tournament_results(
+        scenario_name,
+        ais,
+        &ratings,
+        |i, j| match games.get(&(i, j)) {
+            Some(n) => wins.get(&(i, j)).copied().unwrap_or_default() / n,
+            None => 0.0,
+        },
+        crashes,
+    )
+}
+
+/// Sorts the competitors by rating. `win_fraction(i, j)` is the fraction of
+/// matches in which AI `i` finished ahead of AI `j`.
+fn tournament_results(
+    scenario_name: &str,
+    ais: &[AI],
+    ratings: &[Glicko2Rating],
+    win_fraction: impl Fn(usize, usize) -> f64,
+    crashes: Vec<oort_proto::TournamentCrash>,
+) -> TournamentResults {
+    let mut order: Vec<usize> = (0..ais.len()).collect();
+    order.sort_by_key(|&i| (-ratings[i].rating * 1e6) as i64);
+
+    let competitors = order
         .iter()
-        .enumerate()
-        .map(|(i, x)| TournamentCompetitor {
-            username: x.name.clone(),
+        .map(|&i| TournamentCompetitor {
+            username: ais[i].name.clone(),
             shortcode: "".to_string(),
             rating: ratings[i].rating,
         })
         .collect();
-    competitors.sort_by_key(|c| (-c.rating * 1e6) as i64);
 
     let mut win_matrix: Vec<f64> = vec![];
-    for competitor in &competitors {
-        for other_competitor in &competitors {
-            win_matrix.push(
-                pairings
-                    .get(&(
-                        competitor.username.clone(),
-                        other_competitor.username.clone(),
-                    ))
-                    .copied()
-                    .unwrap_or_default(),
-            );
+    for &i in &order {
+        for &j in &order {
+            win_matrix.push(win_fraction(i, j));
         }
     }
 
@@ -722,19 +893,14 @@ fn run_tournament(
     }
 }
 
-fn run_simulation(scenario_name: &str, seed: u32, ais: &[&AI]) -> Result<Outcomes, String> {
+fn run_simulation(scenario_name: &str, seed: u32, ais: &[&AI]) -> Result<Vec<usize>, String> {
     let f = move || {
         let codes: Vec<_> = ais.iter().map(|x| x.compiled_code.clone()).collect();
         let mut sim = simulation::Simulation::new(scenario_name, seed, &codes);
         while sim.status() == scenario::Status::Running && sim.tick() < scenario::MAX_TICKS {
             sim.step();
         }
-        match sim.status() {
-            scenario::Status::Victory { team: 0 } => Outcomes::WIN,
-            scenario::Status::Victory { team: 1 } => Outcomes::LOSS,
-            scenario::Status::Draw => Outcomes::DRAW,
-            _ => unreachable!(),
-        }
+        sim.ranking()
     };
     match ::std::panic::catch_unwind(f) {
         Ok(x) => Ok(x),
@@ -937,7 +1103,15 @@ async fn cmd_write(
 mod tests {
     use super::*;
 
-    fn mock_run_simulation(_scenario_name: &str, _seed: u32, ais: &[&AI]) -> Result<Outcomes, String> {
+    fn mock_run_simulation(_scenario_name: &str, _seed: u32, ais: &[&AI]) -> Result<Vec<usize>, String> {
+        if ais.len() > 2 {
+            // Free-for-all: finish in name order.
+            return Ok(ais
+                .iter()
+                .map(|ai| ais.iter().filter(|other| other.name < ai.name).count())
+                .collect());
+        }
+
         let name0 = &ais[0].name;
         let name1 = &ais[1].name;
 
@@ -954,7 +1128,7 @@ mod tests {
         } else {
             Outcomes::LOSS
         };
-        Ok(outcome)
+        Ok(outcome_ranking(outcome))
     }
 
 
@@ -1070,6 +1244,24 @@ mod tests {
         }
     }
 
+    fn test_free_for_all_tournament(pool: &ProcessPool<WorkerTask, WorkerResponse>, ais: &[AI]) {
+        let ai_hashes: Vec<String> = ais.iter().map(|ai| get_code_hash(&ai.source_code)).collect();
+        let secret_hash = get_code_hash("");
+        let results = run_tournament(pool, "free_for_all", ais, 10, None, &ai_hashes, &secret_hash);
+
+        assert_eq!(results.competitors.len(), 6);
+        assert_eq!(results.win_matrix.len(), 36);
+        assert!(results.crashes.is_empty());
+
+        let ranking: Vec<String> = results.competitors.iter().map(|c| c.username.clone()).collect();
+        assert_eq!(ranking.first().unwrap(), "bot0");
+        assert_eq!(ranking.last().unwrap(), "bot5");
+
+        // bot0 finishes ahead of bot5 in every match they share.
+        assert!((results.win_matrix[5] - 1.0).abs() < 1e-9);
+        assert_eq!(results.win_matrix[5 * 6], 0.0);
+    }
+
     pub fn run_all_tests() -> anyhow::Result<()> {
         let names = vec![
             "bot0".to_string(),
@@ -1117,6 +1309,10 @@ mod tests {
         test_cache_consistency(&pool, &ais);
         println!("test_cache_consistency passed.");
 
+        println!("Running test_free_for_all_tournament...");
+        test_free_for_all_tournament(&pool, &ais);
+        println!("test_free_for_all_tournament passed.");
+
         Ok(())
     }
 }